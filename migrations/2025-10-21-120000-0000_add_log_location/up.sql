@@ -0,0 +1,13 @@
+-- Store log positions as a PostGIS geography point alongside the legacy float columns
+CREATE EXTENSION IF NOT EXISTS postgis;
+
+ALTER TABLE logs ADD COLUMN location geography(Point, 4326);
+
+-- Existing rows were written with latitude in xcoord and longitude in ycoord.
+-- Rows that are out of range in that order are left NULL for repair.
+UPDATE logs
+SET location = ST_SetSRID(ST_MakePoint(ycoord, xcoord), 4326)::geography
+WHERE xcoord BETWEEN -90 AND 90
+  AND ycoord BETWEEN -180 AND 180;
+
+CREATE INDEX logs_location_idx ON logs USING GIST (location);
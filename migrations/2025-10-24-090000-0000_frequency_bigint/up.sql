@@ -0,0 +1,9 @@
+-- Store frequency as integer Hz so GHz signals keep 1 Hz resolution.
+-- The Create Log form used to take MHz, so values below 100000 are
+-- assumed to be MHz (anything up to 100 GHz) and everything else Hz.
+ALTER TABLE logs
+    ALTER COLUMN frequency TYPE BIGINT
+    USING CASE
+        WHEN frequency < 100000 THEN round(frequency::numeric * 1000000)::bigint
+        ELSE round(frequency::numeric)::bigint
+    END;
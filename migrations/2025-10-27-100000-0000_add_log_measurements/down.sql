@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE logs
+    DROP COLUMN power_dbm,
+    DROP COLUMN snr_db,
+    DROP COLUMN bandwidth_hz,
+    DROP COLUMN altitude_m,
+    DROP COLUMN position_accuracy_m,
+    DROP COLUMN heading_deg,
+    DROP COLUMN speed_mps;
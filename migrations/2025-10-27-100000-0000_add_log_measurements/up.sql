@@ -0,0 +1,9 @@
+-- Signal strength and GPS fix details recorded with each log
+ALTER TABLE logs
+    ADD COLUMN power_dbm REAL,
+    ADD COLUMN snr_db REAL,
+    ADD COLUMN bandwidth_hz BIGINT,
+    ADD COLUMN altitude_m REAL,
+    ADD COLUMN position_accuracy_m REAL,
+    ADD COLUMN heading_deg REAL,
+    ADD COLUMN speed_mps REAL;
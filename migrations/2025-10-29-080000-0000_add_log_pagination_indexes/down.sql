@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS logs_frequency_idx;
+DROP INDEX IF EXISTS logs_timestamp_id_idx;
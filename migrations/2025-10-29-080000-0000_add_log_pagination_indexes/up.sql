@@ -0,0 +1,3 @@
+-- Support keyset pagination over (timestamp, id), newest first, and the common filters
+CREATE INDEX logs_timestamp_id_idx ON logs ("timestamp" DESC, id DESC);
+CREATE INDEX logs_frequency_idx ON logs (frequency);
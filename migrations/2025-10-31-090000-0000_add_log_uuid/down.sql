@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS logs_uuid_idx;
+ALTER TABLE logs DROP COLUMN uuid;
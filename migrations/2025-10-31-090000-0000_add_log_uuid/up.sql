@@ -0,0 +1,4 @@
+-- Client-generated identity, so entries replayed from an offline capture queue are
+-- inserted at most once. Older rows keep NULL.
+ALTER TABLE logs ADD COLUMN uuid UUID;
+CREATE UNIQUE INDEX logs_uuid_idx ON logs (uuid);
@@ -0,0 +1,21 @@
+-- Give rows written before logs carried a uuid a version 7 one built from their own
+-- timestamp, so merged databases still sort by capture time, then make it required.
+-- Setting bits 52 and 53 turns the version nibble of gen_random_uuid() from 4 into 7.
+UPDATE logs
+SET uuid = encode(
+    set_bit(
+        set_bit(
+            overlay(
+                uuid_send(gen_random_uuid())
+                PLACING substring(int8send(floor(extract(epoch FROM "timestamp") * 1000)::bigint) FROM 3)
+                FROM 1 FOR 6
+            ),
+            52, 1
+        ),
+        53, 1
+    ),
+    'hex'
+)::uuid
+WHERE uuid IS NULL;
+
+ALTER TABLE logs ALTER COLUMN uuid SET NOT NULL;
@@ -0,0 +1,16 @@
+-- Soft delete: rows with deleted_at set are hidden from queries but kept for their history
+ALTER TABLE logs ADD COLUMN deleted_at TIMESTAMP;
+
+-- One row per update, delete or restore of a log, with JSON snapshots of the row
+-- before and after. new_values is NULL for deletes.
+CREATE TABLE log_revisions (
+    id SERIAL PRIMARY KEY,
+    log_id INTEGER NOT NULL REFERENCES logs (id),
+    action VARCHAR(20) NOT NULL,
+    old_values JSONB NOT NULL,
+    new_values JSONB,
+    operator VARCHAR(50) NOT NULL,
+    changed_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX log_revisions_log_id_idx ON log_revisions (log_id, id);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS logs_session_id_idx;
+ALTER TABLE logs DROP COLUMN session_id;
+DROP TABLE sessions;
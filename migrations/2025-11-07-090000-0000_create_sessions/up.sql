@@ -0,0 +1,17 @@
+-- A survey outing, e.g. one drive test or hilltop session, that logs are grouped under
+CREATE TABLE sessions (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    operator VARCHAR(50) NOT NULL,
+    started_at TIMESTAMP NOT NULL DEFAULT now(),
+    -- NULL while the session is running
+    ended_at TIMESTAMP,
+    notes TEXT,
+    equipment VARCHAR(200)
+);
+
+CREATE INDEX sessions_operator_active_idx ON sessions (operator, started_at DESC)
+    WHERE ended_at IS NULL;
+
+ALTER TABLE logs ADD COLUMN session_id INTEGER REFERENCES sessions (id);
+CREATE INDEX logs_session_id_idx ON logs (session_id);
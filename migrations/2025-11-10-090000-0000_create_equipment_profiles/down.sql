@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS logs_equipment_profile_id_idx;
+ALTER TABLE logs DROP COLUMN equipment_profile_id;
+DROP TABLE equipment_profiles;
@@ -0,0 +1,17 @@
+-- Receive chain a measurement was taken with, so levels from different setups can be compared
+CREATE TABLE equipment_profiles (
+    id SERIAL PRIMARY KEY,
+    sdr_model VARCHAR(100) NOT NULL,
+    serial VARCHAR(100),
+    antenna VARCHAR(200),
+    cable_loss_db REAL NOT NULL DEFAULT 0,
+    preamp BOOLEAN NOT NULL DEFAULT false,
+    lna_gain_db INTEGER NOT NULL DEFAULT 0,
+    vga_gain_db INTEGER NOT NULL DEFAULT 0,
+    -- Frequency error of the receiver's reference oscillator
+    calibration_offset_ppm REAL NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+ALTER TABLE logs ADD COLUMN equipment_profile_id INTEGER REFERENCES equipment_profiles (id);
+CREATE INDEX logs_equipment_profile_id_idx ON logs (equipment_profile_id);
@@ -0,0 +1,15 @@
+-- Free-form labels for logs, e.g. interference, repeater, weak, needs-id
+CREATE TABLE tags (
+    id SERIAL PRIMARY KEY,
+    -- Stored trimmed and lowercase
+    name VARCHAR(50) NOT NULL UNIQUE
+);
+
+CREATE TABLE log_tags (
+    log_id INTEGER NOT NULL REFERENCES logs (id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags (id) ON DELETE CASCADE,
+    PRIMARY KEY (log_id, tag_id)
+);
+
+-- Find the logs carrying a tag
+CREATE INDEX log_tags_tag_id_idx ON log_tags (tag_id);
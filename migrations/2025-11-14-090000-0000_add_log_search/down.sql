@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS logs_search_vector_idx;
+ALTER TABLE logs DROP COLUMN search_vector;
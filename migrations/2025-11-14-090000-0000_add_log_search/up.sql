@@ -0,0 +1,7 @@
+-- Full-text search over callsign and comment; callsign matches rank above comment ones
+ALTER TABLE logs ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(callsign, '')), 'A')
+        || setweight(to_tsvector('english', coalesce(comment, '')), 'B')
+) STORED;
+
+CREATE INDEX logs_search_vector_idx ON logs USING GIN (search_vector);
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE logs ALTER COLUMN "timestamp" TYPE TIMESTAMP USING "timestamp" AT TIME ZONE 'UTC';
@@ -0,0 +1,2 @@
+-- Log times have always been written in UTC; store them as absolute instants
+ALTER TABLE logs ALTER COLUMN "timestamp" TYPE TIMESTAMPTZ USING "timestamp" AT TIME ZONE 'UTC';
@@ -0,0 +1,126 @@
+//! Storage backends for logs
+//!
+//! [`LogStore`] abstracts over where logs live so the TUI and tools can run against a
+//! central Postgres server, an embedded sled database on a field laptop, or memory in
+//! tests.
+use std::path::PathBuf;
+
+use crate::db::filter::{LogCursor, LogFilter, LogPage};
+use crate::db::memory_store::MemoryLogStore;
+use crate::db::postgres_store::PgLogStore;
+use crate::db::queued_store::QueuedLogStore;
+use crate::db::search::SearchHit;
+use crate::db::sled_store::SledLogStore;
+use crate::db::sync::SyncStatus;
+use crate::error::DatabaseError;
+use crate::model::{Log, LogChanges, LogUuid, NewEquipmentProfile, NewLog, Session};
+
+/// Iterator over every stored log, as returned by [`LogStore::stream_all`]
+pub type LogStream<'a> = Box<dyn Iterator<Item = Result<Log, DatabaseError>> + 'a>;
+
+/// A place logs can be written to and read back from
+///
+/// Every implementation validates entries and changes with the same rules as
+/// [`NewLog::new`], and pages with the same newest-first `(timestamp, id)` ordering.
+/// Stores are `Send` so a [`DbWorker`](crate::db::worker::DbWorker) can own one.
+pub trait LogStore: Send {
+    /// Validate and store a new entry, returning it with its assigned id
+    fn create(&mut self, new_log: &NewLog) -> Result<Log, DatabaseError>;
+
+    /// Store a new entry along with the receive chain it was measured with
+    ///
+    /// Stores without equipment profiles keep only the entry.
+    fn create_with_equipment(
+        &mut self,
+        new_log: &NewLog,
+        equipment: &NewEquipmentProfile,
+    ) -> Result<Log, DatabaseError> {
+        equipment.validate()?;
+        self.create(new_log)
+    }
+
+    /// Get a single log by id
+    fn get(&mut self, id: i32) -> Result<Log, DatabaseError>;
+
+    /// The stored logs carrying any of `uuids`, in no particular order
+    ///
+    /// Looks up only the given uuids, so callers matching a batch of incoming logs
+    /// need not read the whole store.
+    fn get_by_uuids(&mut self, uuids: &[LogUuid]) -> Result<Vec<Log>, DatabaseError>;
+
+    /// Fetch up to `limit` logs matching `filter`, starting after `after`
+    fn query(
+        &mut self,
+        filter: &LogFilter,
+        after: Option<LogCursor>,
+        limit: i64,
+    ) -> Result<LogPage, DatabaseError>;
+
+    /// Apply `changes` to a log, returning the updated row
+    fn update(&mut self, id: i32, changes: &LogChanges) -> Result<Log, DatabaseError>;
+
+    /// Apply each of `updates` in turn, all or none of them, returning the rows as
+    /// each change left them
+    fn update_many(&mut self, updates: &[(i32, LogChanges)]) -> Result<Vec<Log>, DatabaseError>;
+
+    /// Delete a log by id
+    ///
+    /// Postgres keeps the row and its history, see [`delete_log`](crate::db::logs::delete_log);
+    /// the other stores remove it.
+    fn delete(&mut self, id: i32) -> Result<(), DatabaseError>;
+
+    /// Up to `limit` logs whose callsign or comment match `query`, best match first
+    ///
+    /// See [`search`](crate::db::search) for how each store matches and ranks.
+    fn search(&mut self, query: &str, limit: i64) -> Result<Vec<SearchHit>, DatabaseError>;
+
+    /// Every stored log in ascending id order, read incrementally
+    fn stream_all(&mut self) -> Result<LogStream<'_>, DatabaseError>;
+
+    /// State of background syncing, for stores that write through a local queue
+    fn sync_status(&self) -> Option<SyncStatus> {
+        None
+    }
+
+    /// The running survey session new logs should be attached to, for stores that
+    /// keep sessions
+    fn active_session(&mut self) -> Result<Option<Session>, DatabaseError> {
+        Ok(None)
+    }
+
+    /// The sessions with any of `ids`, e.g. to name them in an export, for stores that
+    /// keep sessions
+    fn sessions(&mut self, _ids: &[i32]) -> Result<Vec<Session>, DatabaseError> {
+        Ok(Vec::new())
+    }
+}
+
+/// Which backend to open and where it lives
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoreConfig {
+    Postgres {
+        database_url: String,
+    },
+    Sled {
+        path: PathBuf,
+    },
+    /// Queue new logs in sled at `queue_path` and sync them to Postgres
+    Queued {
+        database_url: String,
+        queue_path: PathBuf,
+    },
+    Memory,
+}
+
+/// Open the backend described by `config`
+pub fn open_store(config: &StoreConfig) -> Result<Box<dyn LogStore>, DatabaseError> {
+    Ok(match config {
+        StoreConfig::Postgres { database_url } => Box::new(PgLogStore::connect(database_url)?),
+        StoreConfig::Sled { path } => Box::new(SledLogStore::open(path)?),
+        StoreConfig::Queued {
+            database_url,
+            queue_path,
+        } => Box::new(QueuedLogStore::open(database_url, queue_path)?),
+        StoreConfig::Memory => Box::new(MemoryLogStore::new()),
+    })
+}
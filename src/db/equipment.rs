@@ -0,0 +1,91 @@
+//! Receive chain profiles, kept in the `equipment_profiles` table
+use diesel::PgConnection;
+use diesel::prelude::*;
+
+use crate::db::logs::insert_row;
+use crate::error::DatabaseError;
+use crate::model::{EquipmentProfile, Log, NewEquipmentProfile, NewLog};
+use crate::schema::equipment_profiles;
+
+/// Store a profile, returning it with its assigned id
+pub fn create_equipment_profile(
+    conn: &mut PgConnection,
+    profile: &NewEquipmentProfile,
+) -> Result<EquipmentProfile, DatabaseError> {
+    profile.validate()?;
+    Ok(diesel::insert_into(equipment_profiles::table)
+        .values(profile)
+        .returning(EquipmentProfile::as_returning())
+        .get_result(conn)?)
+}
+
+/// The stored profile with exactly these settings, created if there is none
+///
+/// Logs taken with an unchanged receive chain share one profile instead of each
+/// storing its own copy.
+pub fn find_or_create_equipment_profile(
+    conn: &mut PgConnection,
+    profile: &NewEquipmentProfile,
+) -> Result<EquipmentProfile, DatabaseError> {
+    use crate::schema::equipment_profiles::dsl::*;
+
+    profile.validate()?;
+    conn.transaction(|conn| {
+        let existing = equipment_profiles
+            .filter(sdr_model.eq(&profile.sdr_model))
+            .filter(serial.is_not_distinct_from(&profile.serial))
+            .filter(antenna.is_not_distinct_from(&profile.antenna))
+            .filter(cable_loss_db.eq(profile.cable_loss_db))
+            .filter(preamp.eq(profile.preamp))
+            .filter(lna_gain_db.eq(profile.lna_gain_db))
+            .filter(vga_gain_db.eq(profile.vga_gain_db))
+            .filter(calibration_offset_ppm.eq(profile.calibration_offset_ppm))
+            .order(id)
+            .select(EquipmentProfile::as_select())
+            .first(conn)
+            .optional()?;
+        match existing {
+            Some(found) => Ok(found),
+            None => create_equipment_profile(conn, profile),
+        }
+    })
+}
+
+/// Get a single profile by id
+pub fn get_equipment_profile(
+    conn: &mut PgConnection,
+    profile_id: i32,
+) -> Result<EquipmentProfile, DatabaseError> {
+    equipment_profiles::table
+        .find(profile_id)
+        .select(EquipmentProfile::as_select())
+        .first(conn)
+        .optional()?
+        .ok_or_else(|| DatabaseError::NotFound(format!("equipment profile {}", profile_id)))
+}
+
+/// Every profile, oldest first
+pub fn list_equipment_profiles(
+    conn: &mut PgConnection,
+) -> Result<Vec<EquipmentProfile>, DatabaseError> {
+    Ok(equipment_profiles::table
+        .order(equipment_profiles::id)
+        .select(EquipmentProfile::as_select())
+        .load(conn)?)
+}
+
+/// Insert an entry together with the receive chain it was measured with
+///
+/// The profile is looked up or created in the same transaction as the log, so a
+/// rejected log leaves no orphaned profile behind.
+pub fn insert_log_with_equipment(
+    conn: &mut PgConnection,
+    new_log: &NewLog,
+    equipment: &NewEquipmentProfile,
+) -> Result<Log, DatabaseError> {
+    conn.transaction(|conn| {
+        let profile = find_or_create_equipment_profile(conn, equipment)?;
+        let new_log = new_log.clone().with_equipment_profile(Some(profile.id));
+        insert_row(conn, &new_log)
+    })
+}
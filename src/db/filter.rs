@@ -0,0 +1,499 @@
+//! Composable log queries with keyset pagination
+//!
+//! A [`LogFilter`] compiles to a single boxed Diesel query. Pages are ordered newest
+//! first by `(timestamp, id)` and continue from a [`LogCursor`] rather than an OFFSET,
+//! so deep pages cost the same as the first.
+use std::cmp::Reverse;
+
+use chrono::{DateTime, Utc};
+use diesel::dsl::{exists, sql};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::sql_types::{Bool, Integer, Timestamptz};
+
+use crate::error::{DatabaseError, Error, ValidationError};
+use crate::model::tag::normalize_tag;
+use crate::model::{Frequency, FrequencyUnit, Log, LogTimeZone, SignalMode, parse_timestamp};
+use crate::schema::{log_tags, logs, tags};
+use crate::spatial::{BoundingBox, Coordinate, DbPoint};
+
+/// Keys understood by [`LogFilter::term`]
+pub const FILTER_KEYS: [&str; 10] = [
+    "mode", "callsign", "comment", "tag", "session", "since", "until", "freq", "near", "bbox",
+];
+
+/// Criteria for selecting logs; every criterion that is set must match
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogFilter {
+    min_frequency: Option<Frequency>,
+    max_frequency: Option<Frequency>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    modes: Vec<SignalMode>,
+    callsign_pattern: Option<String>,
+    comment_text: Option<String>,
+    bbox: Option<BoundingBox>,
+    radius: Option<(Coordinate, f64)>,
+    session: Option<i32>,
+    tags: Vec<String>,
+}
+
+impl LogFilter {
+    /// A filter that matches every log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frequencies at or above `min`
+    pub fn min_frequency(mut self, min: Frequency) -> Self {
+        self.min_frequency = Some(min);
+        self
+    }
+
+    /// Frequencies at or below `max`
+    pub fn max_frequency(mut self, max: Frequency) -> Self {
+        self.max_frequency = Some(max);
+        self
+    }
+
+    /// Frequencies in `[min, max]`
+    pub fn frequency_range(self, min: Frequency, max: Frequency) -> Self {
+        self.min_frequency(min).max_frequency(max)
+    }
+
+    /// Logs recorded at or after `start`
+    pub fn since(mut self, start: DateTime<Utc>) -> Self {
+        self.since = Some(start);
+        self
+    }
+
+    /// Logs recorded before `end`
+    pub fn until(mut self, end: DateTime<Utc>) -> Self {
+        self.until = Some(end);
+        self
+    }
+
+    /// Logs recorded in `[start, end)`
+    pub fn time_window(self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.since(start).until(end)
+    }
+
+    /// Add a mode to the set of accepted modes
+    pub fn mode(mut self, mode: SignalMode) -> Self {
+        if !self.modes.contains(&mode) {
+            self.modes.push(mode);
+        }
+        self
+    }
+
+    /// Add several modes to the set of accepted modes
+    pub fn modes(self, modes: impl IntoIterator<Item = SignalMode>) -> Self {
+        modes.into_iter().fold(self, Self::mode)
+    }
+
+    /// Callsigns matching a case-insensitive glob, where `*` matches any run of
+    /// characters and `?` a single character, e.g. `W1*` or `K?ABC`
+    pub fn callsign_like(mut self, pattern: impl Into<String>) -> Self {
+        self.callsign_pattern = Some(pattern.into());
+        self
+    }
+
+    /// Comments containing `text`, ignoring case
+    pub fn comment_contains(mut self, text: impl Into<String>) -> Self {
+        self.comment_text = Some(text.into());
+        self
+    }
+
+    /// Positions inside `bbox`
+    pub fn within_bbox(mut self, bbox: BoundingBox) -> Self {
+        self.bbox = Some(bbox);
+        self
+    }
+
+    /// Positions within `radius_m` metres of `center`
+    pub fn within_radius(mut self, center: Coordinate, radius_m: f64) -> Self {
+        self.radius = Some((center, radius_m));
+        self
+    }
+
+    /// Logs recorded in the session with id `session_id`
+    pub fn session(mut self, session_id: i32) -> Self {
+        self.session = Some(session_id);
+        self
+    }
+
+    /// Logs carrying `tag`, on top of any tags already required
+    ///
+    /// Tags are compared in their normalized form; one that can never be valid matches
+    /// no log.
+    pub fn tag(mut self, tag: &str) -> Self {
+        let tag = normalize_tag(tag).unwrap_or_else(|_| tag.to_string());
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
+
+    /// Logs carrying every one of `tags`
+    pub fn tags<S: AsRef<str>>(self, tags: impl IntoIterator<Item = S>) -> Self {
+        tags.into_iter()
+            .fold(self, |filter, tag| filter.tag(tag.as_ref()))
+    }
+
+    /// Stop requiring `tag`, matched the way [`LogFilter::tag`] stored it
+    pub fn without_tag(mut self, tag: &str) -> Self {
+        let tag = normalize_tag(tag).unwrap_or_else(|_| tag.to_string());
+        self.tags.retain(|required| *required != tag);
+        self
+    }
+
+    /// Tags a log must carry, in the order they were added
+    pub fn required_tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Add the criterion written as `key=value`, e.g. on the command line
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | `mode` | comma-separated modes, e.g. `FM,NFM` |
+    /// | `callsign` | glob as for [`LogFilter::callsign_like`] |
+    /// | `comment` | text the comment contains |
+    /// | `tag` | a tag the log carries |
+    /// | `session` | session id |
+    /// | `since`, `until` | a time as for [`parse_timestamp`], read in `zone` |
+    /// | `freq` | `min..max` with either end optional, bare numbers in MHz |
+    /// | `near` | `lat,lon,radius_km` |
+    /// | `bbox` | `min_lat,min_lon,max_lat,max_lon` |
+    ///
+    /// # Errors
+    /// Returns `ValidationError::InvalidFilter` for an unknown key or a malformed value,
+    /// or the error from parsing a frequency, time or coordinate
+    pub fn term(self, term: &str, zone: LogTimeZone) -> Result<Self, ValidationError> {
+        let invalid = || ValidationError::InvalidFilter(term.to_string());
+        let (key, value) = term.split_once('=').ok_or_else(invalid)?;
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(invalid());
+        }
+        let numbers = |count: usize| -> Result<Vec<f64>, ValidationError> {
+            let numbers = value
+                .split(',')
+                .map(|n| n.trim().parse::<f64>().map_err(|_| invalid()))
+                .collect::<Result<Vec<_>, _>>()?;
+            if numbers.len() == count {
+                Ok(numbers)
+            } else {
+                Err(invalid())
+            }
+        };
+
+        Ok(match key.trim().to_ascii_lowercase().as_str() {
+            "mode" => {
+                let modes = value
+                    .split(',')
+                    .map(str::parse::<SignalMode>)
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.modes(modes)
+            }
+            "callsign" => self.callsign_like(value),
+            "comment" => self.comment_contains(value),
+            "tag" => self.tag(value),
+            "session" => self.session(value.parse().map_err(|_| invalid())?),
+            "since" => self.since(parse_timestamp(value, zone)?),
+            "until" => self.until(parse_timestamp(value, zone)?),
+            "freq" => {
+                let (min, max) = value.split_once("..").ok_or_else(invalid)?;
+                let parse = |bound: &str| match bound.trim() {
+                    "" => Ok(None),
+                    bound => {
+                        Frequency::parse_with_default_unit(bound, FrequencyUnit::MHz).map(Some)
+                    }
+                };
+                let mut filter = self;
+                if let Some(min) = parse(min)? {
+                    filter = filter.min_frequency(min);
+                }
+                if let Some(max) = parse(max)? {
+                    filter = filter.max_frequency(max);
+                }
+                filter
+            }
+            "near" => {
+                let numbers = numbers(3)?;
+                self.within_radius(
+                    Coordinate::checked(numbers[0], numbers[1])?,
+                    numbers[2] * 1000.0,
+                )
+            }
+            "bbox" => {
+                let numbers = numbers(4)?;
+                self.within_bbox(BoundingBox::checked(
+                    Coordinate::checked(numbers[0], numbers[1])?,
+                    Coordinate::checked(numbers[2], numbers[3])?,
+                )?)
+            }
+            _ => return Err(invalid()),
+        })
+    }
+
+    /// Add every `key=value` criterion in `terms`, see [`LogFilter::term`]
+    pub fn terms<S: AsRef<str>>(
+        self,
+        terms: impl IntoIterator<Item = S>,
+        zone: LogTimeZone,
+    ) -> Result<Self, ValidationError> {
+        terms
+            .into_iter()
+            .try_fold(self, |filter, term| filter.term(term.as_ref(), zone))
+    }
+
+    /// Whether no criterion is set
+    pub fn is_empty(&self) -> bool {
+        *self == LogFilter::default()
+    }
+
+    /// Compile the filter to a query over undeleted `logs`, without ordering or limit
+    pub fn to_query(&self) -> logs::BoxedQuery<'static, Pg> {
+        use crate::schema::logs::dsl::*;
+        use crate::spatial::postgis::st_dwithin;
+
+        let mut query = logs.filter(deleted_at.is_null()).into_boxed();
+        if let Some(min) = self.min_frequency {
+            query = query.filter(frequency.ge(min));
+        }
+        if let Some(max) = self.max_frequency {
+            query = query.filter(frequency.le(max));
+        }
+        if let Some(start) = self.since {
+            query = query.filter(timestamp.ge(start));
+        }
+        if let Some(end) = self.until {
+            query = query.filter(timestamp.lt(end));
+        }
+        if !self.modes.is_empty() {
+            query = query.filter(mode.eq_any(self.modes.clone()));
+        }
+        if let Some(pattern) = &self.callsign_pattern {
+            query = query.filter(callsign.ilike(glob_to_like(pattern)));
+        }
+        if let Some(text) = &self.comment_text {
+            query = query.filter(comment.ilike(format!("%{}%", escape_like(text))));
+        }
+        if let Some(bbox) = &self.bbox {
+            query = query.filter(
+                xcoord
+                    .between(bbox.min().lat() as f32, bbox.max().lat() as f32)
+                    .and(ycoord.between(bbox.min().lon() as f32, bbox.max().lon() as f32)),
+            );
+        }
+        if let Some((center, radius_m)) = self.radius {
+            query = query.filter(st_dwithin(
+                location.assume_not_null(),
+                DbPoint::from(center),
+                radius_m,
+            ));
+        }
+        if let Some(session) = self.session {
+            query = query.filter(session_id.eq(session));
+        }
+        for tag in &self.tags {
+            query = query.filter(exists(
+                log_tags::table
+                    .inner_join(tags::table)
+                    .filter(log_tags::log_id.eq(id))
+                    .filter(tags::name.eq(tag.clone())),
+            ));
+        }
+        query
+    }
+
+    /// Evaluate the filter against a log already in memory
+    ///
+    /// Agrees with [`LogFilter::to_query`], except that radius distances are
+    /// spherical rather than measured on the WGS84 spheroid.
+    pub fn matches(&self, log: &Log) -> bool {
+        let in_frequency_range = self.min_frequency.is_none_or(|min| log.frequency >= min)
+            && self.max_frequency.is_none_or(|max| log.frequency <= max);
+        let in_time_window = self.since.is_none_or(|start| log.timestamp >= start)
+            && self.until.is_none_or(|end| log.timestamp < end);
+        let mode_accepted = self.modes.is_empty() || self.modes.contains(&log.mode);
+        let callsign_matches = self.callsign_pattern.as_ref().is_none_or(|pattern| {
+            log.callsign
+                .as_deref()
+                .is_some_and(|callsign| glob_matches(pattern, callsign))
+        });
+        let comment_matches = self.comment_text.as_ref().is_none_or(|text| {
+            log.comment
+                .as_deref()
+                .is_some_and(|comment| comment.to_lowercase().contains(&text.to_lowercase()))
+        });
+        let in_bbox = self.bbox.as_ref().is_none_or(|bbox| {
+            (bbox.min().lat() as f32..=bbox.max().lat() as f32).contains(&log.xcoord)
+                && (bbox.min().lon() as f32..=bbox.max().lon() as f32).contains(&log.ycoord)
+        });
+        let in_radius = self.radius.is_none_or(|(center, radius_m)| {
+            log.location
+                .and_then(|point| Coordinate::try_from(point).ok())
+                .is_some_and(|position| position.distance_m(&center) <= radius_m)
+        });
+        let in_session = self
+            .session
+            .is_none_or(|session| log.session_id == Some(session));
+        let has_tags = self.tags.iter().all(|tag| log.tags.contains(tag));
+
+        in_frequency_range
+            && in_time_window
+            && mode_accepted
+            && callsign_matches
+            && comment_matches
+            && in_bbox
+            && in_radius
+            && in_session
+            && has_tags
+    }
+}
+
+/// Position of a log in the `(timestamp, id)` ordering, used to fetch the following page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LogCursor {
+    pub timestamp: DateTime<Utc>,
+    pub id: i32,
+}
+
+impl From<&Log> for LogCursor {
+    fn from(log: &Log) -> Self {
+        LogCursor {
+            timestamp: log.timestamp,
+            id: log.id,
+        }
+    }
+}
+
+/// One page of logs, newest first
+#[derive(Debug, Clone, Default)]
+pub struct LogPage {
+    pub logs: Vec<Log>,
+    /// Cursor for the next page, None if this is the last one
+    pub next: Option<LogCursor>,
+}
+
+/// Fetch up to `limit` logs matching `filter`, starting after `after`
+///
+/// Pass `None` for the first page and the returned `LogPage::next` for each
+/// following one. A negative `limit` is treated as zero, as in [`paginate`].
+pub fn query_logs(
+    conn: &mut PgConnection,
+    filter: &LogFilter,
+    after: Option<LogCursor>,
+    limit: i64,
+) -> Result<LogPage, Error> {
+    Ok(query_rows(conn, filter, after, limit)?)
+}
+
+/// [`query_logs`] for the stores, which report a `DatabaseError`
+pub(crate) fn query_rows(
+    conn: &mut PgConnection,
+    filter: &LogFilter,
+    after: Option<LogCursor>,
+    limit: i64,
+) -> Result<LogPage, DatabaseError> {
+    use crate::schema::logs::dsl::*;
+
+    let limit = limit.max(0);
+    let mut query = filter.to_query();
+    if let Some(cursor) = after {
+        // A row comparison lets Postgres walk logs_timestamp_id_idx directly
+        query = query.filter(
+            sql::<Bool>(r#"("logs"."timestamp", "logs"."id") < ("#)
+                .bind::<Timestamptz, _>(cursor.timestamp)
+                .sql(", ")
+                .bind::<Integer, _>(cursor.id)
+                .sql(")"),
+        );
+    }
+
+    // Fetch one extra row to learn whether another page follows
+    let mut rows: Vec<Log> = query
+        .order((timestamp.desc(), id.desc()))
+        .limit(limit.saturating_add(1))
+        .select(Log::as_select())
+        .load(conn)?;
+
+    let has_more = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+    let next = if has_more {
+        rows.last().map(LogCursor::from)
+    } else {
+        None
+    };
+    Ok(LogPage { logs: rows, next })
+}
+
+/// Page through logs already in memory with the same ordering as [`query_logs`]
+///
+/// `logs` must already be filtered; they are sorted here.
+pub(crate) fn paginate(mut logs: Vec<Log>, after: Option<LogCursor>, limit: i64) -> LogPage {
+    logs.sort_unstable_by_key(|log| Reverse((log.timestamp, log.id)));
+    if let Some(cursor) = after {
+        logs.retain(|log| (log.timestamp, log.id) < (cursor.timestamp, cursor.id));
+    }
+
+    let limit = limit.max(0) as usize;
+    let has_more = logs.len() > limit;
+    logs.truncate(limit);
+    let next = if has_more {
+        logs.last().map(LogCursor::from)
+    } else {
+        None
+    };
+    LogPage { logs, next }
+}
+
+/// Escape the `LIKE` wildcards `%` and `_` and the escape character itself
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Translate a `*`/`?` glob into a `LIKE` pattern
+fn glob_to_like(pattern: &str) -> String {
+    escape_like(pattern).replace('*', "%").replace('?', "_")
+}
+
+/// Case-insensitive `*`/`?` glob match over the whole of `text`
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    // Greedy match, backtracking to the most recent `*`
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, star_t)) => {
+                    p = star + 1;
+                    t = star_t + 1;
+                    backtrack = Some((star, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
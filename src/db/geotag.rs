@@ -0,0 +1,161 @@
+//! Placing logs on a recorded GPS track by their timestamps
+//!
+//! Each log's position is interpolated between the track fixes either side of its time.
+//! Logs the track does not cover are reported rather than guessed: before it starts,
+//! after it ends, or in a gap longer than the allowed one.
+use std::fmt;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::db::LogStore;
+use crate::db::filter::LogFilter;
+use crate::error::DatabaseError;
+use crate::model::{Log, LogChanges};
+use crate::spatial::{Coordinate, Track, TrackMiss};
+
+/// Logs read from the store at a time
+const PAGE_SIZE: i64 = 1000;
+
+/// How log times are matched to the track
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeotagOptions {
+    /// How far the logging clock runs ahead of the GPS clock; negative if it is behind
+    pub clock_offset: TimeDelta,
+    /// Longest time between two fixes a position is interpolated across
+    pub max_gap: TimeDelta,
+}
+
+impl Default for GeotagOptions {
+    fn default() -> Self {
+        Self {
+            clock_offset: TimeDelta::zero(),
+            max_gap: TimeDelta::seconds(60),
+        }
+    }
+}
+
+/// A log the track places, and where
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeotagFix {
+    pub id: i32,
+    pub xcoord: f32,
+    pub ycoord: f32,
+    pub position: Coordinate,
+}
+
+/// A log the track does not cover
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeotagMiss {
+    pub id: i32,
+    /// The log's time on the GPS clock
+    pub track_time: DateTime<Utc>,
+    pub miss: TrackMiss,
+}
+
+/// Result of geotagging the logs matching a filter
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeotagReport {
+    pub scanned: usize,
+    pub tagged: Vec<GeotagFix>,
+    pub untagged: Vec<GeotagMiss>,
+    /// Whether the positions were written to the store
+    pub applied: bool,
+}
+
+impl fmt::Display for GeotagReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Scanned {} logs, {} placed on the track, {} outside it",
+            self.scanned,
+            self.tagged.len(),
+            self.untagged.len()
+        )?;
+        for untagged in &self.untagged {
+            writeln!(
+                f,
+                "  id {} at {}: {}",
+                untagged.id,
+                untagged.track_time.format("%Y-%m-%d %H:%M:%S%.3f UTC"),
+                untagged.miss
+            )?;
+        }
+        if self.applied {
+            write!(f, "Moved {} logs", self.tagged.len())
+        } else {
+            write!(f, "Dry run, nothing written (use --apply to move the logs)")
+        }
+    }
+}
+
+/// The log's time on the GPS clock, correcting for `options.clock_offset`
+pub fn track_time(log: &Log, options: &GeotagOptions) -> DateTime<Utc> {
+    log.timestamp - options.clock_offset
+}
+
+/// Where `track` places `log`
+///
+/// # Errors
+/// Returns the [`TrackMiss`] saying why the track does not cover the log's time
+pub fn geotag_log(
+    log: &Log,
+    track: &Track,
+    options: &GeotagOptions,
+) -> Result<Coordinate, TrackMiss> {
+    track.position_at(track_time(log, options), options.max_gap)
+}
+
+/// Place every log matching `filter` on `track`, moving them if `apply`
+///
+/// Logs outside the track keep their stored position and are listed in the report.
+/// The moves are written as one batch, so if any of them fails none is applied.
+pub fn geotag_logs(
+    store: &mut dyn LogStore,
+    filter: &LogFilter,
+    track: &Track,
+    options: &GeotagOptions,
+    apply: bool,
+) -> Result<GeotagReport, DatabaseError> {
+    let mut report = GeotagReport::default();
+    let mut after = None;
+    loop {
+        let page = store.query(filter, after, PAGE_SIZE)?;
+        for log in &page.logs {
+            report.scanned += 1;
+            match geotag_log(log, track, options) {
+                Ok(position) => report.tagged.push(GeotagFix {
+                    id: log.id,
+                    xcoord: log.xcoord,
+                    ycoord: log.ycoord,
+                    position,
+                }),
+                Err(miss) => report.untagged.push(GeotagMiss {
+                    id: log.id,
+                    track_time: track_time(log, options),
+                    miss,
+                }),
+            }
+        }
+        match page.next {
+            Some(next) => after = Some(next),
+            None => break,
+        }
+    }
+
+    if apply {
+        let updates: Vec<(i32, LogChanges)> = report
+            .tagged
+            .iter()
+            .map(|fix| {
+                let changes = LogChanges {
+                    position: Some(fix.position),
+                    ..LogChanges::default()
+                };
+                (fix.id, changes)
+            })
+            .collect();
+        store.update_many(&updates)?;
+    }
+    report.applied = apply;
+    Ok(report)
+}
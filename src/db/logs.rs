@@ -0,0 +1,388 @@
+//! Create, read, update and delete operations on the `logs` table
+//!
+//! Deletes are soft: the row keeps its data with `deleted_at` set, and every read here
+//! skips it. Updates and deletes are recorded in `log_revisions`.
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::db::revisions::record_revision;
+use crate::db::tags::set_log_tags;
+use crate::error::{DatabaseError, Error, ValidationError};
+use crate::model::revision::validate_operator;
+use crate::model::{
+    Frequency, Log, LogChanges, LogDetails, LogUuid, NewLog, RevisionAction, SignalMode,
+};
+use crate::schema::logs;
+use crate::spatial::{Coordinate, DbPoint};
+
+/// Rows per INSERT statement, keeping the bind parameter count under Postgres' 65535 limit
+const BATCH_CHUNK_ROWS: usize = 2_000;
+
+/// Validate and insert a single log entry, timestamped now unless `timestamp` is given
+#[allow(clippy::too_many_arguments)]
+pub fn create_log(
+    conn: &mut PgConnection,
+    frequency: Frequency,
+    position: Coordinate,
+    callsign: String,
+    mode: SignalMode,
+    comment: String,
+    recording_duration: f32,
+    timestamp: Option<DateTime<Utc>>,
+    details: LogDetails,
+) -> Result<Log, Error> {
+    let new_log = NewLog::new(
+        frequency,
+        position,
+        &callsign,
+        mode,
+        &comment,
+        recording_duration,
+        timestamp,
+        details,
+    )?;
+    Ok(insert_row(conn, &new_log)?)
+}
+
+/// Validate and insert an entry that has already been built, along with its tags
+pub fn insert_log(conn: &mut PgConnection, new_log: &NewLog) -> Result<Log, Error> {
+    Ok(insert_row(conn, new_log)?)
+}
+
+/// [`insert_log`] for the stores, which report a `DatabaseError`
+pub(crate) fn insert_row(conn: &mut PgConnection, new_log: &NewLog) -> Result<Log, DatabaseError> {
+    new_log.validate()?;
+    conn.transaction(|conn| {
+        let mut log = diesel::insert_into(logs::table)
+            .values(new_log)
+            .returning(Log::as_select())
+            .get_result(conn)?;
+        log.tags = set_log_tags(conn, log.id, &new_log.tags)?;
+        Ok(log)
+    })
+}
+
+/// A batch row that failed validation and was not inserted
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectedRow {
+    /// Position of the row in the input slice
+    pub index: usize,
+    pub error: ValidationError,
+}
+
+/// Outcome of [`create_logs_batch`]
+#[derive(Debug, Clone, Default)]
+pub struct BatchReport {
+    /// Inserted rows, in input order
+    pub inserted: Vec<Log>,
+    pub rejected: Vec<RejectedRow>,
+}
+
+/// Validate every row and insert the valid ones in a single transaction
+///
+/// Invalid rows are reported rather than failing the batch. Valid rows are sent in
+/// multi-row INSERTs of up to `BATCH_CHUNK_ROWS` rows; a database error rolls back the
+/// whole batch.
+pub fn create_logs_batch(
+    conn: &mut PgConnection,
+    new_logs: &[NewLog],
+) -> Result<BatchReport, Error> {
+    let mut valid = Vec::with_capacity(new_logs.len());
+    let mut rejected = Vec::new();
+    for (index, new_log) in new_logs.iter().enumerate() {
+        match new_log.validate() {
+            Ok(()) => valid.push(new_log),
+            Err(error) => rejected.push(RejectedRow { index, error }),
+        }
+    }
+
+    let inserted = conn.transaction(|conn| {
+        let mut inserted = Vec::with_capacity(valid.len());
+        for chunk in valid.chunks(BATCH_CHUNK_ROWS) {
+            let rows: Vec<Log> = diesel::insert_into(logs::table)
+                .values(chunk.to_vec())
+                .returning(Log::as_select())
+                .get_results(conn)?;
+            // RETURNING keeps the order of VALUES
+            for (mut log, new_log) in rows.into_iter().zip(chunk) {
+                if !new_log.tags.is_empty() {
+                    log.tags = set_log_tags(conn, log.id, &new_log.tags)?;
+                }
+                inserted.push(log);
+            }
+        }
+        Ok::<_, DatabaseError>(inserted)
+    })?;
+
+    Ok(BatchReport { inserted, rejected })
+}
+
+/// Get a single log by id
+pub fn get_log(conn: &mut PgConnection, log_id: i32) -> Result<Log, Error> {
+    Ok(get_row(conn, log_id)?)
+}
+
+/// [`get_log`] for the stores, which report a `DatabaseError`
+pub(crate) fn get_row(conn: &mut PgConnection, log_id: i32) -> Result<Log, DatabaseError> {
+    logs::table
+        .find(log_id)
+        .filter(logs::deleted_at.is_null())
+        .select(Log::as_select())
+        .first(conn)
+        .optional()?
+        .ok_or_else(|| not_found(log_id))
+}
+
+/// Get the logs carrying any of `uuids`, in no particular order
+///
+/// Uuids with no log, or only a deleted one, are left out.
+pub fn get_logs_by_uuid(
+    conn: &mut PgConnection,
+    uuids: &[LogUuid],
+) -> Result<Vec<Log>, DatabaseError> {
+    if uuids.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(logs::table
+        .filter(logs::uuid.eq_any(uuids))
+        .filter(logs::deleted_at.is_null())
+        .select(Log::as_select())
+        .load(conn)?)
+}
+
+/// Get the most recent logs; a negative limit is treated as zero
+pub fn get_logs(conn: &mut PgConnection, limit: i64) -> Result<Vec<Log>, Error> {
+    use crate::schema::logs::dsl::*;
+
+    Ok(logs
+        .filter(deleted_at.is_null())
+        .order(timestamp.desc())
+        .limit(limit.max(0))
+        .select(Log::as_select())
+        .load(conn)?)
+}
+
+/// Get logs recorded within `radius_m` metres of `center`, nearest first
+pub fn get_logs_within_radius(
+    conn: &mut PgConnection,
+    center: Coordinate,
+    radius_m: f64,
+    limit: i64,
+) -> Result<Vec<Log>, Error> {
+    use crate::schema::logs::dsl::*;
+    use crate::spatial::postgis::{st_distance, st_dwithin};
+
+    let center = DbPoint::from(center);
+    Ok(logs
+        .filter(deleted_at.is_null())
+        .filter(st_dwithin(location.assume_not_null(), center, radius_m))
+        .order(st_distance(location.assume_not_null(), center))
+        .limit(limit.max(0))
+        .select(Log::as_select())
+        .load(conn)?)
+}
+
+/// Column values written by [`update_log`]; `None` leaves a column unchanged
+#[derive(AsChangeset)]
+#[diesel(table_name = logs)]
+struct LogChangeset<'a> {
+    frequency: Option<Frequency>,
+    xcoord: Option<f32>,
+    ycoord: Option<f32>,
+    location: Option<DbPoint>,
+    callsign: Option<&'a str>,
+    mode: Option<&'a SignalMode>,
+    comment: Option<&'a str>,
+    recording_duration: Option<f32>,
+    timestamp: Option<DateTime<Utc>>,
+    power_dbm: Option<Option<f32>>,
+    snr_db: Option<Option<f32>>,
+    bandwidth_hz: Option<Option<Frequency>>,
+    altitude_m: Option<Option<f32>>,
+    position_accuracy_m: Option<Option<f32>>,
+    heading_deg: Option<Option<f32>>,
+    speed_mps: Option<Option<f32>>,
+    session_id: Option<Option<i32>>,
+    equipment_profile_id: Option<Option<i32>>,
+}
+
+impl<'a> From<&'a LogChanges> for LogChangeset<'a> {
+    fn from(changes: &'a LogChanges) -> Self {
+        let details = changes.details.as_ref();
+        LogChangeset {
+            frequency: changes.frequency,
+            xcoord: changes.position.map(|p| p.lat() as f32),
+            ycoord: changes.position.map(|p| p.lon() as f32),
+            location: changes.position.map(DbPoint::from),
+            callsign: changes.callsign.as_deref(),
+            mode: changes.mode.as_ref(),
+            comment: changes.comment.as_deref(),
+            recording_duration: changes.recording_duration,
+            timestamp: changes.timestamp,
+            power_dbm: details.map(|d| d.power_dbm),
+            snr_db: details.map(|d| d.snr_db),
+            bandwidth_hz: details.map(|d| d.bandwidth_hz),
+            altitude_m: details.map(|d| d.altitude_m),
+            position_accuracy_m: details.map(|d| d.position_accuracy_m),
+            heading_deg: details.map(|d| d.heading_deg),
+            speed_mps: details.map(|d| d.speed_mps),
+            session_id: changes.session_id,
+            equipment_profile_id: changes.equipment_profile_id,
+        }
+    }
+}
+
+/// Apply `changes` to a log on behalf of `operator`, returning the updated row
+///
+/// The changes are validated with the same rules as [`NewLog::new`], and the row
+/// before and after is recorded as a revision. Empty changes return the row
+/// unmodified without recording anything.
+pub fn update_log(
+    conn: &mut PgConnection,
+    log_id: i32,
+    changes: &LogChanges,
+    operator: &str,
+) -> Result<Log, Error> {
+    Ok(update_row(conn, log_id, changes, operator)?)
+}
+
+/// [`update_log`] for the stores, which report a `DatabaseError`
+pub(crate) fn update_row(
+    conn: &mut PgConnection,
+    log_id: i32,
+    changes: &LogChanges,
+    operator: &str,
+) -> Result<Log, DatabaseError> {
+    changes.validate()?;
+    validate_operator(operator)?;
+    if changes.is_empty() {
+        return get_row(conn, log_id);
+    }
+
+    conn.transaction(|conn| {
+        let old = lock_log(conn, log_id, false)?;
+        let new = write_changes(conn, log_id, changes, None)?;
+        record_revision(
+            conn,
+            log_id,
+            RevisionAction::Update,
+            &old,
+            Some(&new),
+            operator,
+        )?;
+        Ok(new)
+    })
+}
+
+/// Apply each of `updates` in turn on behalf of `operator`, in one transaction
+///
+/// A change that fails rolls back the ones before it, so either every log is updated
+/// or none is.
+pub fn update_logs(
+    conn: &mut PgConnection,
+    updates: &[(i32, LogChanges)],
+    operator: &str,
+) -> Result<Vec<Log>, Error> {
+    Ok(update_rows(conn, updates, operator)?)
+}
+
+/// [`update_logs`] for the stores, which report a `DatabaseError`
+pub(crate) fn update_rows(
+    conn: &mut PgConnection,
+    updates: &[(i32, LogChanges)],
+    operator: &str,
+) -> Result<Vec<Log>, DatabaseError> {
+    conn.transaction(|conn| {
+        updates
+            .iter()
+            .map(|(log_id, changes)| update_row(conn, *log_id, changes, operator))
+            .collect()
+    })
+}
+
+/// Write validated `changes` to a locked row, returning it as updated
+///
+/// `deleted_at` is written too when given, e.g. to undelete the row.
+pub(crate) fn write_changes(
+    conn: &mut PgConnection,
+    log_id: i32,
+    changes: &LogChanges,
+    deleted_at: Option<Option<DateTime<Utc>>>,
+) -> Result<Log, DatabaseError> {
+    if let Some(tags) = &changes.tags {
+        set_log_tags(conn, log_id, tags)?;
+    }
+    let columns = LogChanges {
+        tags: None,
+        ..changes.clone()
+    };
+    // Diesel refuses an UPDATE without columns, as when only the tags change
+    if columns.is_empty() && deleted_at.is_none() {
+        return lock_log(conn, log_id, false);
+    }
+    Ok(diesel::update(logs::table.find(log_id))
+        .set((
+            LogChangeset::from(&columns),
+            deleted_at.map(|deleted_at| logs::deleted_at.eq(deleted_at)),
+        ))
+        .returning(Log::as_select())
+        .get_result(conn)?)
+}
+
+/// Soft delete a log on behalf of `operator`
+///
+/// The row is hidden from every query but kept, so the delete can be undone with
+/// [`restore_revision`](crate::db::revisions::restore_revision).
+pub fn delete_log(conn: &mut PgConnection, log_id: i32, operator: &str) -> Result<(), Error> {
+    Ok(delete_row(conn, log_id, operator)?)
+}
+
+/// [`delete_log`] for the stores, which report a `DatabaseError`
+pub(crate) fn delete_row(
+    conn: &mut PgConnection,
+    log_id: i32,
+    operator: &str,
+) -> Result<(), DatabaseError> {
+    validate_operator(operator)?;
+    conn.transaction(|conn| {
+        let old = lock_log(conn, log_id, false)?;
+        diesel::update(logs::table.find(log_id))
+            .set(logs::deleted_at.eq(diesel::dsl::now))
+            .execute(conn)?;
+        record_revision(conn, log_id, RevisionAction::Delete, &old, None, operator)
+    })
+}
+
+/// Read a log and lock its row until the end of the transaction
+pub(crate) fn lock_log(
+    conn: &mut PgConnection,
+    log_id: i32,
+    include_deleted: bool,
+) -> Result<Log, DatabaseError> {
+    let locked = if include_deleted {
+        logs::table
+            .find(log_id)
+            .select(Log::as_select())
+            .for_update()
+            .first(conn)
+            .optional()?
+    } else {
+        logs::table
+            .find(log_id)
+            .filter(logs::deleted_at.is_null())
+            .select(Log::as_select())
+            .for_update()
+            .first(conn)
+            .optional()?
+    };
+    locked.ok_or_else(|| not_found(log_id))
+}
+
+pub(crate) fn not_found(log_id: i32) -> DatabaseError {
+    DatabaseError::NotFound(format!("log {}", log_id))
+}
+
+/// The error Postgres' unique index on `uuid` raises, for stores that check it themselves
+pub(crate) fn duplicate_uuid(uuid: &LogUuid) -> DatabaseError {
+    DatabaseError::DuplicateEntry(format!("a log with uuid {} is already stored", uuid))
+}
@@ -0,0 +1,127 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::db::db::{LogStore, LogStream};
+use crate::db::filter::{LogCursor, LogFilter, LogPage, paginate};
+use crate::db::logs::{duplicate_uuid, not_found};
+use crate::db::search::{SearchHit, search_in};
+use crate::error::DatabaseError;
+use crate::model::{Log, LogChanges, LogUuid, NewLog};
+
+/// [`LogStore`] that keeps logs in memory, for tests and throwaway sessions
+#[derive(Debug, Default)]
+pub struct MemoryLogStore {
+    logs: BTreeMap<i32, Log>,
+    /// Uuids of every log stored, deleted ones included, as Postgres keeps them
+    uuids: HashSet<LogUuid>,
+    last_id: i32,
+}
+
+impl MemoryLogStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A store holding `logs` as they are, e.g. ones read from a file to merge elsewhere
+    ///
+    /// Logs are numbered from 1 in the order given and are not validated.
+    pub fn from_logs(logs: impl IntoIterator<Item = Log>) -> Self {
+        let mut store = Self::default();
+        for mut log in logs {
+            store.last_id += 1;
+            log.id = store.last_id;
+            store.uuids.insert(log.uuid);
+            store.logs.insert(log.id, log);
+        }
+        store
+    }
+
+    pub fn len(&self) -> usize {
+        self.logs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.logs.is_empty()
+    }
+}
+
+impl LogStore for MemoryLogStore {
+    fn create(&mut self, new_log: &NewLog) -> Result<Log, DatabaseError> {
+        new_log.validate()?;
+        if !self.uuids.insert(new_log.uuid) {
+            return Err(duplicate_uuid(&new_log.uuid));
+        }
+        self.last_id += 1;
+        let log = new_log.to_log(self.last_id);
+        self.logs.insert(log.id, log.clone());
+        Ok(log)
+    }
+
+    fn get(&mut self, id: i32) -> Result<Log, DatabaseError> {
+        self.logs.get(&id).cloned().ok_or_else(|| not_found(id))
+    }
+
+    fn get_by_uuids(&mut self, uuids: &[LogUuid]) -> Result<Vec<Log>, DatabaseError> {
+        let wanted: HashSet<&LogUuid> = uuids.iter().collect();
+        Ok(self
+            .logs
+            .values()
+            .filter(|log| wanted.contains(&log.uuid))
+            .cloned()
+            .collect())
+    }
+
+    fn query(
+        &mut self,
+        filter: &LogFilter,
+        after: Option<LogCursor>,
+        limit: i64,
+    ) -> Result<LogPage, DatabaseError> {
+        let matching = self
+            .logs
+            .values()
+            .filter(|log| filter.matches(log))
+            .cloned()
+            .collect();
+        Ok(paginate(matching, after, limit))
+    }
+
+    fn update(&mut self, id: i32, changes: &LogChanges) -> Result<Log, DatabaseError> {
+        changes.validate()?;
+        let log = self.logs.get_mut(&id).ok_or_else(|| not_found(id))?;
+        changes.apply(log);
+        Ok(log.clone())
+    }
+
+    fn update_many(&mut self, updates: &[(i32, LogChanges)]) -> Result<Vec<Log>, DatabaseError> {
+        // Check everything first, so a failure leaves every log as it was
+        for (id, changes) in updates {
+            changes.validate()?;
+            if !self.logs.contains_key(id) {
+                return Err(not_found(*id));
+            }
+        }
+        Ok(updates
+            .iter()
+            .map(|(id, changes)| {
+                let log = self.logs.get_mut(id).expect("checked above");
+                changes.apply(log);
+                log.clone()
+            })
+            .collect())
+    }
+
+    fn delete(&mut self, id: i32) -> Result<(), DatabaseError> {
+        self.logs
+            .remove(&id)
+            .map(|_| ())
+            .ok_or_else(|| not_found(id))
+    }
+
+    fn search(&mut self, query: &str, limit: i64) -> Result<Vec<SearchHit>, DatabaseError> {
+        Ok(search_in(self.logs.values(), query, limit))
+    }
+
+    fn stream_all(&mut self) -> Result<LogStream<'_>, DatabaseError> {
+        Ok(Box::new(self.logs.values().cloned().map(Ok)))
+    }
+}
@@ -0,0 +1,207 @@
+//! Combining logs captured by different field units
+//!
+//! Logs are matched by [`LogUuid`], which every unit generates itself, so a log that
+//! travelled between databases is recognised wherever it ends up.
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use chrono::SubsecRound;
+
+use crate::db::db::{LogStore, StoreConfig};
+use crate::db::logs::RejectedRow;
+use crate::error::DatabaseError;
+use crate::model::{Log, LogUuid};
+
+/// A source log whose uuid is already in the target with different content
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    pub uuid: LogUuid,
+    /// Id of the log already in the target
+    pub local_id: i32,
+    /// Id of the log in the source
+    pub incoming_id: i32,
+    /// Names of the fields that differ
+    pub fields: Vec<&'static str>,
+}
+
+/// Outcome of [`merge_logs`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    /// Logs read from the source
+    pub scanned: usize,
+    pub imported: usize,
+    /// Logs already in the target with the same content
+    pub duplicates: usize,
+    /// Logs left out because the target holds a different version
+    pub conflicts: Vec<MergeConflict>,
+    /// Logs left out because they were deleted in the target
+    pub deleted: usize,
+    /// Logs the target refused; `index` is the position in the source
+    pub rejected: Vec<RejectedRow>,
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Scanned {} logs: {} imported, {} already present, {} conflicts, {} deleted here, {} rejected",
+            self.scanned,
+            self.imported,
+            self.duplicates,
+            self.conflicts.len(),
+            self.deleted,
+            self.rejected.len()
+        )?;
+        for conflict in &self.conflicts {
+            write!(
+                f,
+                "\n  conflict {}: local id {}, incoming id {} differ in {}",
+                conflict.uuid,
+                conflict.local_id,
+                conflict.incoming_id,
+                conflict.fields.join(", ")
+            )?;
+        }
+        for row in &self.rejected {
+            write!(f, "\n  rejected source row {}: {}", row.index, row.error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where `merge --from` reads: a Postgres URL, otherwise a sled directory
+pub fn source_config(from: &str) -> StoreConfig {
+    if from.starts_with("postgres://") || from.starts_with("postgresql://") {
+        StoreConfig::Postgres {
+            database_url: from.to_string(),
+        }
+    } else {
+        StoreConfig::Sled {
+            path: PathBuf::from(from),
+        }
+    }
+}
+
+/// Source logs matched against the target per round trip
+const MERGE_BATCH: usize = 500;
+
+/// Copy every log from `source` into `target` that `target` does not already have
+///
+/// Logs whose uuid is already present are counted as duplicates if their content
+/// matches and reported as conflicts otherwise; neither side is changed. Merging the
+/// same source again imports nothing. Imported logs are not attached to a session or
+/// equipment profile, since those ids only mean something in the database they came
+/// from. The source is read in batches and only each batch's uuids are looked up in
+/// the target.
+pub fn merge_logs(
+    target: &mut dyn LogStore,
+    source: &mut dyn LogStore,
+) -> Result<MergeReport, DatabaseError> {
+    let mut report = MergeReport::default();
+    let mut batch = Vec::with_capacity(MERGE_BATCH);
+    for incoming in source.stream_all()? {
+        batch.push(incoming?);
+        if batch.len() == MERGE_BATCH {
+            merge_batch(target, &batch, &mut report)?;
+            batch.clear();
+        }
+    }
+    merge_batch(target, &batch, &mut report)?;
+    Ok(report)
+}
+
+/// Merge the next `batch` of source logs, continuing `report`
+fn merge_batch(
+    target: &mut dyn LogStore,
+    batch: &[Log],
+    report: &mut MergeReport,
+) -> Result<(), DatabaseError> {
+    let uuids: Vec<LogUuid> = batch.iter().map(|log| log.uuid).collect();
+    let mut known: HashMap<LogUuid, Log> = target
+        .get_by_uuids(&uuids)?
+        .into_iter()
+        .map(|log| (log.uuid, log))
+        .collect();
+
+    for incoming in batch {
+        let index = report.scanned;
+        report.scanned += 1;
+
+        if let Some(local) = known.get(&incoming.uuid) {
+            let fields = differing_fields(local, incoming);
+            if fields.is_empty() {
+                report.duplicates += 1;
+            } else {
+                report.conflicts.push(MergeConflict {
+                    uuid: incoming.uuid,
+                    local_id: local.id,
+                    incoming_id: incoming.id,
+                    fields,
+                });
+            }
+            continue;
+        }
+
+        let new_log = incoming
+            .as_new_log()
+            .in_session(None)
+            .with_equipment_profile(None);
+        match target.create(&new_log) {
+            Ok(created) => {
+                report.imported += 1;
+                known.insert(created.uuid, created);
+            }
+            // Deleted logs are hidden from lookups but still hold their uuid
+            Err(DatabaseError::DuplicateEntry(_)) => report.deleted += 1,
+            Err(DatabaseError::InvalidData(error)) => {
+                report.rejected.push(RejectedRow { index, error })
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Names of the recorded fields on which two versions of a log disagree
+///
+/// Ids are local to each database and are not compared. Timestamps are compared at
+/// the microsecond precision Postgres stores, and a missing callsign or comment
+/// equals an empty one.
+pub fn differing_fields(local: &Log, incoming: &Log) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    macro_rules! compare {
+        ($($field:ident),* $(,)?) => {
+            $(
+                if local.$field != incoming.$field {
+                    fields.push(stringify!($field));
+                }
+            )*
+        };
+    }
+    compare!(frequency, xcoord, ycoord, mode, recording_duration);
+    if local.callsign.as_deref().unwrap_or_default()
+        != incoming.callsign.as_deref().unwrap_or_default()
+    {
+        fields.push("callsign");
+    }
+    if local.comment.as_deref().unwrap_or_default()
+        != incoming.comment.as_deref().unwrap_or_default()
+    {
+        fields.push("comment");
+    }
+    if local.timestamp.trunc_subsecs(6) != incoming.timestamp.trunc_subsecs(6) {
+        fields.push("timestamp");
+    }
+    compare!(
+        power_dbm,
+        snr_db,
+        bandwidth_hz,
+        altitude_m,
+        position_accuracy_m,
+        heading_deg,
+        speed_mps,
+        tags,
+    );
+    fields
+}
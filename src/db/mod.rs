@@ -1 +1,41 @@
 mod db;
+pub mod equipment;
+pub mod filter;
+pub mod geotag;
+pub mod logs;
+pub mod memory_store;
+pub mod merge;
+pub mod pool;
+pub mod postgres_store;
+pub mod queue;
+pub mod queued_store;
+pub mod repair;
+pub mod revisions;
+pub mod search;
+pub mod sessions;
+pub mod sled_store;
+pub mod sync;
+pub mod tags;
+pub mod worker;
+
+pub use db::{LogStore, LogStream, StoreConfig, open_store};
+pub use equipment::{
+    create_equipment_profile, find_or_create_equipment_profile, get_equipment_profile,
+    insert_log_with_equipment, list_equipment_profiles,
+};
+pub use memory_store::MemoryLogStore;
+pub use merge::{MergeConflict, MergeReport, merge_logs};
+pub use pool::{PgPool, PooledPgConnection, connect_pool, lazy_pool};
+pub use postgres_store::PgLogStore;
+pub use queue::{CaptureQueue, QueuedLog, ReplayReport};
+pub use queued_store::QueuedLogStore;
+pub use revisions::{get_revision, log_history, restore_revision};
+pub use search::{SearchHit, search_logs};
+pub use sessions::{
+    active_session, get_session, get_sessions, list_sessions, start_session, stop_session,
+    summarize_session,
+};
+pub use sled_store::SledLogStore;
+pub use sync::{SyncStatus, SyncWorker};
+pub use tags::{list_tags, set_log_tags};
+pub use worker::{DbCommand, DbReply, DbWorker};
@@ -0,0 +1,40 @@
+//! Pooled Postgres connections
+//!
+//! r2d2 checks each connection before handing it out and replaces broken ones, so
+//! callers recover from a server restart or network drop on their next query.
+use std::time::Duration;
+
+use diesel::pg::PgConnection;
+use diesel::r2d2::{self, ConnectionManager, NopErrorHandler, PooledConnection};
+
+use crate::error::DatabaseError;
+
+pub type PgPool = r2d2::Pool<ConnectionManager<PgConnection>>;
+pub type PooledPgConnection = PooledConnection<ConnectionManager<PgConnection>>;
+
+/// Connections kept open per pool
+pub const DEFAULT_POOL_SIZE: u32 = 4;
+/// How long checking out a connection waits before reporting the server unreachable
+pub const DEFAULT_CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pool builder with this crate's defaults
+///
+/// Connection errors are reported to whoever asked for a connection rather than
+/// logged from r2d2's background threads, which would draw over the TUI.
+pub fn pool_builder() -> r2d2::Builder<ConnectionManager<PgConnection>> {
+    r2d2::Pool::builder()
+        .max_size(DEFAULT_POOL_SIZE)
+        .min_idle(Some(1))
+        .connection_timeout(DEFAULT_CONNECTION_TIMEOUT)
+        .error_handler(Box::new(NopErrorHandler))
+}
+
+/// Build a pool, failing if the server cannot be reached now
+pub fn connect_pool(database_url: &str) -> Result<PgPool, DatabaseError> {
+    Ok(pool_builder().build(ConnectionManager::new(database_url))?)
+}
+
+/// Build a pool without connecting, for callers that must start while offline
+pub fn lazy_pool(database_url: &str) -> PgPool {
+    pool_builder().build_unchecked(ConnectionManager::new(database_url))
+}
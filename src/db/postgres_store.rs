@@ -0,0 +1,132 @@
+use diesel::connection::DefaultLoadingMode;
+use diesel::prelude::*;
+
+use crate::db::db::{LogStore, LogStream};
+use crate::db::equipment::insert_log_with_equipment;
+use crate::db::filter::{LogCursor, LogFilter, LogPage, query_rows};
+use crate::db::logs::{delete_row, get_logs_by_uuid, get_row, insert_row, update_row, update_rows};
+use crate::db::pool::{PgPool, PooledPgConnection, connect_pool};
+use crate::db::revisions::{log_history, restore_revision};
+use crate::db::search::{SearchHit, search_rows};
+use crate::db::sessions::{active_session, get_sessions};
+use crate::error::DatabaseError;
+use crate::model::revision::default_operator;
+use crate::model::{Log, LogChanges, LogRevision, LogUuid, NewEquipmentProfile, NewLog, Session};
+
+/// [`LogStore`] backed by a Postgres/PostGIS server
+///
+/// Each call checks a connection out of the pool, so a dropped connection only fails
+/// the call in flight. Updates and deletes are recorded under the store's operator.
+pub struct PgLogStore {
+    pool: PgPool,
+    operator: String,
+    /// Connection held by the iterator from [`LogStore::stream_all`]
+    streaming: Option<PooledPgConnection>,
+}
+
+impl PgLogStore {
+    pub fn new(pool: PgPool) -> Self {
+        PgLogStore {
+            pool,
+            operator: default_operator(),
+            streaming: None,
+        }
+    }
+
+    /// Record changes as made by `operator` instead of [`default_operator`]
+    pub fn with_operator(mut self, operator: impl Into<String>) -> Self {
+        self.operator = operator.into();
+        self
+    }
+
+    pub fn connect(database_url: &str) -> Result<Self, DatabaseError> {
+        Ok(Self::new(connect_pool(database_url)?))
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Check out a connection for queries the trait does not cover
+    pub fn connection(&self) -> Result<PooledPgConnection, DatabaseError> {
+        Ok(self.pool.get()?)
+    }
+
+    /// Every recorded change to a log, oldest first
+    pub fn history(&mut self, id: i32) -> Result<Vec<LogRevision>, DatabaseError> {
+        log_history(&mut *self.connection()?, id)
+    }
+
+    /// Put a log back the way it was before a revision, undeleting it if needed
+    pub fn restore(&mut self, revision_id: i32) -> Result<Log, DatabaseError> {
+        restore_revision(&mut *self.connection()?, revision_id, &self.operator)
+    }
+}
+
+impl LogStore for PgLogStore {
+    fn create(&mut self, new_log: &NewLog) -> Result<Log, DatabaseError> {
+        insert_row(&mut *self.connection()?, new_log)
+    }
+
+    fn create_with_equipment(
+        &mut self,
+        new_log: &NewLog,
+        equipment: &NewEquipmentProfile,
+    ) -> Result<Log, DatabaseError> {
+        insert_log_with_equipment(&mut *self.connection()?, new_log, equipment)
+    }
+
+    fn get(&mut self, id: i32) -> Result<Log, DatabaseError> {
+        get_row(&mut *self.connection()?, id)
+    }
+
+    fn get_by_uuids(&mut self, uuids: &[LogUuid]) -> Result<Vec<Log>, DatabaseError> {
+        get_logs_by_uuid(&mut *self.connection()?, uuids)
+    }
+
+    fn query(
+        &mut self,
+        filter: &LogFilter,
+        after: Option<LogCursor>,
+        limit: i64,
+    ) -> Result<LogPage, DatabaseError> {
+        query_rows(&mut *self.connection()?, filter, after, limit)
+    }
+
+    fn update(&mut self, id: i32, changes: &LogChanges) -> Result<Log, DatabaseError> {
+        update_row(&mut *self.connection()?, id, changes, &self.operator)
+    }
+
+    fn update_many(&mut self, updates: &[(i32, LogChanges)]) -> Result<Vec<Log>, DatabaseError> {
+        update_rows(&mut *self.connection()?, updates, &self.operator)
+    }
+
+    fn delete(&mut self, id: i32) -> Result<(), DatabaseError> {
+        delete_row(&mut *self.connection()?, id, &self.operator)
+    }
+
+    fn search(&mut self, query: &str, limit: i64) -> Result<Vec<SearchHit>, DatabaseError> {
+        search_rows(&mut *self.connection()?, query, limit)
+    }
+
+    fn stream_all(&mut self) -> Result<LogStream<'_>, DatabaseError> {
+        use crate::schema::logs::dsl::*;
+
+        let conn = self.streaming.insert(self.pool.get()?);
+        let rows = logs
+            .filter(deleted_at.is_null())
+            .order(id)
+            .select(Log::as_select())
+            .load_iter::<Log, DefaultLoadingMode>(&mut **conn)?;
+        Ok(Box::new(rows.map(|row| row.map_err(DatabaseError::from))))
+    }
+
+    /// The latest running session of the store's operator
+    fn active_session(&mut self) -> Result<Option<Session>, DatabaseError> {
+        active_session(&mut *self.connection()?, &self.operator)
+    }
+
+    fn sessions(&mut self, ids: &[i32]) -> Result<Vec<Session>, DatabaseError> {
+        get_sessions(&mut *self.connection()?, ids)
+    }
+}
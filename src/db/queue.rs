@@ -0,0 +1,240 @@
+//! Durable queue of captured logs waiting to be written to Postgres
+//!
+//! Entries are keyed by their [`LogUuid`], so the queue drains in capture order and a
+//! replay interrupted between the insert and the dequeue is harmless: the row is
+//! already present and the second insert is skipped.
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::db::equipment::find_or_create_equipment_profile;
+use crate::db::tags::set_log_tags;
+use crate::error::DatabaseError;
+use crate::model::timestamp::deserialize_utc;
+use crate::model::{Frequency, LogDetails, LogUuid, NewEquipmentProfile, NewLog, SignalMode};
+use crate::schema::logs;
+use crate::spatial::DbPoint;
+
+const PENDING_TREE: &str = "pending_logs";
+const REJECTED_TREE: &str = "rejected_logs";
+
+/// Owned copy of a [`NewLog`], as stored in the queue or sent to another thread
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedLog {
+    pub uuid: LogUuid,
+    pub frequency: Frequency,
+    pub xcoord: f32,
+    pub ycoord: f32,
+    /// Longitude and latitude of `NewLog::location`
+    pub position: (f64, f64),
+    pub callsign: String,
+    pub mode: SignalMode,
+    pub comment: String,
+    pub recording_duration: f32,
+    #[serde(deserialize_with = "deserialize_utc")]
+    pub timestamp: DateTime<Utc>,
+    pub details: LogDetails,
+    /// Absent in entries queued before sessions existed
+    #[serde(default)]
+    pub session_id: Option<i32>,
+    #[serde(default)]
+    pub equipment_profile_id: Option<i32>,
+    /// Receive chain to look up or create as a profile when the entry is stored
+    #[serde(default)]
+    pub equipment: Option<NewEquipmentProfile>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl From<&NewLog<'_>> for QueuedLog {
+    fn from(new_log: &NewLog<'_>) -> Self {
+        QueuedLog {
+            uuid: new_log.uuid,
+            frequency: new_log.frequency,
+            xcoord: new_log.xcoord,
+            ycoord: new_log.ycoord,
+            position: (new_log.location.0.x(), new_log.location.0.y()),
+            callsign: new_log.callsign.to_string(),
+            mode: new_log.mode.clone(),
+            comment: new_log.comment.to_string(),
+            recording_duration: new_log.recording_duration,
+            timestamp: new_log.timestamp,
+            details: new_log.details(),
+            session_id: new_log.session_id,
+            equipment_profile_id: new_log.equipment_profile_id,
+            equipment: None,
+            tags: new_log.tags.clone(),
+        }
+    }
+}
+
+impl QueuedLog {
+    /// Snapshot the receive chain to store alongside the entry
+    pub fn with_equipment(mut self, equipment: Option<NewEquipmentProfile>) -> Self {
+        self.equipment = equipment;
+        self
+    }
+
+    pub fn as_new_log(&self) -> NewLog<'_> {
+        let details = self.details;
+        NewLog {
+            frequency: self.frequency,
+            xcoord: self.xcoord,
+            ycoord: self.ycoord,
+            callsign: &self.callsign,
+            mode: self.mode.clone(),
+            comment: &self.comment,
+            recording_duration: self.recording_duration,
+            timestamp: self.timestamp,
+            location: DbPoint(geo::Point::new(self.position.0, self.position.1)),
+            power_dbm: details.power_dbm,
+            snr_db: details.snr_db,
+            bandwidth_hz: details.bandwidth_hz,
+            altitude_m: details.altitude_m,
+            position_accuracy_m: details.position_accuracy_m,
+            heading_deg: details.heading_deg,
+            speed_mps: details.speed_mps,
+            uuid: self.uuid,
+            session_id: self.session_id,
+            equipment_profile_id: self.equipment_profile_id,
+            tags: self.tags.clone(),
+        }
+    }
+}
+
+/// Outcome of one [`CaptureQueue::replay`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    /// Entries written to Postgres, or found already there
+    pub replayed: usize,
+    /// Entries Postgres refused, moved aside so they do not block the queue
+    pub rejected: usize,
+}
+
+/// Captured logs not yet confirmed in Postgres, persisted in sled
+///
+/// Cloning is cheap and clones share the same queue.
+#[derive(Clone)]
+pub struct CaptureQueue {
+    db: sled::Db,
+    pending: sled::Tree,
+    rejected: sled::Tree,
+}
+
+impl CaptureQueue {
+    /// Open or create the queue at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DatabaseError> {
+        Self::from_db(sled::open(path)?)
+    }
+
+    /// Use an already opened database, e.g. a temporary one
+    pub fn from_db(db: sled::Db) -> Result<Self, DatabaseError> {
+        Ok(CaptureQueue {
+            pending: db.open_tree(PENDING_TREE)?,
+            rejected: db.open_tree(REJECTED_TREE)?,
+            db,
+        })
+    }
+
+    /// Validate and durably queue an entry, returning its uuid
+    pub fn enqueue(&self, new_log: &NewLog) -> Result<LogUuid, DatabaseError> {
+        self.enqueue_entry(QueuedLog::from(new_log))
+    }
+
+    /// Validate and durably queue an entry and its equipment snapshot, returning its uuid
+    pub fn enqueue_entry(&self, queued: QueuedLog) -> Result<LogUuid, DatabaseError> {
+        queued.as_new_log().validate()?;
+        if let Some(equipment) = &queued.equipment {
+            equipment.validate()?;
+        }
+        self.pending
+            .insert(queued.uuid.as_bytes(), encode(&queued)?)?;
+        self.db.flush()?;
+        Ok(queued.uuid)
+    }
+
+    /// Number of entries waiting to be replayed
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Number of entries Postgres refused
+    pub fn rejected_len(&self) -> usize {
+        self.rejected.len()
+    }
+
+    /// Waiting entries in capture order
+    pub fn pending(&self) -> Result<Vec<QueuedLog>, DatabaseError> {
+        self.pending
+            .iter()
+            .values()
+            .map(|value| decode(&value?))
+            .collect()
+    }
+
+    /// Insert every waiting entry into Postgres, dequeuing each once it is stored
+    ///
+    /// Entries whose uuid is already present are dequeued without inserting. An entry
+    /// Postgres rejects while still reachable is moved to the rejected tree; losing the
+    /// connection stops the replay and leaves the rest queued.
+    pub fn replay(&self, conn: &mut PgConnection) -> Result<ReplayReport, DatabaseError> {
+        let mut report = ReplayReport::default();
+        for entry in self.pending.iter() {
+            let (key, value) = entry?;
+            let queued = decode(&value)?;
+
+            match insert_queued(conn, &queued) {
+                Ok(()) => report.replayed += 1,
+                Err(e) => {
+                    // Only blame the entry if the server is still answering
+                    if diesel::sql_query("SELECT 1").execute(conn).is_err() {
+                        return Err(e);
+                    }
+                    self.rejected.insert(&key, value)?;
+                    report.rejected += 1;
+                }
+            }
+            self.pending.remove(&key)?;
+        }
+        // Losing unflushed removals only means replaying rows Postgres already has
+        self.db.flush()?;
+        Ok(report)
+    }
+}
+
+/// Insert one entry unless its uuid is already present, with its equipment profile
+/// and tags
+fn insert_queued(conn: &mut PgConnection, queued: &QueuedLog) -> Result<(), DatabaseError> {
+    conn.transaction(|conn| {
+        let mut new_log = queued.as_new_log();
+        if let Some(equipment) = &queued.equipment {
+            let profile = find_or_create_equipment_profile(conn, equipment)?;
+            new_log = new_log.with_equipment_profile(Some(profile.id));
+        }
+        let inserted = diesel::insert_into(logs::table)
+            .values(&new_log)
+            .on_conflict(logs::uuid)
+            .do_nothing()
+            .returning(logs::id)
+            .get_result::<i32>(conn)
+            .optional()?;
+        if let Some(log_id) = inserted {
+            set_log_tags(conn, log_id, &new_log.tags)?;
+        }
+        Ok(())
+    })
+}
+
+fn encode(queued: &QueuedLog) -> Result<Vec<u8>, DatabaseError> {
+    serde_json::to_vec(queued).map_err(|e| DatabaseError::QueryError(e.to_string()))
+}
+
+fn decode(bytes: &[u8]) -> Result<QueuedLog, DatabaseError> {
+    serde_json::from_slice(bytes).map_err(|e| DatabaseError::QueryError(e.to_string()))
+}
@@ -0,0 +1,125 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::db::db::{LogStore, LogStream};
+use crate::db::filter::{LogCursor, LogFilter, LogPage};
+use crate::db::pool::{PgPool, lazy_pool};
+use crate::db::postgres_store::PgLogStore;
+use crate::db::queue::{CaptureQueue, QueuedLog};
+use crate::db::search::SearchHit;
+use crate::db::sync::{DEFAULT_SYNC_INTERVAL, SyncStatus, SyncWorker};
+use crate::error::DatabaseError;
+use crate::model::{Log, LogChanges, LogUuid, NewEquipmentProfile, NewLog, Session};
+
+/// Offline-first [`LogStore`]: new logs go to a local queue, everything else to Postgres
+///
+/// `create` only touches the sled [`CaptureQueue`], so capture keeps working without
+/// a connection; a [`SyncWorker`] replays the queue in the background. Reads, updates
+/// and deletes go to Postgres through the pool and fail while it is unreachable.
+/// Queued logs appear in queries once they have been replayed.
+pub struct QueuedLogStore {
+    remote: PgLogStore,
+    queue: CaptureQueue,
+    sync: SyncWorker,
+}
+
+impl QueuedLogStore {
+    /// Open the queue at `queue_path` and start syncing it to `database_url`
+    ///
+    /// Succeeds even if the server is unreachable.
+    pub fn open(database_url: &str, queue_path: impl AsRef<Path>) -> Result<Self, DatabaseError> {
+        Ok(Self::with_queue(
+            lazy_pool(database_url),
+            CaptureQueue::open(queue_path)?,
+            DEFAULT_SYNC_INTERVAL,
+        ))
+    }
+
+    pub fn with_queue(pool: PgPool, queue: CaptureQueue, sync_interval: Duration) -> Self {
+        QueuedLogStore {
+            sync: SyncWorker::spawn(queue.clone(), pool.clone(), sync_interval),
+            remote: PgLogStore::new(pool),
+            queue,
+        }
+    }
+
+    pub fn queue(&self) -> &CaptureQueue {
+        &self.queue
+    }
+}
+
+impl LogStore for QueuedLogStore {
+    /// Queue the entry for replay; the returned log has id 0 until it reaches Postgres
+    fn create(&mut self, new_log: &NewLog) -> Result<Log, DatabaseError> {
+        self.queue.enqueue(new_log)?;
+        self.sync.wake();
+        Ok(new_log.to_log(0))
+    }
+
+    /// Queue the entry with its equipment snapshot; the profile is resolved on replay
+    fn create_with_equipment(
+        &mut self,
+        new_log: &NewLog,
+        equipment: &NewEquipmentProfile,
+    ) -> Result<Log, DatabaseError> {
+        let queued = QueuedLog::from(new_log).with_equipment(Some(equipment.clone()));
+        self.queue.enqueue_entry(queued)?;
+        self.sync.wake();
+        Ok(new_log.to_log(0))
+    }
+
+    fn get(&mut self, id: i32) -> Result<Log, DatabaseError> {
+        self.remote.get(id)
+    }
+
+    /// Look the uuids up in Postgres; queued entries are not included
+    fn get_by_uuids(&mut self, uuids: &[LogUuid]) -> Result<Vec<Log>, DatabaseError> {
+        self.remote.get_by_uuids(uuids)
+    }
+
+    fn query(
+        &mut self,
+        filter: &LogFilter,
+        after: Option<LogCursor>,
+        limit: i64,
+    ) -> Result<LogPage, DatabaseError> {
+        self.remote.query(filter, after, limit)
+    }
+
+    fn update(&mut self, id: i32, changes: &LogChanges) -> Result<Log, DatabaseError> {
+        self.remote.update(id, changes)
+    }
+
+    fn update_many(&mut self, updates: &[(i32, LogChanges)]) -> Result<Vec<Log>, DatabaseError> {
+        self.remote.update_many(updates)
+    }
+
+    fn delete(&mut self, id: i32) -> Result<(), DatabaseError> {
+        self.remote.delete(id)
+    }
+
+    /// Search the logs already in Postgres; queued entries are not included
+    fn search(&mut self, query: &str, limit: i64) -> Result<Vec<SearchHit>, DatabaseError> {
+        self.remote.search(query, limit)
+    }
+
+    /// Stream the logs already in Postgres; queued entries are not included
+    fn stream_all(&mut self) -> Result<LogStream<'_>, DatabaseError> {
+        self.remote.stream_all()
+    }
+
+    fn sync_status(&self) -> Option<SyncStatus> {
+        Some(SyncStatus {
+            pending: self.queue.len(),
+            ..self.sync.status()
+        })
+    }
+
+    fn active_session(&mut self) -> Result<Option<Session>, DatabaseError> {
+        self.remote.active_session()
+    }
+
+    fn sessions(&mut self, ids: &[i32]) -> Result<Vec<Session>, DatabaseError> {
+        self.remote.sessions(ids)
+    }
+}
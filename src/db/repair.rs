@@ -0,0 +1,183 @@
+//! Detection and repair of log rows with swapped or implausible coordinates
+//!
+//! The canonical convention is `xcoord` = latitude and `ycoord` = longitude,
+//! with `location` holding the same position as a PostGIS point.
+use std::fmt;
+
+use diesel::prelude::*;
+
+use crate::spatial::{Coordinate, DbPoint};
+
+/// Why a row's coordinates were flagged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoordIssue {
+    /// Only valid (or only near the reference) with latitude and longitude exchanged
+    Swapped,
+    /// Out of range in either axis order
+    OutOfRange,
+    /// Valid, but further than the allowed distance from the reference position
+    FarFromReference { distance_m: f64 },
+    /// Valid, but the `location` column was never populated
+    MissingLocation,
+}
+
+impl fmt::Display for CoordIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoordIssue::Swapped => write!(f, "latitude/longitude swapped"),
+            CoordIssue::OutOfRange => write!(f, "out of range in either axis order"),
+            CoordIssue::FarFromReference { distance_m } => {
+                write!(f, "{:.1} km from reference position", distance_m / 1000.0)
+            }
+            CoordIssue::MissingLocation => write!(f, "location column not populated"),
+        }
+    }
+}
+
+/// A position the stored coordinates should be compared against
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReferencePosition {
+    pub position: Coordinate,
+    /// Maximum plausible distance from `position` in metres
+    pub max_distance_m: f64,
+}
+
+/// A flagged row and, if one can be determined, its corrected position
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoordRepair {
+    pub id: i32,
+    pub xcoord: f32,
+    pub ycoord: f32,
+    pub issue: CoordIssue,
+    pub fix: Option<Coordinate>,
+}
+
+/// Result of scanning the logs table
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepairReport {
+    pub scanned: usize,
+    pub repairs: Vec<CoordRepair>,
+    /// Whether fixes were written to the database
+    pub applied: bool,
+}
+
+impl RepairReport {
+    /// Number of flagged rows that have an automatic fix
+    pub fn fixable(&self) -> usize {
+        self.repairs.iter().filter(|r| r.fix.is_some()).count()
+    }
+}
+
+impl fmt::Display for RepairReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Scanned {} logs, {} flagged, {} fixable",
+            self.scanned,
+            self.repairs.len(),
+            self.fixable()
+        )?;
+        for repair in &self.repairs {
+            write!(
+                f,
+                "  id {}: ({}, {}) {}",
+                repair.id, repair.xcoord, repair.ycoord, repair.issue
+            )?;
+            match repair.fix {
+                Some(fix) => writeln!(f, " -> lat {}, lon {}", fix.lat(), fix.lon())?,
+                None => writeln!(f, " -> no automatic fix")?,
+            }
+        }
+        if self.applied {
+            write!(f, "Rewrote {} rows", self.fixable())
+        } else {
+            write!(f, "Dry run, nothing written (use --apply to rewrite)")
+        }
+    }
+}
+
+/// Classify a stored `(xcoord, ycoord)` pair
+///
+/// Returns None if the row is fine, otherwise the issue and the corrected position if one
+/// can be determined. `has_location` is whether the row's `location` column is populated.
+pub fn classify_coordinates(
+    xcoord: f32,
+    ycoord: f32,
+    has_location: bool,
+    reference: Option<&ReferencePosition>,
+) -> Option<(CoordIssue, Option<Coordinate>)> {
+    let as_stored = Coordinate::new(xcoord as f64, ycoord as f64).ok();
+    let swapped = Coordinate::new(ycoord as f64, xcoord as f64).ok();
+
+    let Some(as_stored) = as_stored else {
+        return Some(match swapped {
+            Some(fixed) => (CoordIssue::Swapped, Some(fixed)),
+            None => (CoordIssue::OutOfRange, None),
+        });
+    };
+
+    if let Some(reference) = reference {
+        let distance_m = as_stored.distance_m(&reference.position);
+        if distance_m > reference.max_distance_m {
+            let swapped_is_near = swapped
+                .is_some_and(|s| s.distance_m(&reference.position) <= reference.max_distance_m);
+            return Some(if swapped_is_near {
+                (CoordIssue::Swapped, swapped)
+            } else {
+                (CoordIssue::FarFromReference { distance_m }, None)
+            });
+        }
+    }
+
+    (!has_location).then_some((CoordIssue::MissingLocation, Some(as_stored)))
+}
+
+/// Scan every log for coordinate problems, rewriting fixable rows in one transaction if `apply`
+pub fn repair_coordinates(
+    conn: &mut PgConnection,
+    reference: Option<ReferencePosition>,
+    apply: bool,
+) -> Result<RepairReport, diesel::result::Error> {
+    use crate::schema::logs::dsl::*;
+
+    conn.transaction(|conn| {
+        let rows: Vec<(i32, f32, f32, Option<DbPoint>)> = logs
+            .filter(deleted_at.is_null())
+            .select((id, xcoord, ycoord, location))
+            .order(id)
+            .load(conn)?;
+
+        let repairs: Vec<CoordRepair> = rows
+            .iter()
+            .filter_map(|(row_id, x, y, loc)| {
+                let (issue, fix) = classify_coordinates(*x, *y, loc.is_some(), reference.as_ref())?;
+                Some(CoordRepair {
+                    id: *row_id,
+                    xcoord: *x,
+                    ycoord: *y,
+                    issue,
+                    fix,
+                })
+            })
+            .collect();
+
+        if apply {
+            for repair in &repairs {
+                let Some(fix) = repair.fix else { continue };
+                diesel::update(logs.find(repair.id))
+                    .set((
+                        xcoord.eq(fix.lat() as f32),
+                        ycoord.eq(fix.lon() as f32),
+                        location.eq(DbPoint::from(fix)),
+                    ))
+                    .execute(conn)?;
+            }
+        }
+
+        Ok(RepairReport {
+            scanned: rows.len(),
+            repairs,
+            applied: apply,
+        })
+    })
+}
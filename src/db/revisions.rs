@@ -0,0 +1,94 @@
+//! Change history of logs, kept in the `log_revisions` table
+use diesel::prelude::*;
+
+use crate::db::logs::{lock_log, write_changes};
+use crate::error::DatabaseError;
+use crate::model::revision::{log_snapshot, validate_operator};
+use crate::model::{Log, LogChanges, LogRevision, RevisionAction};
+use crate::schema::log_revisions;
+
+#[derive(Insertable)]
+#[diesel(table_name = log_revisions)]
+struct NewLogRevision<'a> {
+    log_id: i32,
+    action: RevisionAction,
+    old_values: serde_json::Value,
+    new_values: Option<serde_json::Value>,
+    operator: &'a str,
+}
+
+/// Record a change to a log; call inside the transaction that makes it
+pub(crate) fn record_revision(
+    conn: &mut PgConnection,
+    log_id: i32,
+    action: RevisionAction,
+    old: &Log,
+    new: Option<&Log>,
+    operator: &str,
+) -> Result<(), DatabaseError> {
+    let revision = NewLogRevision {
+        log_id,
+        action,
+        old_values: log_snapshot(old)?,
+        new_values: new.map(log_snapshot).transpose()?,
+        operator,
+    };
+    diesel::insert_into(log_revisions::table)
+        .values(&revision)
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Every recorded change to a log, oldest first, including for deleted logs
+pub fn log_history(
+    conn: &mut PgConnection,
+    log_id: i32,
+) -> Result<Vec<LogRevision>, DatabaseError> {
+    Ok(log_revisions::table
+        .filter(log_revisions::log_id.eq(log_id))
+        .order(log_revisions::id)
+        .select(LogRevision::as_select())
+        .load(conn)?)
+}
+
+/// Get a single revision by id
+pub fn get_revision(
+    conn: &mut PgConnection,
+    revision_id: i32,
+) -> Result<LogRevision, DatabaseError> {
+    log_revisions::table
+        .find(revision_id)
+        .select(LogRevision::as_select())
+        .first(conn)
+        .optional()?
+        .ok_or_else(|| DatabaseError::NotFound(format!("revision {}", revision_id)))
+}
+
+/// Put a log back the way it was before `revision_id`, on behalf of `operator`
+///
+/// Every field is rewritten from the revision's `old_values`, a deleted log is
+/// undeleted, and the restore is itself recorded as a revision so it can be undone.
+pub fn restore_revision(
+    conn: &mut PgConnection,
+    revision_id: i32,
+    operator: &str,
+) -> Result<Log, DatabaseError> {
+    validate_operator(operator)?;
+    conn.transaction(|conn| {
+        let revision = get_revision(conn, revision_id)?;
+        let changes = LogChanges::try_from(&revision.old_log()?)?;
+        changes.validate()?;
+
+        let current = lock_log(conn, revision.log_id, true)?;
+        let restored = write_changes(conn, revision.log_id, &changes, Some(None))?;
+        record_revision(
+            conn,
+            revision.log_id,
+            RevisionAction::Restore,
+            &current,
+            Some(&restored),
+            operator,
+        )?;
+        Ok(restored)
+    })
+}
@@ -0,0 +1,154 @@
+//! Ranked full-text search over callsign and comment
+//!
+//! Postgres matches the `search_vector` column with English stemming; the sled and
+//! memory stores match words by prefix and rank them with the same weights, so
+//! `burst` finds "pager bursts" everywhere but `bursts` only finds "burst" on Postgres.
+//! Every query word must match, and a partly typed last word matches as a prefix, so
+//! the search can run on each keystroke.
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::{Bool, Float, Text};
+
+use crate::error::{DatabaseError, Error};
+use crate::model::Log;
+
+/// Weight of a word in the callsign, Postgres' default for weight A
+pub const CALLSIGN_WEIGHT: f32 = 1.0;
+/// Weight of a word in the comment, Postgres' default for weight B
+pub const COMMENT_WEIGHT: f32 = 0.4;
+
+/// A log matching a search, with how well it matched
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub log: Log,
+    /// Higher is better; only comparable between hits of the same search and store
+    pub rank: f32,
+}
+
+/// Lowercase alphanumeric words of `text`, as both queries and indexes split it
+pub fn search_terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Searchable words of a log with their weight, a callsign word outranking a comment one
+pub(crate) fn weighted_terms(log: &Log) -> HashMap<String, f32> {
+    let mut terms = HashMap::new();
+    let fields = [
+        (log.comment.as_deref(), COMMENT_WEIGHT),
+        (log.callsign.as_deref(), CALLSIGN_WEIGHT),
+    ];
+    for (text, weight) in fields {
+        for term in search_terms(text.unwrap_or_default()) {
+            let best = terms.entry(term).or_insert(weight);
+            *best = best.max(weight);
+        }
+    }
+    terms
+}
+
+/// Rank of `log` for the query words `terms`, None unless every word prefixes one of its words
+///
+/// Each query word contributes the weight of the best word it prefixes.
+pub(crate) fn rank_log(log: &Log, terms: &[String]) -> Option<f32> {
+    let words = weighted_terms(log);
+    terms.iter().try_fold(0.0, |rank, term| {
+        let best = words
+            .iter()
+            .filter(|(word, _)| word.starts_with(term.as_str()))
+            .map(|(_, &weight)| weight)
+            .reduce(f32::max)?;
+        Some(rank + best)
+    })
+}
+
+/// Order hits best first, newest first among equal ranks, and keep the first `limit`
+pub(crate) fn best_hits(mut hits: Vec<SearchHit>, limit: i64) -> Vec<SearchHit> {
+    hits.sort_by(|a, b| {
+        b.rank.total_cmp(&a.rank).then_with(|| {
+            Reverse((a.log.timestamp, a.log.id)).cmp(&Reverse((b.log.timestamp, b.log.id)))
+        })
+    });
+    hits.truncate(limit.max(0) as usize);
+    hits
+}
+
+/// Search undeleted logs in Postgres, best match first
+///
+/// Returns nothing for a query without words. Only the words of `query` reach the
+/// tsquery, so punctuation such as `&`, `|`, `!` or `:` is ignored rather than read as
+/// an operator. A negative limit is treated as zero, as in the other stores.
+pub fn search_logs(
+    conn: &mut PgConnection,
+    query: &str,
+    limit: i64,
+) -> Result<Vec<SearchHit>, Error> {
+    Ok(search_rows(conn, query, limit)?)
+}
+
+/// [`search_logs`] for the stores, which report a `DatabaseError`
+pub(crate) fn search_rows(
+    conn: &mut PgConnection,
+    query: &str,
+    limit: i64,
+) -> Result<Vec<SearchHit>, DatabaseError> {
+    use crate::schema::logs::dsl::*;
+
+    let terms = search_terms(query);
+    if terms.is_empty() || limit <= 0 {
+        return Ok(Vec::new());
+    }
+    // Words are alphanumeric, so they cannot smuggle tsquery operators in
+    let tsquery = terms
+        .iter()
+        .map(|term| format!("{}:*", term))
+        .collect::<Vec<_>>()
+        .join(" & ");
+    let matches = sql::<Bool>(r#""logs"."search_vector" @@ to_tsquery('english', "#)
+        .bind::<Text, _>(tsquery.clone())
+        .sql(")");
+    let rank = || {
+        sql::<Float>(r#"ts_rank("logs"."search_vector", to_tsquery('english', "#)
+            .bind::<Text, _>(tsquery.clone())
+            .sql("))")
+    };
+
+    let rows: Vec<(Log, f32)> = logs
+        .filter(deleted_at.is_null())
+        .filter(matches)
+        .select((Log::as_select(), rank()))
+        .order((rank().desc(), timestamp.desc(), id.desc()))
+        .limit(limit)
+        .load(conn)?;
+    Ok(rows
+        .into_iter()
+        .map(|(log, rank)| SearchHit { log, rank })
+        .collect())
+}
+
+/// Search logs held in memory, for stores without an index
+pub(crate) fn search_in<'a>(
+    candidates: impl IntoIterator<Item = &'a Log>,
+    query: &str,
+    limit: i64,
+) -> Vec<SearchHit> {
+    let terms = search_terms(query);
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    let hits = candidates
+        .into_iter()
+        .filter_map(|log| {
+            rank_log(log, &terms).map(|rank| SearchHit {
+                log: log.clone(),
+                rank,
+            })
+        })
+        .collect();
+    best_hits(hits, limit)
+}
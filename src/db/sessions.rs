@@ -0,0 +1,102 @@
+//! Survey sessions, kept in the `sessions` table, and the logs recorded in them
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::error::DatabaseError;
+use crate::model::{Frequency, NewSession, Session, SessionSummary};
+use crate::schema::{logs, sessions};
+
+/// Start a session, returning it with its assigned id
+///
+/// An operator can have several sessions running; [`active_session`] picks the
+/// latest of them.
+pub fn start_session(
+    conn: &mut PgConnection,
+    new_session: &NewSession,
+) -> Result<Session, DatabaseError> {
+    new_session.validate()?;
+    Ok(diesel::insert_into(sessions::table)
+        .values(new_session)
+        .returning(Session::as_returning())
+        .get_result(conn)?)
+}
+
+/// End a running session now, returning the updated row
+///
+/// Stopping a session that has already ended keeps its original end time.
+pub fn stop_session(conn: &mut PgConnection, session_id: i32) -> Result<Session, DatabaseError> {
+    let now = Utc::now();
+    let stopped = diesel::update(
+        sessions::table
+            .find(session_id)
+            .filter(sessions::ended_at.is_null()),
+    )
+    .set(sessions::ended_at.eq(now))
+    .returning(Session::as_returning())
+    .get_result(conn)
+    .optional()?;
+    match stopped {
+        Some(session) => Ok(session),
+        None => get_session(conn, session_id),
+    }
+}
+
+/// Get a single session by id
+pub fn get_session(conn: &mut PgConnection, session_id: i32) -> Result<Session, DatabaseError> {
+    sessions::table
+        .find(session_id)
+        .select(Session::as_select())
+        .first(conn)
+        .optional()?
+        .ok_or_else(|| DatabaseError::NotFound(format!("session {}", session_id)))
+}
+
+/// The most recently started session of `operator` that has not ended
+pub fn active_session(
+    conn: &mut PgConnection,
+    operator: &str,
+) -> Result<Option<Session>, DatabaseError> {
+    Ok(sessions::table
+        .filter(sessions::operator.eq(operator))
+        .filter(sessions::ended_at.is_null())
+        .order((sessions::started_at.desc(), sessions::id.desc()))
+        .select(Session::as_select())
+        .first(conn)
+        .optional()?)
+}
+
+/// The sessions with any of `ids`, in no particular order; unknown ids are left out
+pub fn get_sessions(conn: &mut PgConnection, ids: &[i32]) -> Result<Vec<Session>, DatabaseError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(sessions::table
+        .filter(sessions::id.eq_any(ids))
+        .select(Session::as_select())
+        .load(conn)?)
+}
+
+/// Every session, most recently started first
+pub fn list_sessions(conn: &mut PgConnection) -> Result<Vec<Session>, DatabaseError> {
+    Ok(sessions::table
+        .order((sessions::started_at.desc(), sessions::id.desc()))
+        .select(Session::as_select())
+        .load(conn)?)
+}
+
+/// Count, duration, extent and bands of the logs in a session
+///
+/// Deleted logs are not counted; a running session's duration runs up to `now`.
+pub fn summarize_session(
+    conn: &mut PgConnection,
+    session_id: i32,
+    now: DateTime<Utc>,
+) -> Result<SessionSummary, DatabaseError> {
+    let session = get_session(conn, session_id)?;
+    let positions = logs::table
+        .filter(logs::session_id.eq(session_id))
+        .filter(logs::deleted_at.is_null())
+        .select((logs::frequency, logs::xcoord, logs::ycoord))
+        .load::<(Frequency, f32, f32)>(conn)?;
+    Ok(SessionSummary::from_logs(session, positions, now))
+}
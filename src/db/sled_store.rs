@@ -0,0 +1,358 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sled::Transactional;
+use sled::transaction::{ConflictableTransactionError, TransactionalTree};
+
+use crate::db::db::{LogStore, LogStream};
+use crate::db::filter::{LogCursor, LogFilter, LogPage, paginate};
+use crate::db::logs::{duplicate_uuid, not_found};
+use crate::db::search::{SearchHit, best_hits, search_terms, weighted_terms};
+use crate::error::DatabaseError;
+use crate::model::timestamp::parse_stored_utc;
+use crate::model::{Log, LogChanges, LogUuid, NewLog};
+use crate::spatial::DbPoint;
+
+const LOGS_TREE: &str = "logs";
+const SEARCH_TREE: &str = "search_index";
+const UUID_TREE: &str = "uuid_index";
+
+/// [`LogStore`] in an embedded sled database, for laptops without a server
+///
+/// Logs are stored as JSON under their big-endian id, so iteration is in id order.
+/// The search index maps each word of a log's callsign and comment, a zero byte and
+/// the log's id to the word's weight, so a word prefix is a key prefix. The uuid
+/// index maps each log's uuid to its id, and keeps the uuids of deleted logs, like the
+/// rows Postgres keeps, so a uuid is never stored twice and a deleted log is not merged
+/// back in. Every change writes the logs and both indexes in one transaction, so they
+/// never disagree.
+pub struct SledLogStore {
+    db: sled::Db,
+    logs: sled::Tree,
+    search_index: sled::Tree,
+    uuid_index: sled::Tree,
+}
+
+/// On-disk form of a log; `Log` skips `location` when serialized
+#[derive(Serialize, Deserialize)]
+struct StoredLog {
+    #[serde(flatten)]
+    log: Log,
+    /// Longitude and latitude of `Log::location`
+    position: Option<(f64, f64)>,
+}
+
+impl SledLogStore {
+    /// Open or create the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DatabaseError> {
+        Self::from_db(sled::open(path)?)
+    }
+
+    /// Use an already opened database, e.g. a temporary one
+    pub fn from_db(db: sled::Db) -> Result<Self, DatabaseError> {
+        let logs = db.open_tree(LOGS_TREE)?;
+        backfill_uuids(&logs)?;
+        let store = SledLogStore {
+            search_index: db.open_tree(SEARCH_TREE)?,
+            uuid_index: db.open_tree(UUID_TREE)?,
+            db,
+            logs,
+        };
+        // Deleted logs keep their uuid, so only fewer uuids than logs means a database
+        // from before the index was added
+        if store.uuid_index.len() < store.logs.len() {
+            for entry in store.logs.iter().values() {
+                let log = decode(&entry?)?;
+                store
+                    .uuid_index
+                    .insert(log.uuid.as_bytes(), &log.id.to_be_bytes())?;
+            }
+            store.uuid_index.flush()?;
+        }
+        if store.search_index.is_empty() {
+            // Databases from before search was added
+            for entry in store.logs.iter().values() {
+                let log = decode(&entry?)?;
+                for (term, weight) in weighted_terms(&log) {
+                    store
+                        .search_index
+                        .insert(index_key(&term, log.id), &weight.to_be_bytes())?;
+                }
+            }
+            store.search_index.flush()?;
+        }
+        Ok(store)
+    }
+
+    /// Run `change` as one transaction over the logs and both indexes, then flush
+    ///
+    /// sled may run `change` again if another writer conflicts with it.
+    fn transact<T>(&self, change: impl Fn(&Writer) -> WriteResult<T>) -> Result<T, DatabaseError> {
+        let result = (&self.logs, &self.search_index, &self.uuid_index).transaction(
+            |(logs, search_index, uuid_index)| {
+                change(&Writer {
+                    logs,
+                    search_index,
+                    uuid_index,
+                })
+            },
+        )?;
+        self.db.flush()?;
+        Ok(result)
+    }
+
+    /// Weight of the best word starting with `term` in each log that has one
+    fn term_weights(&self, term: &str) -> Result<HashMap<i32, f32>, DatabaseError> {
+        let mut weights = HashMap::new();
+        for entry in self.search_index.scan_prefix(term.as_bytes()) {
+            let (key, value) = entry?;
+            let (Some(id), Ok(weight)) = (index_key_id(&key), <[u8; 4]>::try_from(&*value)) else {
+                continue;
+            };
+            let best = weights.entry(id).or_insert(f32::MIN);
+            *best = best.max(f32::from_be_bytes(weight));
+        }
+        Ok(weights)
+    }
+
+    fn read(&self, id: i32) -> Result<Option<Log>, DatabaseError> {
+        self.logs
+            .get(id.to_be_bytes())?
+            .map(|bytes| decode(&bytes))
+            .transpose()
+    }
+}
+
+type WriteResult<T> = Result<T, ConflictableTransactionError<DatabaseError>>;
+
+/// The trees of a [`SledLogStore`] inside a transaction
+struct Writer<'a> {
+    logs: &'a TransactionalTree,
+    search_index: &'a TransactionalTree,
+    uuid_index: &'a TransactionalTree,
+}
+
+impl Writer<'_> {
+    /// Store `log`, refusing a uuid another log, live or deleted, already holds
+    fn write(&self, log: &Log) -> WriteResult<()> {
+        if let Some(holder) = self.uuid_index.get(log.uuid.as_bytes())?
+            && *holder != log.id.to_be_bytes()
+        {
+            return Err(ConflictableTransactionError::Abort(duplicate_uuid(
+                &log.uuid,
+            )));
+        }
+        let bytes = encode(log).map_err(ConflictableTransactionError::Abort)?;
+        self.logs.insert(&log.id.to_be_bytes(), bytes)?;
+        self.uuid_index
+            .insert(log.uuid.as_bytes(), &log.id.to_be_bytes())?;
+        Ok(())
+    }
+
+    /// Remove a log, keeping its uuid, and return it, or None if there is no such log
+    fn remove(&self, id: i32) -> WriteResult<Option<Log>> {
+        let Some(removed) = self.logs.remove(&id.to_be_bytes())? else {
+            return Ok(None);
+        };
+        let log = decode(&removed).map_err(ConflictableTransactionError::Abort)?;
+        Ok(Some(log))
+    }
+
+    fn index(&self, log: &Log) -> WriteResult<()> {
+        for (term, weight) in weighted_terms(log) {
+            self.search_index
+                .insert(index_key(&term, log.id), &weight.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn unindex(&self, log: &Log) -> WriteResult<()> {
+        for term in weighted_terms(log).into_keys() {
+            self.search_index.remove(index_key(&term, log.id))?;
+        }
+        Ok(())
+    }
+}
+
+impl LogStore for SledLogStore {
+    fn create(&mut self, new_log: &NewLog) -> Result<Log, DatabaseError> {
+        new_log.validate()?;
+        // generate_id is monotonic across restarts and starts at 0
+        let id = i32::try_from(self.db.generate_id()? + 1)
+            .map_err(|_| DatabaseError::QueryError("log id space exhausted".to_string()))?;
+        let log = new_log.to_log(id);
+        self.transact(|writer| {
+            writer.write(&log)?;
+            writer.index(&log)
+        })?;
+        Ok(log)
+    }
+
+    fn get(&mut self, id: i32) -> Result<Log, DatabaseError> {
+        self.read(id)?.ok_or_else(|| not_found(id))
+    }
+
+    fn get_by_uuids(&mut self, uuids: &[LogUuid]) -> Result<Vec<Log>, DatabaseError> {
+        let mut found = Vec::new();
+        for uuid in uuids {
+            let Some(id) = self.uuid_index.get(uuid.as_bytes())? else {
+                continue;
+            };
+            let id = <[u8; 4]>::try_from(&*id)
+                .map_err(|_| DatabaseError::QueryError("corrupt uuid index".to_string()))?;
+            if let Some(log) = self.read(i32::from_be_bytes(id))? {
+                found.push(log);
+            }
+        }
+        Ok(found)
+    }
+
+    fn query(
+        &mut self,
+        filter: &LogFilter,
+        after: Option<LogCursor>,
+        limit: i64,
+    ) -> Result<LogPage, DatabaseError> {
+        let mut matching = Vec::new();
+        for log in self.stream_all()? {
+            let log = log?;
+            if filter.matches(&log) {
+                matching.push(log);
+            }
+        }
+        Ok(paginate(matching, after, limit))
+    }
+
+    fn update(&mut self, id: i32, changes: &LogChanges) -> Result<Log, DatabaseError> {
+        changes.validate()?;
+        let old = self.get(id)?;
+        let mut log = old.clone();
+        changes.apply(&mut log);
+        self.transact(|writer| {
+            writer.write(&log)?;
+            writer.unindex(&old)?;
+            writer.index(&log)
+        })?;
+        Ok(log)
+    }
+
+    fn update_many(&mut self, updates: &[(i32, LogChanges)]) -> Result<Vec<Log>, DatabaseError> {
+        // Stored and final version of each log, so one changed twice is reindexed once
+        let mut versions: BTreeMap<i32, (Log, Log)> = BTreeMap::new();
+        let mut updated = Vec::with_capacity(updates.len());
+        for (id, changes) in updates {
+            changes.validate()?;
+            if !versions.contains_key(id) {
+                let old = self.get(*id)?;
+                versions.insert(*id, (old.clone(), old));
+            }
+            let (_, log) = versions.get_mut(id).expect("inserted above");
+            changes.apply(log);
+            updated.push(log.clone());
+        }
+        self.transact(|writer| {
+            for (old, log) in versions.values() {
+                writer.write(log)?;
+                writer.unindex(old)?;
+                writer.index(log)?;
+            }
+            Ok(())
+        })?;
+        Ok(updated)
+    }
+
+    fn delete(&mut self, id: i32) -> Result<(), DatabaseError> {
+        self.transact(|writer| {
+            let removed = writer
+                .remove(id)?
+                .ok_or_else(|| ConflictableTransactionError::Abort(not_found(id)))?;
+            writer.unindex(&removed)
+        })
+    }
+
+    fn search(&mut self, query: &str, limit: i64) -> Result<Vec<SearchHit>, DatabaseError> {
+        let mut terms = search_terms(query).into_iter();
+        let Some(first) = terms.next() else {
+            return Ok(Vec::new());
+        };
+        let mut ranks = self.term_weights(&first)?;
+        for term in terms {
+            let weights = self.term_weights(&term)?;
+            ranks.retain(|id, rank| match weights.get(id) {
+                Some(weight) => {
+                    *rank += weight;
+                    true
+                }
+                None => false,
+            });
+        }
+        let mut hits = Vec::with_capacity(ranks.len());
+        for (id, rank) in ranks {
+            if let Some(log) = self.read(id)? {
+                hits.push(SearchHit { log, rank });
+            }
+        }
+        Ok(best_hits(hits, limit))
+    }
+
+    fn stream_all(&mut self) -> Result<LogStream<'_>, DatabaseError> {
+        Ok(Box::new(self.logs.iter().values().map(|value| {
+            let bytes = value?;
+            decode(&bytes)
+        })))
+    }
+}
+
+/// Give logs stored before they carried a uuid one based on their timestamp
+fn backfill_uuids(logs: &sled::Tree) -> Result<(), DatabaseError> {
+    for entry in logs.iter() {
+        let (key, value) = entry?;
+        let mut stored: serde_json::Value = serde_json::from_slice(&value).map_err(json_error)?;
+        if !stored.get("uuid").is_none_or(serde_json::Value::is_null) {
+            continue;
+        }
+        let timestamp = stored["timestamp"].as_str().unwrap_or_default();
+        let timestamp = parse_stored_utc(timestamp)
+            .map_err(|e| DatabaseError::QueryError(format!("timestamp {:?}: {}", timestamp, e)))?;
+        let unix_ms = u64::try_from(timestamp.timestamp_millis()).unwrap_or(0);
+        stored["uuid"] = LogUuid::new_v7_at(unix_ms).to_string().into();
+        logs.insert(key, serde_json::to_vec(&stored).map_err(json_error)?)?;
+    }
+    logs.flush()?;
+    Ok(())
+}
+
+fn index_key(term: &str, id: i32) -> Vec<u8> {
+    let mut key = Vec::with_capacity(term.len() + 5);
+    key.extend_from_slice(term.as_bytes());
+    key.push(0);
+    key.extend_from_slice(&id.to_be_bytes());
+    key
+}
+
+fn index_key_id(key: &[u8]) -> Option<i32> {
+    let id = key.get(key.len().checked_sub(4)?..)?;
+    Some(i32::from_be_bytes(id.try_into().ok()?))
+}
+
+fn json_error(e: serde_json::Error) -> DatabaseError {
+    DatabaseError::QueryError(e.to_string())
+}
+
+fn encode(log: &Log) -> Result<Vec<u8>, DatabaseError> {
+    let stored = StoredLog {
+        log: log.clone(),
+        position: log.location.map(|p| (p.0.x(), p.0.y())),
+    };
+    serde_json::to_vec(&stored).map_err(json_error)
+}
+
+fn decode(bytes: &[u8]) -> Result<Log, DatabaseError> {
+    let stored: StoredLog = serde_json::from_slice(bytes).map_err(json_error)?;
+    let mut log = stored.log;
+    log.location = stored
+        .position
+        .map(|(lon, lat)| DbPoint(geo::Point::new(lon, lat)));
+    Ok(log)
+}
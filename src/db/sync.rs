@@ -0,0 +1,123 @@
+//! Background replay of the capture queue into Postgres
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::db::pool::PgPool;
+use crate::db::queue::CaptureQueue;
+use crate::error::DatabaseError;
+
+/// How often the worker retries when it is not woken
+pub const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Snapshot of the sync state, for display
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncStatus {
+    /// Entries waiting in the queue
+    pub pending: usize,
+    /// Entries Postgres refused
+    pub rejected: usize,
+    /// Whether the last attempt succeeded
+    pub connected: bool,
+    /// Error from the last attempt, cleared by the next successful one
+    pub last_error: Option<String>,
+    /// When the queue was last fully drained
+    pub last_synced: Option<DateTime<Utc>>,
+}
+
+enum SyncSignal {
+    Wake,
+    Stop,
+}
+
+/// Thread that keeps replaying a [`CaptureQueue`] into Postgres
+///
+/// It checks a connection out of the pool for each attempt and retries every
+/// interval or as soon as it is woken. Dropping the worker stops the thread.
+pub struct SyncWorker {
+    signals: Sender<SyncSignal>,
+    status: Arc<Mutex<SyncStatus>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SyncWorker {
+    pub fn spawn(queue: CaptureQueue, pool: PgPool, interval: Duration) -> Self {
+        let (signals, receiver) = mpsc::channel();
+        let status = Arc::new(Mutex::new(SyncStatus {
+            pending: queue.len(),
+            rejected: queue.rejected_len(),
+            ..Default::default()
+        }));
+
+        let thread_status = Arc::clone(&status);
+        let thread = thread::spawn(move || {
+            loop {
+                let result = sync_once(&queue, &pool);
+                update_status(&thread_status, &queue, result);
+
+                match receiver.recv_timeout(interval) {
+                    Ok(SyncSignal::Wake) | Err(RecvTimeoutError::Timeout) => {}
+                    Ok(SyncSignal::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+
+        SyncWorker {
+            signals,
+            status,
+            thread: Some(thread),
+        }
+    }
+
+    /// Ask for a sync attempt now, e.g. right after queueing an entry
+    pub fn wake(&self) {
+        let _ = self.signals.send(SyncSignal::Wake);
+    }
+
+    pub fn status(&self) -> SyncStatus {
+        self.status.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+}
+
+impl Drop for SyncWorker {
+    fn drop(&mut self) {
+        let _ = self.signals.send(SyncSignal::Stop);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Replay the queue once
+///
+/// Also runs with an empty queue, so the status shows whether the server is reachable.
+fn sync_once(queue: &CaptureQueue, pool: &PgPool) -> Result<(), DatabaseError> {
+    let mut conn = pool.get()?;
+    queue.replay(&mut conn)?;
+    Ok(())
+}
+
+fn update_status(
+    status: &Mutex<SyncStatus>,
+    queue: &CaptureQueue,
+    result: Result<(), DatabaseError>,
+) {
+    let Ok(mut status) = status.lock() else {
+        return;
+    };
+    status.pending = queue.len();
+    status.rejected = queue.rejected_len();
+    status.connected = result.is_ok();
+    match result {
+        Ok(()) => {
+            status.last_error = None;
+            if status.pending == 0 {
+                status.last_synced = Some(Utc::now());
+            }
+        }
+        Err(e) => status.last_error = Some(e.to_string()),
+    }
+}
@@ -0,0 +1,50 @@
+//! Tags of logs, kept in the `tags` and `log_tags` tables
+use diesel::prelude::*;
+
+use crate::error::DatabaseError;
+use crate::model::Tag;
+use crate::model::tag::normalize_tags;
+use crate::schema::{log_tags, tags};
+
+/// Replace every tag of a log, creating tags that do not exist yet
+///
+/// Returns the normalized tag names, sorted. Call inside the transaction that writes
+/// the log so a rejected tag leaves the log untouched.
+pub fn set_log_tags<S: AsRef<str>>(
+    conn: &mut PgConnection,
+    log_id: i32,
+    names: &[S],
+) -> Result<Vec<String>, DatabaseError> {
+    let names = normalize_tags(names)?;
+    diesel::delete(log_tags::table.filter(log_tags::log_id.eq(log_id))).execute(conn)?;
+    if names.is_empty() {
+        return Ok(names);
+    }
+
+    let new_tags: Vec<_> = names.iter().map(|name| tags::name.eq(name)).collect();
+    diesel::insert_into(tags::table)
+        .values(&new_tags)
+        .on_conflict(tags::name)
+        .do_nothing()
+        .execute(conn)?;
+    let tag_ids: Vec<i32> = tags::table
+        .filter(tags::name.eq_any(&names))
+        .select(tags::id)
+        .load(conn)?;
+    let links: Vec<_> = tag_ids
+        .into_iter()
+        .map(|tag_id| (log_tags::log_id.eq(log_id), log_tags::tag_id.eq(tag_id)))
+        .collect();
+    diesel::insert_into(log_tags::table)
+        .values(&links)
+        .execute(conn)?;
+    Ok(names)
+}
+
+/// Every tag in use or ever used, by name
+pub fn list_tags(conn: &mut PgConnection) -> Result<Vec<Tag>, DatabaseError> {
+    Ok(tags::table
+        .order(tags::name)
+        .select(Tag::as_select())
+        .load(conn)?)
+}
@@ -0,0 +1,154 @@
+//! Database access off the UI thread
+//!
+//! A [`DbWorker`] owns the [`LogStore`] inside a tokio runtime. The UI sends
+//! [`DbCommand`]s and polls for [`DbReply`]s between frames, so a slow query or an
+//! unreachable server never stops it from drawing or handling keys.
+use std::time::Duration;
+
+use tokio::runtime::{self, Runtime};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task;
+
+use crate::db::db::LogStore;
+use crate::db::filter::{LogCursor, LogFilter, LogPage};
+use crate::db::queue::QueuedLog;
+use crate::db::search::SearchHit;
+use crate::db::sync::SyncStatus;
+use crate::error::DatabaseError;
+use crate::model::{Log, Session};
+
+/// How often the worker reports [`LogStore::sync_status`]
+pub const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Work the UI asks of the store
+#[derive(Debug, Clone)]
+pub enum DbCommand {
+    CreateLog(QueuedLog),
+    QueryLogs {
+        filter: LogFilter,
+        after: Option<LogCursor>,
+        limit: i64,
+    },
+    SearchLogs {
+        query: String,
+        limit: i64,
+    },
+    LoadActiveSession,
+}
+
+/// Result of a [`DbCommand`], or a periodic status report
+#[derive(Debug)]
+pub enum DbReply {
+    LogCreated(Result<Log, DatabaseError>),
+    /// A page of logs; `after` is the cursor it was requested with
+    LogsLoaded {
+        after: Option<LogCursor>,
+        page: Result<LogPage, DatabaseError>,
+    },
+    /// Matches for `query`, best first
+    SearchResults {
+        query: String,
+        hits: Result<Vec<SearchHit>, DatabaseError>,
+    },
+    ActiveSession(Result<Option<Session>, DatabaseError>),
+    SyncStatus(Option<SyncStatus>),
+}
+
+/// Handle to a store running on its own tokio runtime
+///
+/// Commands run one at a time, in the order they were sent. Dropping the handle
+/// stops the worker and closes the store.
+pub struct DbWorker {
+    commands: UnboundedSender<DbCommand>,
+    replies: UnboundedReceiver<DbReply>,
+    _runtime: Runtime,
+}
+
+impl DbWorker {
+    pub fn spawn(store: Box<dyn LogStore>) -> Result<Self, DatabaseError> {
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("sdr-db")
+            .enable_time()
+            .build()
+            .map_err(|e| DatabaseError::ConnectionError(e.to_string()))?;
+        let (commands, command_receiver) = mpsc::unbounded_channel();
+        let (reply_sender, replies) = mpsc::unbounded_channel();
+        runtime.spawn(serve(store, command_receiver, reply_sender));
+
+        Ok(DbWorker {
+            commands,
+            replies,
+            _runtime: runtime,
+        })
+    }
+
+    /// Queue a command; its reply arrives through [`DbWorker::try_recv`]
+    pub fn send(&self, command: DbCommand) -> Result<(), DatabaseError> {
+        self.commands
+            .send(command)
+            .map_err(|_| DatabaseError::ConnectionError("database worker stopped".to_string()))
+    }
+
+    /// The next reply, if one is ready
+    pub fn try_recv(&mut self) -> Option<DbReply> {
+        self.replies.try_recv().ok()
+    }
+}
+
+async fn serve(
+    mut store: Box<dyn LogStore>,
+    mut commands: UnboundedReceiver<DbCommand>,
+    replies: UnboundedSender<DbReply>,
+) {
+    let mut status_ticks = tokio::time::interval(STATUS_INTERVAL);
+    loop {
+        let reply = tokio::select! {
+            command = commands.recv() => {
+                let Some(command) = command else {
+                    break;
+                };
+                // Diesel and sled block, so keep them off the runtime's worker thread
+                let finished = task::spawn_blocking(move || {
+                    let reply = execute(store.as_mut(), command);
+                    (store, reply)
+                })
+                .await;
+                let Ok((returned, reply)) = finished else {
+                    break;
+                };
+                store = returned;
+                reply
+            }
+            _ = status_ticks.tick() => DbReply::SyncStatus(store.sync_status()),
+        };
+        if replies.send(reply).is_err() {
+            break;
+        }
+    }
+}
+
+fn execute(store: &mut dyn LogStore, command: DbCommand) -> DbReply {
+    match command {
+        DbCommand::CreateLog(entry) => {
+            let new_log = entry.as_new_log();
+            DbReply::LogCreated(match &entry.equipment {
+                Some(equipment) => store.create_with_equipment(&new_log, equipment),
+                None => store.create(&new_log),
+            })
+        }
+        DbCommand::QueryLogs {
+            filter,
+            after,
+            limit,
+        } => DbReply::LogsLoaded {
+            after,
+            page: store.query(&filter, after, limit),
+        },
+        DbCommand::SearchLogs { query, limit } => DbReply::SearchResults {
+            hits: store.search(&query, limit),
+            query,
+        },
+        DbCommand::LoadActiveSession => DbReply::ActiveSession(store.active_session()),
+    }
+}
@@ -6,18 +6,73 @@ pub enum ValidationError {
     InvalidLatitude(f64),
     InvalidLongitude(f64),
     InvalidFrequency(f64),
+    InvalidFrequencyInput(String),
+    InvalidBandwidth(f64),
     InvalidTimestamp(String),
     InvalidBoundingBox(String),
+    InvalidGridSquare(String),
+    InvalidGeometry(String),
     InvalidRecordingDuration(f32),
+    InvalidMode(String),
+    InvalidPower(f64),
+    InvalidSnr(f64),
+    InvalidAltitude(f64),
+    InvalidPositionAccuracy(f64),
+    InvalidHeading(f64),
+    InvalidSpeed(f64),
+    InvalidNumber {
+        field: String,
+        input: String,
+    },
+    InvalidUuid(String),
+    InvalidOperator(String),
+    InvalidSessionName(String),
+    InvalidEquipment(String),
+    InvalidEquipmentProfile(String),
+    InvalidTag(String),
+    InvalidFilter(String),
+    /// A record in an imported file that could not be read; `line` counts from 1
+    InvalidRecord {
+        line: usize,
+        reason: String,
+    },
+    /// A feature in an imported GeoJSON file that could not be read; `index` counts
+    /// from 0, as in the `features` array
+    InvalidFeature {
+        index: usize,
+        reason: String,
+    },
+    UnsupportedFormat(String),
     EmptyDataset,
 }
 
+/// Errors from SDR devices and recorded spectrum files
+#[derive(Debug)]
+pub enum SourceError {
+    StartError(String),
+    StopError(String),
+    DeviceError(String),
+    IOError(std::io::Error),
+    StreamError(String),
+}
+
+/// Any error the crate returns, for callers that do not need to tell the layers apart
+#[derive(Debug)]
+pub enum Error {
+    Validation(ValidationError),
+    Database(DatabaseError),
+    Source(SourceError),
+    Io(std::io::Error),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DatabaseError {
     ConnectionError(String),
     QueryError(String),
     NotFound(String),
     DuplicateEntry(String),
+    /// The data was rejected before reaching the database
+    InvalidData(ValidationError),
 }
 
 impl fmt::Display for ValidationError {
@@ -36,15 +91,122 @@ impl fmt::Display for ValidationError {
             ValidationError::InvalidFrequency(freq) => {
                 write!(f, "Invalid frequency: {} (must be positive)", freq)
             }
+            ValidationError::InvalidFrequencyInput(input) => {
+                write!(
+                    f,
+                    "Invalid frequency: {:?} (expected e.g. 146.52M, 7.074 MHz, 433920k or 2.45e9)",
+                    input
+                )
+            }
+            ValidationError::InvalidBandwidth(bw) => {
+                write!(f, "Invalid bandwidth: {} (must be positive)", bw)
+            }
             ValidationError::InvalidTimestamp(msg) => {
                 write!(f, "Invalid timestamp: {}", msg)
             }
             ValidationError::InvalidBoundingBox(msg) => {
                 write!(f, "Invalid bounding box: {}", msg)
             }
+            ValidationError::InvalidGridSquare(locator) => {
+                write!(
+                    f,
+                    "Invalid grid square: {:?} (expected a Maidenhead locator like IO91wm)",
+                    locator
+                )
+            }
+            ValidationError::InvalidGeometry(msg) => {
+                write!(f, "Invalid geometry: {}", msg)
+            }
             ValidationError::EmptyDataset => {
                 write!(f, "Dataset is empty")
             }
+            ValidationError::InvalidMode(mode) => {
+                write!(f, "Invalid mode: {:?} (must be 1-20 characters)", mode)
+            }
+            ValidationError::InvalidPower(power) => {
+                write!(f, "Invalid power: {} dBm (must be finite)", power)
+            }
+            ValidationError::InvalidSnr(snr) => {
+                write!(f, "Invalid SNR: {} dB (must be finite)", snr)
+            }
+            ValidationError::InvalidAltitude(altitude) => {
+                write!(
+                    f,
+                    "Invalid altitude: {} m (must be between -1000 and 100000)",
+                    altitude
+                )
+            }
+            ValidationError::InvalidPositionAccuracy(accuracy) => {
+                write!(
+                    f,
+                    "Invalid position accuracy: {} m (must not be negative)",
+                    accuracy
+                )
+            }
+            ValidationError::InvalidHeading(heading) => {
+                write!(
+                    f,
+                    "Invalid heading: {} (must be at least 0 and below 360)",
+                    heading
+                )
+            }
+            ValidationError::InvalidSpeed(speed) => {
+                write!(f, "Invalid speed: {} m/s (must not be negative)", speed)
+            }
+            ValidationError::InvalidNumber { field, input } => {
+                write!(f, "Invalid {}: {:?} is not a number", field, input)
+            }
+            ValidationError::InvalidUuid(input) => {
+                write!(f, "Invalid UUID: {:?} (expected 32 hex digits)", input)
+            }
+            ValidationError::InvalidSessionName(name) => {
+                write!(
+                    f,
+                    "Invalid session name: {:?} (must be 1-100 characters)",
+                    name
+                )
+            }
+            ValidationError::InvalidEquipment(equipment) => {
+                write!(
+                    f,
+                    "Invalid equipment description: {:?} (must be at most 200 characters)",
+                    equipment
+                )
+            }
+            ValidationError::InvalidEquipmentProfile(reason) => {
+                write!(f, "Invalid equipment profile: {}", reason)
+            }
+            ValidationError::InvalidTag(tag) => {
+                write!(
+                    f,
+                    "Invalid tag: {:?} (must be 1-50 characters without spaces or commas)",
+                    tag
+                )
+            }
+            ValidationError::InvalidFilter(term) => {
+                write!(
+                    f,
+                    "Invalid filter: {:?} (expected key=value with key one of {})",
+                    term,
+                    crate::db::filter::FILTER_KEYS.join(", ")
+                )
+            }
+            ValidationError::InvalidRecord { line, reason } => {
+                write!(f, "Invalid record on line {}: {}", line, reason)
+            }
+            ValidationError::InvalidFeature { index, reason } => {
+                write!(f, "Invalid feature at index {}: {}", index, reason)
+            }
+            ValidationError::UnsupportedFormat(msg) => {
+                write!(f, "Unsupported format: {}", msg)
+            }
+            ValidationError::InvalidOperator(operator) => {
+                write!(
+                    f,
+                    "Invalid operator name: {:?} (must be 1-50 characters)",
+                    operator
+                )
+            }
             ValidationError::InvalidRecordingDuration(duration) => {
                 write!(
                     f,
@@ -57,3 +219,159 @@ impl fmt::Display for ValidationError {
 }
 
 impl std::error::Error for ValidationError {}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatabaseError::ConnectionError(msg) => write!(f, "Connection error: {}", msg),
+            DatabaseError::QueryError(msg) => write!(f, "Query error: {}", msg),
+            DatabaseError::NotFound(what) => write!(f, "Not found: {}", what),
+            DatabaseError::DuplicateEntry(msg) => write!(f, "Duplicate entry: {}", msg),
+            DatabaseError::InvalidData(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {}
+
+impl From<ValidationError> for DatabaseError {
+    fn from(e: ValidationError) -> Self {
+        DatabaseError::InvalidData(e)
+    }
+}
+
+impl From<diesel::result::Error> for DatabaseError {
+    fn from(e: diesel::result::Error) -> Self {
+        use diesel::result::{DatabaseErrorKind, Error};
+
+        match e {
+            Error::NotFound => DatabaseError::NotFound("record".to_string()),
+            Error::DatabaseError(DatabaseErrorKind::UniqueViolation, info) => {
+                DatabaseError::DuplicateEntry(info.message().to_string())
+            }
+            Error::DatabaseError(DatabaseErrorKind::ClosedConnection, info) => {
+                DatabaseError::ConnectionError(info.message().to_string())
+            }
+            e => DatabaseError::QueryError(e.to_string()),
+        }
+    }
+}
+
+impl From<diesel::ConnectionError> for DatabaseError {
+    fn from(e: diesel::ConnectionError) -> Self {
+        DatabaseError::ConnectionError(e.to_string())
+    }
+}
+
+impl From<sled::Error> for DatabaseError {
+    fn from(e: sled::Error) -> Self {
+        match e {
+            sled::Error::Io(e) => DatabaseError::ConnectionError(e.to_string()),
+            e => DatabaseError::QueryError(e.to_string()),
+        }
+    }
+}
+
+impl From<sled::transaction::TransactionError<DatabaseError>> for DatabaseError {
+    fn from(e: sled::transaction::TransactionError<DatabaseError>) -> Self {
+        match e {
+            sled::transaction::TransactionError::Abort(e) => e,
+            sled::transaction::TransactionError::Storage(e) => e.into(),
+        }
+    }
+}
+
+impl From<diesel::r2d2::PoolError> for DatabaseError {
+    fn from(e: diesel::r2d2::PoolError) -> Self {
+        DatabaseError::ConnectionError(e.to_string())
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::StartError(msg) => write!(f, "Source Start Error: {}", msg),
+            SourceError::StopError(msg) => write!(f, "Source Stop Error: {}", msg),
+            SourceError::DeviceError(msg) => write!(f, "Source Device Error: {}", msg),
+            SourceError::IOError(err) => write!(f, "Source IO Error: {}", err),
+            SourceError::StreamError(msg) => write!(f, "Source Stream Error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SourceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SourceError::IOError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SourceError {
+    fn from(e: std::io::Error) -> Self {
+        SourceError::IOError(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Validation(e) => write!(f, "{}", e),
+            Error::Database(e) => write!(f, "{}", e),
+            Error::Source(e) => write!(f, "{}", e),
+            Error::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Validation(e) => Some(e),
+            Error::Database(e) => Some(e),
+            Error::Source(e) => Some(e),
+            Error::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(e: ValidationError) -> Self {
+        Error::Validation(e)
+    }
+}
+
+/// Rejected data stays a validation error however deep it was caught
+impl From<DatabaseError> for Error {
+    fn from(e: DatabaseError) -> Self {
+        match e {
+            DatabaseError::InvalidData(e) => Error::Validation(e),
+            e => Error::Database(e),
+        }
+    }
+}
+
+impl From<SourceError> for Error {
+    fn from(e: SourceError) -> Self {
+        Error::Source(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<diesel::result::Error> for Error {
+    fn from(e: diesel::result::Error) -> Self {
+        Error::Database(e.into())
+    }
+}
+
+impl From<diesel::ConnectionError> for Error {
+    fn from(e: diesel::ConnectionError) -> Self {
+        Error::Database(e.into())
+    }
+}
@@ -0,0 +1,521 @@
+//! ADIF, the Amateur Data Interchange Format of logging software, as tagged `.adi`
+//! text and `.adx` XML
+//!
+//! Each log is a QSO record: callsign, UTC date and time, frequency, mode and comment
+//! map to their ADIF fields and the receiver position to `MY_LAT`, `MY_LON` and
+//! `MY_GRIDSQUARE`. Values ADIF has no field for, or would round, go in
+//! application-defined `APP_SDRDB_*` fields, so a file written here reads back
+//! unchanged while other programs still see the standard fields.
+use std::io::{self, Write};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Timelike, Utc};
+
+use crate::error::ValidationError;
+use crate::formats::{content_uuid, imported_log, xml};
+use crate::model::{Frequency, FrequencyUnit, Log, SignalMode, parse_timestamp};
+use crate::model::{LogTimeZone, tag::parse_tags};
+use crate::spatial::{Coordinate, grid_square, grid_square_center};
+
+/// ADIF version the writers follow
+pub const ADIF_VERSION: &str = "3.1.4";
+
+/// Program id in the header and in the names of application-defined fields
+pub const PROGRAM_ID: &str = "SDRDB";
+
+/// ADIF band names and their edges in Hz, both inclusive
+const BANDS: [(&str, i64, i64); 32] = [
+    ("2190m", 135_700, 137_800),
+    ("630m", 472_000, 479_000),
+    ("560m", 501_000, 504_000),
+    ("160m", 1_800_000, 2_000_000),
+    ("80m", 3_500_000, 4_000_000),
+    ("60m", 5_060_000, 5_450_000),
+    ("40m", 7_000_000, 7_300_000),
+    ("30m", 10_100_000, 10_150_000),
+    ("20m", 14_000_000, 14_350_000),
+    ("17m", 18_068_000, 18_168_000),
+    ("15m", 21_000_000, 21_450_000),
+    ("12m", 24_890_000, 24_990_000),
+    ("10m", 28_000_000, 29_700_000),
+    ("8m", 40_000_000, 45_000_000),
+    ("6m", 50_000_000, 54_000_000),
+    ("5m", 54_000_001, 69_900_000),
+    ("4m", 70_000_000, 71_000_000),
+    ("2m", 144_000_000, 148_000_000),
+    ("1.25m", 222_000_000, 225_000_000),
+    ("70cm", 420_000_000, 450_000_000),
+    ("33cm", 902_000_000, 928_000_000),
+    ("23cm", 1_240_000_000, 1_300_000_000),
+    ("13cm", 2_300_000_000, 2_450_000_000),
+    ("9cm", 3_300_000_000, 3_500_000_000),
+    ("6cm", 5_650_000_000, 5_925_000_000),
+    ("3cm", 10_000_000_000, 10_500_000_000),
+    ("1.25cm", 24_000_000_000, 24_250_000_000),
+    ("6mm", 47_000_000_000, 47_200_000_000),
+    ("4mm", 75_500_000_000, 81_000_000_000),
+    ("2.5mm", 119_980_000_000, 123_000_000_000),
+    ("2mm", 134_000_000_000, 149_000_000_000),
+    ("1mm", 241_000_000_000, 250_000_000_000),
+];
+
+/// ADIF band of `frequency`, e.g. `2m`, None outside the amateur bands
+pub fn adif_band(frequency: Frequency) -> Option<&'static str> {
+    BANDS
+        .iter()
+        .find(|(_, low, high)| (*low..=*high).contains(&frequency.hz()))
+        .map(|(name, _, _)| *name)
+}
+
+/// ADIF `MODE` and `SUBMODE` for `mode`
+///
+/// Modes ADIF has no enumeration for get the nearest mode or none; their exact name
+/// is kept in `APP_SDRDB_MODE`.
+pub fn adif_mode(mode: &SignalMode) -> (Option<&str>, Option<&str>) {
+    match mode {
+        SignalMode::FM | SignalMode::NFM | SignalMode::WFM => (Some("FM"), None),
+        SignalMode::AM => (Some("AM"), None),
+        SignalMode::USB => (Some("SSB"), Some("USB")),
+        SignalMode::LSB => (Some("SSB"), Some("LSB")),
+        SignalMode::CW => (Some("CW"), None),
+        SignalMode::DMR => (Some("DIGITALVOICE"), Some("DMR")),
+        SignalMode::P25 => (Some("DIGITALVOICE"), None),
+        SignalMode::DStar => (Some("DSTAR"), None),
+        SignalMode::FT8 => (Some("FT8"), None),
+        SignalMode::RTTY => (Some("RTTY"), None),
+        SignalMode::APRS => (Some("PKT"), None),
+        SignalMode::ADSB | SignalMode::Digital => (None, None),
+        SignalMode::Other(name) => (Some(name.as_str()), None),
+    }
+}
+
+/// The mode an ADIF `MODE` and `SUBMODE` describe
+///
+/// `SSB` without a submode is read as the sideband conventional at `frequency`: lower
+/// below 10 MHz, upper above.
+pub fn signal_mode(
+    mode: &str,
+    submode: Option<&str>,
+    frequency: Frequency,
+) -> Result<SignalMode, ValidationError> {
+    let mode = mode.trim().to_ascii_uppercase();
+    let submode = submode.map(|s| s.trim().to_ascii_uppercase());
+    Ok(match (mode.as_str(), submode.as_deref()) {
+        ("SSB", Some("LSB")) => SignalMode::LSB,
+        ("SSB", Some("USB")) => SignalMode::USB,
+        ("SSB", _) if frequency < Frequency::from_mhz(10.0) => SignalMode::LSB,
+        ("SSB", _) => SignalMode::USB,
+        ("DIGITALVOICE", Some("DMR")) => SignalMode::DMR,
+        ("DIGITALVOICE", Some("DSTAR")) => SignalMode::DStar,
+        ("DIGITALVOICE", None) => SignalMode::Digital,
+        ("PKT", None) => SignalMode::APRS,
+        (_, Some(submode)) => submode.parse()?,
+        (mode, None) => mode.parse()?,
+    })
+}
+
+/// Degrees as an ADIF location, `XDDD MM.MMM`, e.g. `N051 30.444`
+pub fn adif_location(degrees: f64, positive: char, negative: char) -> String {
+    let hemisphere = if degrees < 0.0 { negative } else { positive };
+    let thousandths = (degrees.abs() * 60_000.0).round() as i64;
+    format!(
+        "{}{:03} {:06.3}",
+        hemisphere,
+        thousandths / 60_000,
+        (thousandths % 60_000) as f64 / 1000.0
+    )
+}
+
+/// Degrees of an ADIF location, also accepting plain decimal degrees
+pub fn parse_adif_location(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if let Ok(degrees) = value.parse::<f64>() {
+        return Some(degrees);
+    }
+    let sign = match value.chars().next()?.to_ascii_uppercase() {
+        'N' | 'E' => 1.0,
+        'S' | 'W' => -1.0,
+        _ => return None,
+    };
+    let (degrees, minutes) = value[1..].trim().split_once(' ')?;
+    let degrees: f64 = degrees.parse().ok()?;
+    let minutes: f64 = minutes.trim().parse().ok()?;
+    (0.0..60.0)
+        .contains(&minutes)
+        .then_some(sign * (degrees + minutes / 60.0))
+}
+
+/// A record's fields, names uppercase, in file order
+#[derive(Debug, Default)]
+struct Record {
+    /// Line the record starts on
+    line: usize,
+    fields: Vec<(String, String)>,
+}
+
+impl Record {
+    fn push(&mut self, name: &str, value: impl Into<String>) {
+        self.fields.push((name.to_ascii_uppercase(), value.into()));
+    }
+
+    /// Trimmed value of field `name`, None if absent or blank
+    fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn app(&self, name: &str) -> Option<&str> {
+        self.get(&format!("APP_{}_{}", PROGRAM_ID, name))
+    }
+
+    fn invalid(&self, reason: impl ToString) -> ValidationError {
+        ValidationError::InvalidRecord {
+            line: self.line,
+            reason: reason.to_string(),
+        }
+    }
+
+    fn parse<T: std::str::FromStr>(&self, value: &str) -> Result<T, ValidationError> {
+        value
+            .parse()
+            .map_err(|_| self.invalid(format!("{:?} is not a number", value)))
+    }
+
+    fn number<T: std::str::FromStr>(
+        &self,
+        value: Option<&str>,
+    ) -> Result<Option<T>, ValidationError> {
+        value.map(|value| self.parse(value)).transpose()
+    }
+
+    fn from_log(log: &Log) -> Self {
+        let mut record = Record::default();
+        let app = |name: &str| format!("APP_{}_{}", PROGRAM_ID, name);
+
+        if let Some(callsign) = log.callsign.as_deref().filter(|c| !c.trim().is_empty()) {
+            record.push("CALL", callsign);
+        }
+        record.push("QSO_DATE", log.timestamp.format("%Y%m%d").to_string());
+        record.push("TIME_ON", log.timestamp.format("%H%M%S").to_string());
+        record.push("FREQ", mhz(log.frequency));
+        if let Some(band) = adif_band(log.frequency) {
+            record.push("BAND", band);
+        }
+        let (mode, submode) = adif_mode(&log.mode);
+        if let Some(mode) = mode {
+            record.push("MODE", mode);
+        }
+        if let Some(submode) = submode {
+            record.push("SUBMODE", submode);
+        }
+        let lat = log.xcoord as f64;
+        let lon = log.ycoord as f64;
+        record.push("MY_LAT", adif_location(lat, 'N', 'S'));
+        record.push("MY_LON", adif_location(lon, 'E', 'W'));
+        if let Ok(position) = Coordinate::new(lat, lon) {
+            record.push("MY_GRIDSQUARE", grid_square(&position, 3));
+        }
+        if let Some(altitude) = log.altitude_m {
+            record.push("MY_ALTITUDE", altitude.to_string());
+        }
+        if let Some(comment) = log.comment.as_deref().filter(|c| !c.is_empty()) {
+            record.push("COMMENT", comment);
+        }
+
+        record.push(&app("UUID"), log.uuid.to_string());
+        record.push(&app("LAT"), log.xcoord.to_string());
+        record.push(&app("LON"), log.ycoord.to_string());
+        if log.timestamp.nanosecond() != 0 {
+            record.push(
+                &app("TIMESTAMP"),
+                log.timestamp.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
+            );
+        }
+        let mode_survives = mode
+            .and_then(|mode| signal_mode(mode, submode, log.frequency).ok())
+            .is_some_and(|read| read == log.mode);
+        if !mode_survives {
+            record.push(&app("MODE"), log.mode.to_string());
+        }
+        if log.recording_duration != 0.0 {
+            record.push(&app("DURATION"), log.recording_duration.to_string());
+        }
+        let details = [
+            ("POWER_DBM", log.power_dbm),
+            ("SNR_DB", log.snr_db),
+            ("ACCURACY_M", log.position_accuracy_m),
+            ("HEADING_DEG", log.heading_deg),
+            ("SPEED_MPS", log.speed_mps),
+        ];
+        for (name, value) in details {
+            if let Some(value) = value {
+                record.push(&app(name), value.to_string());
+            }
+        }
+        if let Some(bandwidth) = log.bandwidth_hz {
+            record.push(&app("BANDWIDTH_HZ"), bandwidth.hz().to_string());
+        }
+        if !log.tags.is_empty() {
+            record.push(&app("TAGS"), log.tags.join(","));
+        }
+        record
+    }
+
+    fn to_log(&self) -> Result<Log, ValidationError> {
+        let frequency = self
+            .get("FREQ")
+            .ok_or_else(|| self.invalid("FREQ is missing"))?;
+        let frequency = Frequency::parse_with_default_unit(frequency, FrequencyUnit::MHz)
+            .map_err(|e| self.invalid(e))?;
+
+        let timestamp = match self.app("TIMESTAMP") {
+            Some(timestamp) => {
+                parse_timestamp(timestamp, LogTimeZone::utc()).map_err(|e| self.invalid(e))?
+            }
+            None => self.qso_time()?,
+        };
+
+        let mode = match (self.app("MODE"), self.get("MODE")) {
+            (Some(mode), _) => mode.parse(),
+            (None, Some(mode)) => signal_mode(mode, self.get("SUBMODE"), frequency),
+            (None, None) => return Err(self.invalid("MODE is missing")),
+        }
+        .map_err(|e| self.invalid(e))?;
+
+        let mut log = imported_log(frequency, self.position()?, mode, timestamp);
+        log.callsign = self.get("CALL").map(str::to_string);
+        log.comment = self.get("COMMENT").map(str::to_string);
+        if let Some(duration) = self.number(self.app("DURATION"))? {
+            log.recording_duration = duration;
+        }
+        log.altitude_m = self.number(self.get("MY_ALTITUDE"))?;
+        log.power_dbm = self.number(self.app("POWER_DBM"))?;
+        log.snr_db = self.number(self.app("SNR_DB"))?;
+        log.position_accuracy_m = self.number(self.app("ACCURACY_M"))?;
+        log.heading_deg = self.number(self.app("HEADING_DEG"))?;
+        log.speed_mps = self.number(self.app("SPEED_MPS"))?;
+        log.bandwidth_hz = self
+            .number::<i64>(self.app("BANDWIDTH_HZ"))?
+            .map(Frequency::from_hz);
+        if let Some(tags) = self.app("TAGS") {
+            log.tags = parse_tags(tags).map_err(|e| self.invalid(e))?;
+        }
+        log.uuid = match self.app("UUID") {
+            Some(uuid) => uuid.parse().map_err(|e| self.invalid(e))?,
+            None => content_uuid(&log),
+        };
+        Ok(log)
+    }
+
+    /// `QSO_DATE` and `TIME_ON`, which ADIF keeps in UTC
+    fn qso_time(&self) -> Result<DateTime<Utc>, ValidationError> {
+        let date = self
+            .get("QSO_DATE")
+            .ok_or_else(|| self.invalid("QSO_DATE is missing"))?;
+        let date = NaiveDate::parse_from_str(date, "%Y%m%d")
+            .map_err(|_| self.invalid(format!("QSO_DATE {:?} is not YYYYMMDD", date)))?;
+        let time = self
+            .get("TIME_ON")
+            .ok_or_else(|| self.invalid("TIME_ON is missing"))?;
+        let layout = if time.len() == 4 { "%H%M" } else { "%H%M%S" };
+        let time = NaiveTime::parse_from_str(time, layout)
+            .map_err(|_| self.invalid(format!("TIME_ON {:?} is not HHMM or HHMMSS", time)))?;
+        Ok(date.and_time(time).and_utc())
+    }
+
+    /// The exact position if written here, else `MY_LAT`/`MY_LON`, else the centre of
+    /// `MY_GRIDSQUARE`
+    fn position(&self) -> Result<Coordinate, ValidationError> {
+        let exact = (self.app("LAT"), self.app("LON"));
+        let adif = (self.get("MY_LAT"), self.get("MY_LON"));
+        let (lat, lon) = match (exact, adif) {
+            ((Some(lat), Some(lon)), _) => (self.parse(lat)?, self.parse(lon)?),
+            (_, (Some(lat), Some(lon))) => (self.location(lat)?, self.location(lon)?),
+            _ => {
+                let locator = self
+                    .get("MY_GRIDSQUARE")
+                    .ok_or_else(|| self.invalid("MY_LAT/MY_LON or MY_GRIDSQUARE is missing"))?;
+                return grid_square_center(locator).map_err(|e| self.invalid(e));
+            }
+        };
+        Coordinate::new(lat, lon).map_err(|e| self.invalid(e))
+    }
+
+    fn location(&self, value: &str) -> Result<f64, ValidationError> {
+        parse_adif_location(value)
+            .ok_or_else(|| self.invalid(format!("{:?} is not a location", value)))
+    }
+}
+
+/// Frequency in MHz without trailing zeros, exact to the Hz
+fn mhz(frequency: Frequency) -> String {
+    let hz = frequency.hz();
+    let (whole, fraction) = (hz / 1_000_000, hz % 1_000_000);
+    if fraction == 0 {
+        whole.to_string()
+    } else {
+        let fraction = format!("{:06}", fraction);
+        format!("{}.{}", whole, fraction.trim_end_matches('0'))
+    }
+}
+
+/// Write `.adi`: a header, then one line of `<NAME:length>value` fields per log
+///
+/// Lengths count UTF-8 bytes.
+pub fn write_adi(logs: &[Log], out: &mut impl Write) -> io::Result<()> {
+    let field = |name: &str, value: &str| format!("<{}:{}>{}", name, value.len(), value);
+    writeln!(out, "ADIF export from sdr_db")?;
+    writeln!(
+        out,
+        "{} {} {} <EOH>",
+        field("ADIF_VER", ADIF_VERSION),
+        field("PROGRAMID", PROGRAM_ID),
+        field("PROGRAMVERSION", env!("CARGO_PKG_VERSION"))
+    )?;
+    for log in logs {
+        let fields: Vec<String> = Record::from_log(log)
+            .fields
+            .iter()
+            .map(|(name, value)| field(name, value))
+            .collect();
+        writeln!(out, "{} <EOR>", fields.join(" "))?;
+    }
+    Ok(())
+}
+
+/// Read `.adi` records, skipping the header if there is one
+///
+/// # Errors
+/// Returns `ValidationError::InvalidRecord` for a malformed tag or a record without a
+/// frequency, time, mode or position
+pub fn read_adi(input: &[u8]) -> Result<Vec<Log>, ValidationError> {
+    adi_records(input)?.iter().map(Record::to_log).collect()
+}
+
+fn adi_records(input: &[u8]) -> Result<Vec<Record>, ValidationError> {
+    let mut records = Vec::new();
+    let mut record = Record::default();
+    let mut pos = 0;
+    let mut line = 1;
+    let invalid = |line: usize, reason: &str| ValidationError::InvalidRecord {
+        line,
+        reason: reason.to_string(),
+    };
+
+    while let Some(offset) = input[pos..].iter().position(|&b| b == b'<') {
+        line += input[pos..pos + offset]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        let start = pos + offset + 1;
+        let close = input[start..]
+            .iter()
+            .position(|&b| b == b'>')
+            .ok_or_else(|| invalid(line, "unclosed tag"))?;
+        let tag = std::str::from_utf8(&input[start..start + close])
+            .map_err(|_| invalid(line, "tag is not text"))?;
+        pos = start + close + 1;
+
+        let mut parts = tag.split(':');
+        let name = parts.next().unwrap_or_default().trim().to_ascii_uppercase();
+        let Some(length) = parts.next() else {
+            match name.as_str() {
+                // Fields before the end of the header describe the file, not a log
+                "EOH" => record = Record::default(),
+                "EOR" => records.push(std::mem::take(&mut record)),
+                _ => return Err(invalid(line, &format!("<{}> has no length", tag))),
+            }
+            continue;
+        };
+        let length: usize = length
+            .trim()
+            .parse()
+            .map_err(|_| invalid(line, &format!("<{}> has no length", tag)))?;
+        let value = pos
+            .checked_add(length)
+            .and_then(|end| input.get(pos..end))
+            .ok_or_else(|| invalid(line, &format!("{} runs past the end", name)))?;
+        let value = String::from_utf8(value.to_vec())
+            .map_err(|_| invalid(line, &format!("{} is not UTF-8", name)))?;
+        if record.fields.is_empty() {
+            record.line = line;
+        }
+        line += value.matches('\n').count();
+        record.push(&name, value);
+        pos += length;
+    }
+    Ok(records)
+}
+
+/// Write `.adx`: an `ADX` document with a `RECORD` element per log
+pub fn write_adx(logs: &[Log], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(out, "<ADX>")?;
+    writeln!(out, "  <HEADER>")?;
+    writeln!(out, "    <ADIF_VER>{}</ADIF_VER>", ADIF_VERSION)?;
+    writeln!(out, "    <PROGRAMID>{}</PROGRAMID>", PROGRAM_ID)?;
+    writeln!(
+        out,
+        "    <PROGRAMVERSION>{}</PROGRAMVERSION>",
+        env!("CARGO_PKG_VERSION")
+    )?;
+    writeln!(out, "  </HEADER>")?;
+    writeln!(out, "  <RECORDS>")?;
+    let app_prefix = format!("APP_{}_", PROGRAM_ID);
+    for log in logs {
+        writeln!(out, "    <RECORD>")?;
+        for (name, value) in Record::from_log(log).fields {
+            let value = xml::escape(&value);
+            match name.strip_prefix(&app_prefix) {
+                Some(field) => writeln!(
+                    out,
+                    r#"      <APP PROGRAMID="{}" FIELDNAME="{}" TYPE="S">{}</APP>"#,
+                    PROGRAM_ID, field, value
+                )?,
+                None => writeln!(out, "      <{0}>{1}</{0}>", name, value)?,
+            }
+        }
+        writeln!(out, "    </RECORD>")?;
+    }
+    writeln!(out, "  </RECORDS>")?;
+    writeln!(out, "</ADX>")
+}
+
+/// Read the records of an `.adx` document
+///
+/// # Errors
+/// Returns `ValidationError::InvalidRecord` for malformed XML, a document that is not
+/// ADX, or a record [`read_adi`] would also reject
+pub fn read_adx(input: &str) -> Result<Vec<Log>, ValidationError> {
+    let root = xml::parse(input)?;
+    if !root.is("ADX") {
+        return Err(ValidationError::InvalidRecord {
+            line: root.line,
+            reason: format!("root element is <{}>, not <ADX>", root.name),
+        });
+    }
+    let Some(records) = root.child("RECORDS") else {
+        return Ok(Vec::new());
+    };
+    records
+        .children_named("RECORD")
+        .map(|element| {
+            let mut record = Record {
+                line: element.line,
+                fields: Vec::new(),
+            };
+            for field in &element.children {
+                if field.is("APP") {
+                    let program = field.attribute("PROGRAMID").unwrap_or_default();
+                    let name = field.attribute("FIELDNAME").unwrap_or_default();
+                    record.push(&format!("APP_{}_{}", program, name), field.text.clone());
+                } else {
+                    record.push(field.local_name(), field.text.clone());
+                }
+            }
+            record.to_log()
+        })
+        .collect()
+}
@@ -0,0 +1,267 @@
+//! Logs as comma-separated values with a header row, quoted as in RFC 4180
+//!
+//! Columns are found by their header name, so files from other tools may leave out
+//! or reorder columns; only `timestamp`, `frequency_hz`, `latitude`, `longitude` and
+//! `mode` are required.
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use crate::error::ValidationError;
+use crate::formats::{content_uuid, imported_log};
+use crate::model::{Frequency, Log, LogTimeZone, SignalMode, parse_timestamp};
+use crate::spatial::Coordinate;
+
+/// Header row written by [`write_logs`]
+pub const COLUMNS: [&str; 19] = [
+    "id",
+    "uuid",
+    "timestamp",
+    "frequency_hz",
+    "latitude",
+    "longitude",
+    "callsign",
+    "mode",
+    "comment",
+    "recording_duration",
+    "power_dbm",
+    "snr_db",
+    "bandwidth_hz",
+    "altitude_m",
+    "position_accuracy_m",
+    "heading_deg",
+    "speed_mps",
+    "session_id",
+    "tags",
+];
+
+/// Separator of the tag names in the `tags` column
+const TAG_SEPARATOR: char = ';';
+
+pub fn write_logs(logs: &[Log], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "{}", COLUMNS.join(","))?;
+    for log in logs {
+        let optional = |value: Option<f32>| value.map(|v| v.to_string()).unwrap_or_default();
+        let fields = [
+            log.id.to_string(),
+            log.uuid.to_string(),
+            log.timestamp.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
+            log.frequency.hz().to_string(),
+            log.xcoord.to_string(),
+            log.ycoord.to_string(),
+            log.callsign.clone().unwrap_or_default(),
+            log.mode.to_string(),
+            log.comment.clone().unwrap_or_default(),
+            log.recording_duration.to_string(),
+            optional(log.power_dbm),
+            optional(log.snr_db),
+            log.bandwidth_hz
+                .map(|bw| bw.hz().to_string())
+                .unwrap_or_default(),
+            optional(log.altitude_m),
+            optional(log.position_accuracy_m),
+            optional(log.heading_deg),
+            optional(log.speed_mps),
+            log.session_id.map(|id| id.to_string()).unwrap_or_default(),
+            log.tags.join(&TAG_SEPARATOR.to_string()),
+        ];
+        let fields: Vec<Cow<str>> = fields.iter().map(|field| escape(field)).collect();
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+/// Read logs from CSV with a header row, reading times without an offset in `zone`
+///
+/// # Errors
+/// Returns `ValidationError::InvalidRecord` for a missing required column, unbalanced
+/// quotes or a value that cannot be read
+pub fn read_logs(input: &str, zone: LogTimeZone) -> Result<Vec<Log>, ValidationError> {
+    let mut records = records(input)?.into_iter();
+    let Some((_, header)) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+    };
+    let columns: Vec<Option<usize>> = COLUMNS.iter().map(|name| column(name)).collect();
+    for required in ["timestamp", "frequency_hz", "latitude", "longitude", "mode"] {
+        if column(required).is_none() {
+            return Err(ValidationError::InvalidRecord {
+                line: 1,
+                reason: format!("missing column {:?}", required),
+            });
+        }
+    }
+
+    records
+        .map(|(line, fields)| {
+            let row = Row {
+                line,
+                fields: &fields,
+                columns: &columns,
+            };
+            row.to_log(zone)
+        })
+        .collect()
+}
+
+/// One data record and where each known column sits in it
+struct Row<'a> {
+    line: usize,
+    fields: &'a [String],
+    /// Field index of each of [`COLUMNS`], None if the file lacks it
+    columns: &'a [Option<usize>],
+}
+
+impl Row<'_> {
+    /// Trimmed value of column `name`, None if it is absent or blank
+    fn get(&self, name: &str) -> Option<&str> {
+        let index = COLUMNS.iter().position(|c| *c == name)?;
+        let value = self.fields.get(self.columns[index]?)?.trim();
+        (!value.is_empty()).then_some(value)
+    }
+
+    fn invalid(&self, reason: impl ToString) -> ValidationError {
+        ValidationError::InvalidRecord {
+            line: self.line,
+            reason: reason.to_string(),
+        }
+    }
+
+    fn required(&self, name: &str) -> Result<&str, ValidationError> {
+        self.get(name)
+            .ok_or_else(|| self.invalid(format!("{} is empty", name)))
+    }
+
+    fn number<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, ValidationError> {
+        self.get(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| self.invalid(format!("{} {:?} is not a number", name, value)))
+            })
+            .transpose()
+    }
+
+    fn frequency(&self, name: &str) -> Result<Option<Frequency>, ValidationError> {
+        self.get(name)
+            .map(|value| value.parse::<Frequency>().map_err(|e| self.invalid(e)))
+            .transpose()
+    }
+
+    fn to_log(&self, zone: LogTimeZone) -> Result<Log, ValidationError> {
+        let timestamp =
+            parse_timestamp(self.required("timestamp")?, zone).map_err(|e| self.invalid(e))?;
+        let frequency = self
+            .frequency("frequency_hz")?
+            .ok_or_else(|| self.invalid("frequency_hz is empty"))?;
+        let lat = self.number("latitude")?;
+        let lon = self.number("longitude")?;
+        let (Some(lat), Some(lon)) = (lat, lon) else {
+            return Err(self.invalid("latitude and longitude are required"));
+        };
+        let position = Coordinate::new(lat, lon).map_err(|e| self.invalid(e))?;
+        let mode: SignalMode = self
+            .required("mode")?
+            .parse()
+            .map_err(|e| self.invalid(e))?;
+
+        let mut log = imported_log(frequency, position, mode, timestamp);
+        if let Some(id) = self.number("id")? {
+            log.id = id;
+        }
+        log.callsign = self.get("callsign").map(str::to_string);
+        log.comment = self.get("comment").map(str::to_string);
+        if let Some(duration) = self.number("recording_duration")? {
+            log.recording_duration = duration;
+        }
+        log.power_dbm = self.number("power_dbm")?;
+        log.snr_db = self.number("snr_db")?;
+        log.bandwidth_hz = self.frequency("bandwidth_hz")?;
+        log.altitude_m = self.number("altitude_m")?;
+        log.position_accuracy_m = self.number("position_accuracy_m")?;
+        log.heading_deg = self.number("heading_deg")?;
+        log.speed_mps = self.number("speed_mps")?;
+        log.session_id = self.number("session_id")?;
+        log.tags = self
+            .get("tags")
+            .map(|tags| {
+                tags.split(TAG_SEPARATOR)
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        log.uuid = match self.get("uuid") {
+            Some(uuid) => uuid.parse().map_err(|e| self.invalid(e))?,
+            None => content_uuid(&log),
+        };
+        Ok(log)
+    }
+}
+
+/// Quote a field if it holds a separator, quote or line break
+pub fn escape(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Split CSV text into records, each with the line it starts on
+///
+/// Blank lines are skipped. Quoted fields may hold separators, doubled quotes and
+/// line breaks.
+pub fn records(input: &str) -> Result<Vec<(usize, Vec<String>)>, ValidationError> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut start_line = 1;
+    let mut quoted = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            _ if quoted => field.push(c),
+            ',' => fields.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if !(fields.len() == 1 && fields[0].is_empty()) {
+                    records.push((start_line, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                start_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(ValidationError::InvalidRecord {
+            line: start_line,
+            reason: "unterminated quoted field".to_string(),
+        });
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((start_line, fields));
+    }
+    Ok(records)
+}
@@ -0,0 +1,431 @@
+//! GeoJSON FeatureCollections of logs, measurements and location aggregates
+//!
+//! Each feature is a `[longitude, latitude]` point with every other field as a
+//! property; nested objects such as an aggregate's power statistics are flattened to
+//! `power_min`, `power_max` and so on, which GIS tools display as plain columns.
+//! Features are written one at a time through a [`FeatureWriter`], so a collection of
+//! any size is streamed rather than built in memory.
+use std::io::{self, Write};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::db::filter::LogFilter;
+use crate::error::ValidationError;
+use crate::formats::{content_uuid, imported_log};
+use crate::model::{Frequency, Log, LogTimeZone, SignalMode, parse_timestamp};
+use crate::spatial::{Coordinate, Layer, LocationAggregate, Measurement};
+
+/// Writes a FeatureCollection one feature at a time
+///
+/// The collection is only valid once [`finish`](FeatureWriter::finish) has closed it.
+pub struct FeatureWriter<W: Write> {
+    out: W,
+    count: usize,
+}
+
+impl<W: Write> FeatureWriter<W> {
+    /// Start a collection on `out`
+    pub fn new(mut out: W) -> io::Result<Self> {
+        out.write_all(br#"{"type":"FeatureCollection","features":["#)?;
+        Ok(Self { out, count: 0 })
+    }
+
+    /// Number of features written so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Write a point feature at `[lon, lat]` with `properties`
+    ///
+    /// The coordinates are serialized as given, so `f32` positions keep their
+    /// shortest form.
+    pub fn write<T: Serialize>(
+        &mut self,
+        lon: T,
+        lat: T,
+        properties: &Map<String, Value>,
+    ) -> io::Result<()> {
+        self.out
+            .write_all(if self.count == 0 { b"\n" } else { b",\n" })?;
+        let feature = Feature {
+            kind: "Feature",
+            geometry: Point {
+                kind: "Point",
+                coordinates: [lon, lat],
+            },
+            properties,
+        };
+        serde_json::to_writer(&mut self.out, &feature)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Write a log at its position, with its other fields as properties
+    pub fn write_log(&mut self, log: &Log) -> io::Result<()> {
+        let mut properties = properties(log, &["xcoord", "ycoord"])?;
+        // An explicit UTC offset, so other tools do not read the time as local
+        properties.insert(
+            "timestamp".to_string(),
+            Value::String(log.timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+        );
+        self.write(log.ycoord, log.xcoord, &properties)
+    }
+
+    pub fn write_measurement(&mut self, measurement: &Measurement) -> io::Result<()> {
+        let properties = properties(measurement, &["location"])?;
+        let location = measurement.location;
+        self.write(location.lon(), location.lat(), &properties)
+    }
+
+    pub fn write_aggregate(&mut self, aggregate: &LocationAggregate) -> io::Result<()> {
+        let properties = properties(aggregate, &["location"])?;
+        let location = aggregate.location;
+        self.write(location.lon(), location.lat(), &properties)
+    }
+
+    /// Close the collection and return the output
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.write_all(b"\n]}\n")?;
+        Ok(self.out)
+    }
+}
+
+#[derive(Serialize)]
+struct Feature<'a, T> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    geometry: Point<T>,
+    properties: &'a Map<String, Value>,
+}
+
+#[derive(Serialize)]
+struct Point<T> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    coordinates: [T; 2],
+}
+
+/// The fields of `value` except `skip`, with nested objects flattened
+fn properties(value: &impl Serialize, skip: &[&str]) -> io::Result<Map<String, Value>> {
+    let Value::Object(fields) = serde_json::to_value(value)? else {
+        return Ok(Map::new());
+    };
+    let mut properties = Map::new();
+    for (name, value) in fields {
+        if skip.contains(&name.as_str()) {
+            continue;
+        }
+        match value {
+            Value::Object(nested) => {
+                for (key, value) in nested {
+                    properties.insert(format!("{}_{}", name, key), value);
+                }
+            }
+            value => {
+                properties.insert(name, value);
+            }
+        }
+    }
+    Ok(properties)
+}
+
+/// Write the logs `filter` matches, or all of them, returning how many were written
+pub fn write_logs<'a>(
+    logs: impl IntoIterator<Item = &'a Log>,
+    filter: Option<&LogFilter>,
+    out: &mut impl Write,
+) -> io::Result<usize> {
+    let mut features = FeatureWriter::new(out)?;
+    for log in logs {
+        if filter.is_none_or(|filter| filter.matches(log)) {
+            features.write_log(log)?;
+        }
+    }
+    let count = features.count();
+    features.finish()?;
+    Ok(count)
+}
+
+/// Write the measurements of `layer` that `filter` accepts, or all of them
+pub fn write_layer(
+    layer: &Layer,
+    filter: Option<&dyn Fn(&Measurement) -> bool>,
+    out: &mut impl Write,
+) -> io::Result<usize> {
+    let mut features = FeatureWriter::new(out)?;
+    for measurement in layer.measurements() {
+        if filter.is_none_or(|filter| filter(measurement)) {
+            features.write_measurement(measurement)?;
+        }
+    }
+    let count = features.count();
+    features.finish()?;
+    Ok(count)
+}
+
+/// Write the aggregates `filter` accepts, or all of them
+pub fn write_aggregates(
+    aggregates: &[LocationAggregate],
+    filter: Option<&dyn Fn(&LocationAggregate) -> bool>,
+    out: &mut impl Write,
+) -> io::Result<usize> {
+    let mut features = FeatureWriter::new(out)?;
+    for aggregate in aggregates {
+        if filter.is_none_or(|filter| filter(aggregate)) {
+            features.write_aggregate(aggregate)?;
+        }
+    }
+    let count = features.count();
+    features.finish()?;
+    Ok(count)
+}
+
+/// Read the logs in a FeatureCollection or single Feature
+///
+/// `timestamp`, `frequency` in Hz and `mode` properties are required; the rest may be
+/// missing, as in features drawn in another tool. The point geometry is the position,
+/// so moving a feature moves the log. A timestamp without an offset is read in `zone`.
+///
+/// # Errors
+/// Returns `ValidationError::InvalidRecord` if the text is not a GeoJSON object, and
+/// `ValidationError::InvalidFeature` for the first feature that cannot be read
+pub fn read_logs(input: &str, zone: LogTimeZone) -> Result<Vec<Log>, ValidationError> {
+    features(input)?
+        .iter()
+        .map(|feature| feature.to_log(zone))
+        .collect()
+}
+
+/// Read measurements written by [`write_layer`]
+///
+/// # Errors
+/// As for [`read_logs`], plus the errors of [`Measurement::new`] as
+/// `ValidationError::InvalidFeature`
+pub fn read_layer(input: &str) -> Result<Layer, ValidationError> {
+    features(input)?
+        .iter()
+        .map(PointFeature::to_measurement)
+        .collect::<Result<Vec<_>, _>>()
+        .map(Layer::new)
+}
+
+/// The features of a collection, or the one feature the document is
+fn features(input: &str) -> Result<Vec<PointFeature>, ValidationError> {
+    let mut document: Value =
+        serde_json::from_str(input).map_err(|e| ValidationError::InvalidRecord {
+            line: e.line(),
+            reason: e.to_string(),
+        })?;
+    let invalid = |reason: &str| ValidationError::InvalidRecord {
+        line: 1,
+        reason: reason.to_string(),
+    };
+    let kind = document
+        .get("type")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let features = match kind.as_deref() {
+        Some("FeatureCollection") => match document["features"].take() {
+            Value::Array(features) => features,
+            _ => return Err(invalid("the collection has no features array")),
+        },
+        Some("Feature") => vec![document],
+        _ => return Err(invalid("expected a FeatureCollection or Feature")),
+    };
+    features
+        .into_iter()
+        .enumerate()
+        .map(|(index, feature)| PointFeature::new(index, feature))
+        .collect()
+}
+
+/// A feature's position and properties
+struct PointFeature {
+    index: usize,
+    position: Coordinate,
+    /// Third coordinate of the point, if it has one
+    altitude: Option<f64>,
+    properties: Map<String, Value>,
+}
+
+impl PointFeature {
+    fn new(index: usize, mut feature: Value) -> Result<Self, ValidationError> {
+        let invalid = |reason: String| ValidationError::InvalidFeature { index, reason };
+        let geometry = feature
+            .get("geometry")
+            .filter(|geometry| !geometry.is_null())
+            .ok_or_else(|| invalid("it has no geometry".to_string()))?;
+        match geometry.get("type").and_then(Value::as_str) {
+            Some("Point") => {}
+            kind => {
+                return Err(invalid(format!(
+                    "its geometry is a {}, not a Point",
+                    kind.unwrap_or("value without a type")
+                )));
+            }
+        }
+        let coordinates: Vec<f64> = geometry
+            .get("coordinates")
+            .and_then(Value::as_array)
+            .map(|values| values.iter().filter_map(Value::as_f64).collect())
+            .unwrap_or_default();
+        let [lon, lat, rest @ ..] = coordinates.as_slice() else {
+            return Err(invalid(
+                "the point needs a longitude and latitude".to_string(),
+            ));
+        };
+        let position = Coordinate::new(*lat, *lon).map_err(|e| invalid(e.to_string()))?;
+        let altitude = rest.first().copied();
+        let properties = match feature["properties"].take() {
+            Value::Object(properties) => properties,
+            _ => Map::new(),
+        };
+        Ok(Self {
+            index,
+            position,
+            altitude,
+            properties,
+        })
+    }
+
+    fn invalid(&self, reason: impl ToString) -> ValidationError {
+        ValidationError::InvalidFeature {
+            index: self.index,
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Property `name`, None if it is absent, null or a blank string
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.properties
+            .get(name)
+            .filter(|value| !value.is_null())
+            .filter(|value| value.as_str().is_none_or(|text| !text.trim().is_empty()))
+    }
+
+    fn text(&self, name: &str) -> Result<Option<&str>, ValidationError> {
+        self.get(name)
+            .map(|value| {
+                value
+                    .as_str()
+                    .map(str::trim)
+                    .ok_or_else(|| self.invalid(format!("{} is not text", name)))
+            })
+            .transpose()
+    }
+
+    fn required(&self, name: &str) -> Result<&str, ValidationError> {
+        self.text(name)?
+            .ok_or_else(|| self.invalid(format!("{} is missing", name)))
+    }
+
+    /// A number, or text holding one as spreadsheets sometimes leave it
+    fn number<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, ValidationError> {
+        self.get(name)
+            .map(|value| {
+                let text = match value {
+                    Value::String(text) => text.trim().to_string(),
+                    value => value.to_string(),
+                };
+                text.parse()
+                    .map_err(|_| self.invalid(format!("{} {} is not a number", name, value)))
+            })
+            .transpose()
+    }
+
+    /// A number of Hz, or text with a unit like `146.52 MHz`
+    fn frequency(&self, name: &str) -> Result<Option<Frequency>, ValidationError> {
+        match self.get(name) {
+            Some(Value::String(text)) => text
+                .parse::<Frequency>()
+                .map(Some)
+                .map_err(|e| self.invalid(e)),
+            Some(_) => Ok(self.number::<f64>(name)?.map(Frequency::from_hz_f64)),
+            None => Ok(None),
+        }
+    }
+
+    fn to_log(&self, zone: LogTimeZone) -> Result<Log, ValidationError> {
+        let timestamp =
+            parse_timestamp(self.required("timestamp")?, zone).map_err(|e| self.invalid(e))?;
+        let frequency = self
+            .frequency("frequency")?
+            .ok_or_else(|| self.invalid("frequency is missing"))?;
+        let mode: SignalMode = self
+            .required("mode")?
+            .parse()
+            .map_err(|e| self.invalid(e))?;
+
+        let mut log = imported_log(frequency, self.position, mode, timestamp);
+        if let Some(id) = self.number("id")? {
+            log.id = id;
+        }
+        log.callsign = self.text("callsign")?.map(str::to_string);
+        log.comment = self.text("comment")?.map(str::to_string);
+        if let Some(duration) = self.number("recording_duration")? {
+            log.recording_duration = duration;
+        }
+        log.power_dbm = self.number("power_dbm")?;
+        log.snr_db = self.number("snr_db")?;
+        log.bandwidth_hz = self.frequency("bandwidth_hz")?;
+        log.altitude_m = self
+            .number("altitude_m")?
+            .or(self.altitude.map(|altitude| altitude as f32));
+        log.position_accuracy_m = self.number("position_accuracy_m")?;
+        log.heading_deg = self.number("heading_deg")?;
+        log.speed_mps = self.number("speed_mps")?;
+        log.session_id = self.number("session_id")?;
+        log.equipment_profile_id = self.number("equipment_profile_id")?;
+        log.tags = self.tags()?;
+        log.uuid = match self.text("uuid")? {
+            Some(uuid) => uuid.parse().map_err(|e| self.invalid(e))?,
+            None => content_uuid(&log),
+        };
+        Ok(log)
+    }
+
+    /// An array of names, or one comma separated string
+    fn tags(&self) -> Result<Vec<String>, ValidationError> {
+        let names: Vec<&str> = match self.get("tags") {
+            None => Vec::new(),
+            Some(Value::String(text)) => text.split(',').collect(),
+            Some(Value::Array(values)) => values
+                .iter()
+                .map(|value| {
+                    value
+                        .as_str()
+                        .ok_or_else(|| self.invalid("tags must be text"))
+                })
+                .collect::<Result<_, _>>()?,
+            Some(_) => return Err(self.invalid("tags must be an array of names")),
+        };
+        Ok(names
+            .into_iter()
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn to_measurement(&self) -> Result<Measurement, ValidationError> {
+        let timestamp: DateTime<Utc> = self
+            .required("timestamp")?
+            .parse()
+            .map_err(|_| self.invalid("timestamp is not an RFC 3339 time"))?;
+        let required = |name: &str| {
+            self.number::<f64>(name)?
+                .ok_or_else(|| self.invalid(format!("{} is missing", name)))
+        };
+        Measurement::new(
+            self.position,
+            timestamp,
+            required("frequency")?,
+            required("power")?,
+            required("bandwidth")?,
+            required("snr")?,
+        )
+        .map_err(|e| self.invalid(e))
+    }
+}
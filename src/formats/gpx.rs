@@ -0,0 +1,85 @@
+//! GPS tracks from GPX 1.0 and 1.1 files, for placing logs by their time
+//!
+//! Only track points (`<trkpt>`) are read, one [`Track`] segment per `<trkseg>`.
+//! Points without a `<time>` cannot be matched to a log and are skipped; waypoints and
+//! routes carry no times and are ignored.
+use chrono::{DateTime, Utc};
+
+use crate::error::ValidationError;
+use crate::formats::xml::{self, Element};
+use crate::spatial::{Coordinate, Track, TrackPoint};
+
+/// Read every timed track point in a GPX document
+///
+/// # Errors
+/// Returns `ValidationError::InvalidRecord` with the line of the first point whose
+/// position or time cannot be read, or if the document is not GPX or has no timed
+/// track points
+pub fn read_track(input: &str) -> Result<Track, ValidationError> {
+    let root = xml::parse(input)?;
+    if !root.is("gpx") {
+        return Err(ValidationError::InvalidRecord {
+            line: root.line,
+            reason: format!("root element is <{}>, not <gpx>", root.name),
+        });
+    }
+    let mut segments = Vec::new();
+    for track in root.children_named("trk") {
+        for segment in track.children_named("trkseg") {
+            let mut points = Vec::new();
+            for element in segment.children_named("trkpt") {
+                if let Some(point) = track_point(element)? {
+                    points.push(point);
+                }
+            }
+            segments.push(points);
+        }
+    }
+    let track = Track::new(segments);
+    if track.is_empty() {
+        return Err(ValidationError::InvalidRecord {
+            line: root.line,
+            reason: "no track points with a time".to_string(),
+        });
+    }
+    Ok(track)
+}
+
+/// The point `<trkpt>` holds, None if it has no time
+fn track_point(element: &Element) -> Result<Option<TrackPoint>, ValidationError> {
+    let invalid = |reason: String| ValidationError::InvalidRecord {
+        line: element.line,
+        reason,
+    };
+    let degrees = |name: &str| -> Result<f64, ValidationError> {
+        let value = element
+            .attribute(name)
+            .ok_or_else(|| invalid(format!("track point without {}", name)))?;
+        value
+            .trim()
+            .parse()
+            .map_err(|_| invalid(format!("{} {:?} is not a number", name, value)))
+    };
+    let position =
+        Coordinate::new(degrees("lat")?, degrees("lon")?).map_err(|e| invalid(e.to_string()))?;
+
+    let Some(time) = element.child_text("time") else {
+        return Ok(None);
+    };
+    let time = DateTime::parse_from_rfc3339(time)
+        .map_err(|_| invalid(format!("time {:?} is not an RFC 3339 time", time)))?
+        .with_timezone(&Utc);
+    let elevation_m = element
+        .child_text("ele")
+        .map(|ele| {
+            ele.parse()
+                .map_err(|_| invalid(format!("elevation {:?} is not a number", ele)))
+        })
+        .transpose()?;
+
+    Ok(Some(TrackPoint {
+        position,
+        time,
+        elevation_m,
+    }))
+}
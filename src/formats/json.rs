@@ -0,0 +1,26 @@
+//! Logs as a JSON array of objects with the fields of [`Log`]
+use std::io::{self, Write};
+
+use crate::error::ValidationError;
+use crate::model::Log;
+
+pub fn write_logs(logs: &[Log], out: &mut impl Write) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, logs)?;
+    writeln!(out)
+}
+
+pub fn write_log(log: &Log, out: &mut impl Write) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, log)?;
+    writeln!(out)
+}
+
+/// Read an array written by [`write_logs`]
+///
+/// `id`, `session_id` and `equipment_profile_id` are kept but only mean something in
+/// the database the file came from.
+pub fn read_logs(input: &str) -> Result<Vec<Log>, ValidationError> {
+    serde_json::from_str(input).map_err(|e| ValidationError::InvalidRecord {
+        line: e.line(),
+        reason: e.to_string(),
+    })
+}
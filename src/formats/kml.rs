@@ -0,0 +1,331 @@
+//! Logs as KML placemarks for Google Earth, plain or zipped as KMZ
+//!
+//! Placemarks are grouped into a folder per session or band and coloured by signal
+//! mode or received power; their balloons list the fields [`render_log`] prints. Each
+//! placemark's time span starts when it was logged and stays open, so dragging Google
+//! Earth's time slider replays a drive test as it was recorded. KML is write only.
+//!
+//! [`render_log`]: crate::model::model::render_log
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::error::ValidationError;
+use crate::formats::{xml, zip};
+use crate::model::model::detail_lines;
+use crate::model::{Band, Log, SignalMode};
+
+/// What placemarks are grouped into folders by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KmlFolders {
+    /// A folder per survey session, then one for logs outside any session
+    #[default]
+    Session,
+    /// A folder per ITU band, see [`Band`]
+    Band,
+}
+
+/// What placemark colours show
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KmlColours {
+    /// A colour per signal mode
+    #[default]
+    Mode,
+    /// Received power from blue (weak) to red (strong), grey where it was not measured
+    Power,
+}
+
+/// Layout of an exported KML document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KmlOptions {
+    pub folders: KmlFolders,
+    pub colours: KmlColours,
+    /// Session folder names by session id; sessions missing here are named by id
+    pub session_names: BTreeMap<i32, String>,
+}
+
+/// Name of the document inside a KMZ archive, which Google Earth opens
+pub const KMZ_DOCUMENT: &str = "doc.kml";
+
+/// Placemark colour as `0xRRGGBB` per entry of [`SignalMode::ALL`]
+const MODE_COLOURS: [u32; 15] = [
+    0xe6194b, 0x3cb44b, 0xffe119, 0x4363d8, 0xf58231, 0x911eb4, 0x42d4f4, 0xf032e6, 0xbfef45,
+    0xfabed4, 0x469990, 0xdcbeff, 0x9a6324, 0x800000, 0xaaffc3,
+];
+/// Colour of modes without a dedicated variant, and of logs without a power reading
+const DEFAULT_COLOUR: u32 = 0x9e9e9e;
+
+/// Lower bound in dBm, colour and legend of each power step, weakest first
+const POWER_STEPS: [(f32, u32, &str); 5] = [
+    (f32::NEG_INFINITY, 0x2b83ba, "below -100 dBm"),
+    (-100.0, 0xabdda4, "-100 to -85 dBm"),
+    (-85.0, 0xffffbf, "-85 to -70 dBm"),
+    (-70.0, 0xfdae61, "-70 to -55 dBm"),
+    (-55.0, 0xd7191c, "-55 dBm and above"),
+];
+
+const ICON: &str = "https://maps.google.com/mapfiles/kml/shapes/placemark_circle.png";
+
+/// Write `logs` as a KML document
+pub fn write_kml(logs: &[Log], options: &KmlOptions, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(out, r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#)?;
+    writeln!(out, "<Document>")?;
+    writeln!(out, "  <name>SDR logs</name>")?;
+    writeln!(out, "  <open>1</open>")?;
+
+    let mut styles: BTreeMap<String, (u32, String)> = BTreeMap::new();
+    for log in logs {
+        let (id, colour, legend) = style(log, options.colours);
+        styles.entry(id).or_insert((colour, legend));
+    }
+    for (id, (colour, legend)) in &styles {
+        write_style(id, *colour, legend, out)?;
+    }
+
+    for (name, mut logs) in folders(logs, options) {
+        logs.sort_by_key(|log| (log.timestamp, log.id));
+        writeln!(out, "  <Folder>")?;
+        writeln!(out, "    <name>{}</name>", xml::escape(&name))?;
+        for log in logs {
+            write_placemark(log, &style(log, options.colours).0, out)?;
+        }
+        writeln!(out, "  </Folder>")?;
+    }
+
+    writeln!(out, "</Document>")?;
+    writeln!(out, "</kml>")
+}
+
+/// Write `logs` as a KMZ archive holding the document [`write_kml`] writes
+///
+/// The document is built in memory before it is zipped, as the archive headers need
+/// its length and checksum.
+pub fn write_kmz(logs: &[Log], options: &KmlOptions, out: &mut impl Write) -> io::Result<()> {
+    let mut kml = Vec::new();
+    write_kml(logs, options, &mut kml)?;
+    zip::write_stored(&[(KMZ_DOCUMENT, &kml)], out)
+}
+
+/// Logs grouped by folder name, folders in session id or band order
+fn folders<'a>(logs: &'a [Log], options: &KmlOptions) -> Vec<(String, Vec<&'a Log>)> {
+    match options.folders {
+        KmlFolders::Session => {
+            // Logs outside a session sort last
+            let mut grouped: BTreeMap<(bool, Option<i32>), Vec<&Log>> = BTreeMap::new();
+            for log in logs {
+                let key = (log.session_id.is_none(), log.session_id);
+                grouped.entry(key).or_default().push(log);
+            }
+            grouped
+                .into_iter()
+                .map(|((_, session), logs)| {
+                    let name = match session {
+                        Some(id) => match options.session_names.get(&id) {
+                            Some(name) => name.clone(),
+                            None => format!("Session {}", id),
+                        },
+                        None => "No session".to_string(),
+                    };
+                    (name, logs)
+                })
+                .collect()
+        }
+        KmlFolders::Band => {
+            let mut grouped: BTreeMap<(bool, Option<Band>), Vec<&Log>> = BTreeMap::new();
+            for log in logs {
+                let band = log.frequency.band();
+                grouped.entry((band.is_none(), band)).or_default().push(log);
+            }
+            grouped
+                .into_iter()
+                .map(|((_, band), logs)| {
+                    let name = match band {
+                        Some(band) => format!("{} band", band),
+                        None => "Outside the ITU bands".to_string(),
+                    };
+                    (name, logs)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Id, `0xRRGGBB` colour and legend of the style a log's placemark uses
+fn style(log: &Log, colours: KmlColours) -> (String, u32, String) {
+    match colours {
+        KmlColours::Mode => {
+            let colour = SignalMode::ALL
+                .iter()
+                .position(|mode| *mode == log.mode)
+                .and_then(|index| MODE_COLOURS.get(index))
+                .copied()
+                .unwrap_or(DEFAULT_COLOUR);
+            let id: String = log
+                .mode
+                .as_str()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            (format!("mode-{}", id), colour, log.mode.to_string())
+        }
+        KmlColours::Power => match log.power_dbm {
+            Some(power) => {
+                let step = POWER_STEPS
+                    .iter()
+                    .rposition(|(lower, _, _)| power >= *lower)
+                    .unwrap_or(0);
+                let (_, colour, legend) = POWER_STEPS[step];
+                (format!("power-{}", step), colour, legend.to_string())
+            }
+            None => (
+                "power-none".to_string(),
+                DEFAULT_COLOUR,
+                "power not measured".to_string(),
+            ),
+        },
+    }
+}
+
+fn write_style(id: &str, colour: u32, legend: &str, out: &mut impl Write) -> io::Result<()> {
+    // KML colours are aabbggrr
+    let (r, g, b) = (colour >> 16, (colour >> 8) & 0xff, colour & 0xff);
+    writeln!(out, r#"  <Style id="{}">"#, xml::escape(id))?;
+    writeln!(out, "    <!-- {} -->", legend.replace("--", "- -"))?;
+    writeln!(out, "    <IconStyle>")?;
+    writeln!(out, "      <color>ff{:02x}{:02x}{:02x}</color>", b, g, r)?;
+    writeln!(out, "      <scale>0.8</scale>")?;
+    writeln!(out, "      <Icon><href>{}</href></Icon>", ICON)?;
+    writeln!(out, "    </IconStyle>")?;
+    // Labels of a drive's worth of placemarks would hide the map; they show on hover
+    writeln!(out, "    <LabelStyle><scale>0</scale></LabelStyle>")?;
+    writeln!(out, "  </Style>")
+}
+
+fn write_placemark(log: &Log, style: &str, out: &mut impl Write) -> io::Result<()> {
+    let name = match log.callsign.as_deref().filter(|c| !c.trim().is_empty()) {
+        Some(callsign) => format!("{} {}", log.frequency, callsign.to_uppercase()),
+        None => format!("{} {}", log.frequency, log.mode),
+    };
+    writeln!(out, "    <Placemark>")?;
+    writeln!(out, "      <name>{}</name>", xml::escape(&name))?;
+    writeln!(
+        out,
+        "      <TimeSpan><begin>{}</begin></TimeSpan>",
+        log.timestamp.format("%Y-%m-%dT%H:%M:%S%.fZ")
+    )?;
+    writeln!(out, "      <styleUrl>#{}</styleUrl>", xml::escape(style))?;
+    writeln!(
+        out,
+        "      <description><![CDATA[{}]]></description>",
+        description(log)
+    )?;
+    writeln!(
+        out,
+        "      <Point><coordinates>{},{}</coordinates></Point>",
+        log.ycoord, log.xcoord
+    )?;
+    writeln!(out, "    </Placemark>")
+}
+
+/// Balloon HTML with a line per field, as [`render_log`] prints them
+///
+/// Log text is escaped, so it cannot end the CDATA section it is written in.
+///
+/// [`render_log`]: crate::model::model::render_log
+fn description(log: &Log) -> String {
+    let mut lines = vec![
+        format!("<b>{}</b>", log.frequency),
+        format!(
+            "Callsign: {}",
+            xml::escape(&log.callsign.as_deref().unwrap_or("").to_uppercase())
+        ),
+        format!("Coordinate: ({}, {})", log.xcoord, log.ycoord),
+        format!(
+            "Comment: {}",
+            xml::escape(log.comment.as_deref().unwrap_or(""))
+        ),
+        format!("Mode: {}", xml::escape(log.mode.as_str())),
+        format!(
+            "Recorded at: {} UTC",
+            log.timestamp.format("%Y-%m-%d %H:%M:%S")
+        ),
+        format!("Duration: {:.2} sec", log.recording_duration),
+    ];
+    lines.extend(
+        detail_lines(&log.details())
+            .iter()
+            .map(|line| xml::escape(line).into_owned()),
+    );
+    if !log.tags.is_empty() {
+        lines.push(format!("Tags: {}", xml::escape(&log.tags.join(", "))));
+    }
+    lines.join("<br/>")
+}
+
+impl KmlFolders {
+    pub const ALL: [KmlFolders; 2] = [KmlFolders::Session, KmlFolders::Band];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KmlFolders::Session => "session",
+            KmlFolders::Band => "band",
+        }
+    }
+}
+
+impl KmlColours {
+    pub const ALL: [KmlColours; 2] = [KmlColours::Mode, KmlColours::Power];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KmlColours::Mode => "mode",
+            KmlColours::Power => "power",
+        }
+    }
+}
+
+impl fmt::Display for KmlFolders {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for KmlColours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for KmlFolders {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KmlFolders::ALL
+            .into_iter()
+            .find(|folders| folders.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                ValidationError::UnsupportedFormat(format!(
+                    "KML folders by {:?} (expected session or band)",
+                    s
+                ))
+            })
+    }
+}
+
+impl FromStr for KmlColours {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KmlColours::ALL
+            .into_iter()
+            .find(|colours| colours.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                ValidationError::UnsupportedFormat(format!(
+                    "KML colours by {:?} (expected mode or power)",
+                    s
+                ))
+            })
+    }
+}
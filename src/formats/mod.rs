@@ -0,0 +1,248 @@
+//! Reading and writing logs as files and terminal output
+//!
+//! Every format that can be read back reads what it writes. Imported logs carry no id
+//! of a store yet; they are added with [`merge_logs`](crate::db::merge_logs), which
+//! skips uuids already stored. A log read without a uuid gets one derived from its
+//! contents, so a file imported twice is only stored once whichever tool wrote it.
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+use crate::error::ValidationError;
+use crate::model::{Frequency, Log, LogTimeZone, LogUuid, SignalMode};
+use crate::spatial::Coordinate;
+
+pub mod adif;
+pub mod csv;
+pub mod geojson;
+pub mod gpx;
+pub mod json;
+pub mod kml;
+pub mod table;
+pub mod xml;
+pub mod zip;
+
+/// Layout of logs written by the command line tools
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Aligned columns for reading in a terminal, write only
+    Table,
+    Json,
+    Csv,
+    /// ADIF tagged text, `.adi`
+    Adif,
+    /// ADIF XML, `.adx`
+    Adx,
+    /// A FeatureCollection of points, `.geojson`
+    GeoJson,
+    /// Google Earth placemarks, write only
+    Kml,
+    /// Zipped KML, write only
+    Kmz,
+}
+
+impl LogFormat {
+    pub const ALL: [LogFormat; 8] = [
+        LogFormat::Table,
+        LogFormat::Json,
+        LogFormat::Csv,
+        LogFormat::Adif,
+        LogFormat::Adx,
+        LogFormat::GeoJson,
+        LogFormat::Kml,
+        LogFormat::Kmz,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogFormat::Table => "table",
+            LogFormat::Json => "json",
+            LogFormat::Csv => "csv",
+            LogFormat::Adif => "adif",
+            LogFormat::Adx => "adx",
+            LogFormat::GeoJson => "geojson",
+            LogFormat::Kml => "kml",
+            LogFormat::Kmz => "kmz",
+        }
+    }
+
+    /// File extensions of the format, the usual one first; empty if it is not written
+    /// to files
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            LogFormat::Table => &[],
+            LogFormat::Json => &["json"],
+            LogFormat::Csv => &["csv"],
+            LogFormat::Adif => &["adi", "adif"],
+            LogFormat::Adx => &["adx"],
+            LogFormat::GeoJson => &["geojson"],
+            LogFormat::Kml => &["kml"],
+            LogFormat::Kmz => &["kmz"],
+        }
+    }
+
+    /// The format a file name's extension names, e.g. `csv` for `logs.csv`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        LogFormat::ALL.into_iter().find(|format| {
+            format
+                .extensions()
+                .iter()
+                .any(|ext| ext.eq_ignore_ascii_case(extension))
+        })
+    }
+
+    /// Write `logs` in this format, KML with the default [`kml::KmlOptions`]
+    pub fn write_logs(&self, logs: &[Log], out: &mut impl Write) -> io::Result<()> {
+        match self {
+            LogFormat::Table => table::write_logs(logs, out),
+            LogFormat::Json => json::write_logs(logs, out),
+            LogFormat::Csv => csv::write_logs(logs, out),
+            LogFormat::Adif => adif::write_adi(logs, out),
+            LogFormat::Adx => adif::write_adx(logs, out),
+            LogFormat::GeoJson => geojson::write_logs(logs, None, out).map(|_| ()),
+            LogFormat::Kml => kml::write_kml(logs, &kml::KmlOptions::default(), out),
+            LogFormat::Kmz => kml::write_kmz(logs, &kml::KmlOptions::default(), out),
+        }
+    }
+
+    /// Write every field of one log in this format
+    pub fn write_log(&self, log: &Log, out: &mut impl Write) -> io::Result<()> {
+        match self {
+            LogFormat::Table => table::write_log(log, out),
+            LogFormat::Json => json::write_log(log, out),
+            LogFormat::Csv
+            | LogFormat::Adif
+            | LogFormat::Adx
+            | LogFormat::GeoJson
+            | LogFormat::Kml
+            | LogFormat::Kmz => self.write_logs(std::slice::from_ref(log), out),
+        }
+    }
+
+    /// Read the logs in a file of this format
+    ///
+    /// Times without an offset are read in `zone`; ADIF times are always UTC.
+    ///
+    /// # Errors
+    /// Returns `ValidationError::InvalidRecord` for the first record that cannot be
+    /// read (`ValidationError::InvalidFeature` for GeoJSON), or
+    /// `ValidationError::UnsupportedFormat` for a format that is write only
+    pub fn read_logs(&self, input: &[u8], zone: LogTimeZone) -> Result<Vec<Log>, ValidationError> {
+        match self {
+            LogFormat::Table => Err(ValidationError::UnsupportedFormat(
+                "tables cannot be imported".to_string(),
+            )),
+            LogFormat::Kml | LogFormat::Kmz => Err(ValidationError::UnsupportedFormat(
+                "KML and KMZ cannot be imported".to_string(),
+            )),
+            LogFormat::Json => json::read_logs(text(input)?),
+            LogFormat::Csv => csv::read_logs(text(input)?, zone),
+            LogFormat::Adif => adif::read_adi(input),
+            LogFormat::Adx => adif::read_adx(text(input)?),
+            LogFormat::GeoJson => geojson::read_logs(text(input)?, zone),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LogFormat::ALL
+            .into_iter()
+            .find(|format| format.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                let names: Vec<&str> = LogFormat::ALL.iter().map(LogFormat::as_str).collect();
+                ValidationError::UnsupportedFormat(format!(
+                    "{:?} (expected one of {})",
+                    s,
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+/// A log read from a file, with the fields every format has and the rest empty
+///
+/// The uuid is a placeholder until the reader sets the one in the file, or
+/// [`content_uuid`] once every field is read.
+pub(crate) fn imported_log(
+    frequency: Frequency,
+    position: Coordinate,
+    mode: SignalMode,
+    timestamp: DateTime<Utc>,
+) -> Log {
+    Log {
+        id: 0,
+        frequency,
+        xcoord: position.lat() as f32,
+        ycoord: position.lon() as f32,
+        callsign: None,
+        mode,
+        comment: None,
+        timestamp,
+        recording_duration: 0.0,
+        location: Some(position.into()),
+        power_dbm: None,
+        snr_db: None,
+        bandwidth_hz: None,
+        altitude_m: None,
+        position_accuracy_m: None,
+        heading_deg: None,
+        speed_mps: None,
+        uuid: LogUuid::from_bytes([0; 16]),
+        session_id: None,
+        equipment_profile_id: None,
+        tags: Vec::new(),
+    }
+}
+
+/// Uuid of a log read from a file that has none
+///
+/// It carries `timestamp`, so it sorts with logs captured at the time, and a hash of
+/// every other field, so reading the same log again gives the same uuid while two
+/// logs that differ anywhere, like two QSOs logged in the same minute, do not share
+/// one. The hash is 128-bit FNV-1a of the log as JSON, rather than `DefaultHasher`,
+/// whose output may change between Rust releases.
+pub(crate) fn content_uuid(log: &Log) -> LogUuid {
+    const OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+    const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+    let contents = Log {
+        id: 0,
+        uuid: LogUuid::from_bytes([0; 16]),
+        ..log.clone()
+    };
+    let json = serde_json::to_vec(&contents).expect("a log serializes to JSON");
+    let hash = json.iter().fold(OFFSET, |hash, &byte| {
+        (hash ^ byte as u128).wrapping_mul(PRIME)
+    });
+    let mut random = [0u8; 10];
+    random.copy_from_slice(&hash.to_be_bytes()[..10]);
+    LogUuid::from_parts(log.timestamp.timestamp_millis().max(0) as u64, random)
+}
+
+/// `input` as UTF-8 text
+fn text(input: &[u8]) -> Result<&str, ValidationError> {
+    std::str::from_utf8(input).map_err(|e| {
+        let line = input[..e.valid_up_to()]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count()
+            + 1;
+        ValidationError::InvalidRecord {
+            line,
+            reason: "text is not UTF-8".to_string(),
+        }
+    })
+}
@@ -0,0 +1,113 @@
+//! Logs as aligned columns for reading in a terminal
+use std::io::{self, Write};
+
+use crate::model::Log;
+
+const HEADERS: [&str; 9] = [
+    "ID",
+    "TIME (UTC)",
+    "FREQUENCY",
+    "LAT",
+    "LON",
+    "CALLSIGN",
+    "MODE",
+    "TAGS",
+    "COMMENT",
+];
+
+/// One row per log, newest first as given, with the comment last and unpadded
+pub fn write_logs(logs: &[Log], out: &mut impl Write) -> io::Result<()> {
+    let rows: Vec<[String; 9]> = logs
+        .iter()
+        .map(|log| {
+            [
+                log.id.to_string(),
+                log.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+                log.frequency.to_string(),
+                format!("{:.5}", log.xcoord),
+                format!("{:.5}", log.ycoord),
+                log.callsign.as_deref().unwrap_or("").to_uppercase(),
+                log.mode.to_string(),
+                log.tags.join(","),
+                single_line(log.comment.as_deref().unwrap_or("")),
+            ]
+        })
+        .collect();
+
+    let mut widths = HEADERS.map(|header| header.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    write_row(out, &HEADERS, &widths)?;
+    for row in &rows {
+        write_row(out, row, &widths)?;
+    }
+    Ok(())
+}
+
+/// Every field of one log on a line of its own
+pub fn write_log(log: &Log, out: &mut impl Write) -> io::Result<()> {
+    let optional = |value: Option<f32>, unit: &str| match value {
+        Some(value) => format!("{} {}", value, unit),
+        None => "-".to_string(),
+    };
+    let fields = [
+        ("id", log.id.to_string()),
+        ("uuid", log.uuid.to_string()),
+        (
+            "time",
+            log.timestamp.format("%Y-%m-%d %H:%M:%S%.f UTC").to_string(),
+        ),
+        ("frequency", log.frequency.to_string()),
+        ("position", format!("{}, {}", log.xcoord, log.ycoord)),
+        (
+            "callsign",
+            log.callsign.as_deref().unwrap_or("-").to_uppercase(),
+        ),
+        ("mode", log.mode.to_string()),
+        ("comment", log.comment.clone().unwrap_or_default()),
+        ("duration", format!("{} s", log.recording_duration)),
+        ("power", optional(log.power_dbm, "dBm")),
+        ("snr", optional(log.snr_db, "dB")),
+        (
+            "bandwidth",
+            log.bandwidth_hz
+                .map_or_else(|| "-".to_string(), |bw| bw.to_string()),
+        ),
+        ("altitude", optional(log.altitude_m, "m")),
+        ("accuracy", optional(log.position_accuracy_m, "m")),
+        ("heading", optional(log.heading_deg, "°")),
+        ("speed", optional(log.speed_mps, "m/s")),
+        (
+            "session",
+            log.session_id
+                .map_or_else(|| "-".to_string(), |id| id.to_string()),
+        ),
+        ("tags", log.tags.join(", ")),
+    ];
+    for (name, value) in fields {
+        writeln!(out, "{:<10} {}", format!("{}:", name), value)?;
+    }
+    Ok(())
+}
+
+fn write_row<S: AsRef<str>>(out: &mut impl Write, cells: &[S], widths: &[usize]) -> io::Result<()> {
+    let last = cells.len() - 1;
+    let mut line = String::new();
+    for (i, (cell, width)) in cells.iter().zip(widths).enumerate() {
+        if i == last {
+            line.push_str(cell.as_ref());
+        } else {
+            line.push_str(&format!("{:<width$}  ", cell.as_ref(), width = width));
+        }
+    }
+    writeln!(out, "{}", line.trim_end())
+}
+
+/// `text` with line breaks replaced by spaces, so a row stays on one line
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,297 @@
+//! Just enough XML for the exchange formats: escaping, and reading a document into a
+//! tree of elements
+//!
+//! Processing instructions, comments, doctypes and CDATA markers are skipped; text is
+//! collected per element, so mixed content loses its order.
+use std::borrow::Cow;
+
+use crate::error::ValidationError;
+
+/// An element with its attributes, child elements and own text
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    /// Text directly inside the element, entities decoded
+    pub text: String,
+    /// Line of the start tag, counting from 1
+    pub line: usize,
+}
+
+impl Element {
+    /// Name without a namespace prefix, e.g. `Placemark` for `kml:Placemark`
+    pub fn local_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or(&self.name)
+    }
+
+    /// Whether the local name is `name`, ignoring case
+    pub fn is(&self, name: &str) -> bool {
+        self.local_name().eq_ignore_ascii_case(name)
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.rsplit(':').next() == Some(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// First child with local name `name`
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.is(name))
+    }
+
+    /// Children with local name `name`
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.is(name))
+    }
+
+    /// Trimmed text of the first child named `name`, None if absent or blank
+    pub fn child_text(&self, name: &str) -> Option<&str> {
+        let text = self.child(name)?.text.trim();
+        (!text.is_empty()).then_some(text)
+    }
+
+    /// This element and every element below it, depth first
+    pub fn descendants(&self) -> Vec<&Element> {
+        let mut found = vec![self];
+        let mut index = 0;
+        while index < found.len() {
+            let element = found[index];
+            found.extend(element.children.iter());
+            index += 1;
+        }
+        found
+    }
+}
+
+/// Escape text for use in element content or a double-quoted attribute
+pub fn escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(['<', '>', '&', '"', '\'']) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Read a document and return its root element
+///
+/// # Errors
+/// Returns `ValidationError::InvalidRecord` with the line of the first malformed tag,
+/// unknown entity or unclosed element
+pub fn parse(input: &str) -> Result<Element, ValidationError> {
+    Parser {
+        input,
+        pos: 0,
+        line: 1,
+    }
+    .document()
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, reason: impl Into<String>) -> ValidationError {
+        ValidationError::InvalidRecord {
+            line: self.line,
+            reason: reason.into(),
+        }
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn advance(&mut self, len: usize) {
+        let end = self.pos + len;
+        self.line += self.input[self.pos..end].matches('\n').count();
+        self.pos = end;
+    }
+
+    /// Skip past the next `end`, e.g. the close of a comment
+    fn skip_past(&mut self, end: &str) -> Result<(), ValidationError> {
+        let len = self
+            .rest()
+            .find(end)
+            .ok_or_else(|| self.error(format!("missing {:?}", end)))?;
+        self.advance(len + end.len());
+        Ok(())
+    }
+
+    fn document(mut self) -> Result<Element, ValidationError> {
+        let mut stack: Vec<Element> = Vec::new();
+        while let Some(lt) = self.rest().find('<') {
+            let text = &self.rest()[..lt];
+            if let Some(open) = stack.last_mut() {
+                let text = decode(text).map_err(|e| self.error(e))?;
+                open.text.push_str(&text);
+            }
+            self.advance(lt);
+
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.advance("<![CDATA[".len());
+                let len = self
+                    .rest()
+                    .find("]]>")
+                    .ok_or_else(|| self.error("unclosed CDATA section"))?;
+                if let Some(open) = stack.last_mut() {
+                    open.text.push_str(&self.input[self.pos..self.pos + len]);
+                }
+                self.advance(len + 3);
+            } else if rest.starts_with("<!") {
+                self.skip_past(">")?;
+            } else if rest.starts_with("</") {
+                let len = rest
+                    .find('>')
+                    .ok_or_else(|| self.error("unclosed end tag"))?;
+                let name = rest[2..len].trim();
+                let element = stack
+                    .pop()
+                    .filter(|open| open.name == name)
+                    .ok_or_else(|| self.error(format!("unexpected </{}>", name)))?;
+                self.advance(len + 1);
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            } else {
+                let (element, self_closing) = self.start_tag()?;
+                if !self_closing {
+                    stack.push(element);
+                } else if let Some(parent) = stack.last_mut() {
+                    parent.children.push(element);
+                } else {
+                    return Ok(element);
+                }
+            }
+        }
+        match stack.last() {
+            Some(open) => Err(self.error(format!("<{}> is never closed", open.name))),
+            None => Err(self.error("no root element")),
+        }
+    }
+
+    /// Read `<name attr="value" ...>` or `<name ... />` at the current position
+    fn start_tag(&mut self) -> Result<(Element, bool), ValidationError> {
+        let line = self.line;
+        let input = self.input;
+        let rest = &input[self.pos..];
+        let mut len = None;
+        let mut quote = None;
+        for (i, c) in rest.char_indices().skip(1) {
+            match (c, quote) {
+                ('"' | '\'', None) => quote = Some(c),
+                (c, Some(q)) if c == q => quote = None,
+                ('>', None) => {
+                    len = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let len = len.ok_or_else(|| self.error("unclosed start tag"))?;
+        let mut body = &rest[1..len];
+        let self_closing = body.ends_with('/');
+        if self_closing {
+            body = &body[..body.len() - 1];
+        }
+
+        let name_end = body.find(|c: char| c.is_whitespace()).unwrap_or(body.len());
+        let name = &body[..name_end];
+        if name.is_empty() {
+            return Err(self.error("empty tag name"));
+        }
+        let attributes = attributes(&body[name_end..]).map_err(|e| self.error(e))?;
+        self.advance(len + 1);
+        Ok((
+            Element {
+                name: name.to_string(),
+                attributes,
+                line,
+                ..Element::default()
+            },
+            self_closing,
+        ))
+    }
+}
+
+/// Parse `key="value"` pairs
+fn attributes(mut text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut attributes = Vec::new();
+    loop {
+        text = text.trim_start();
+        if text.is_empty() {
+            return Ok(attributes);
+        }
+        let eq = text
+            .find('=')
+            .ok_or_else(|| format!("attribute without a value in {:?}", text))?;
+        let key = text[..eq].trim();
+        let value = text[eq + 1..].trim_start();
+        let quote = value
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '"' | '\''))
+            .ok_or_else(|| format!("unquoted value of attribute {:?}", key))?;
+        let end = value[1..]
+            .find(quote)
+            .ok_or_else(|| format!("unterminated value of attribute {:?}", key))?;
+        attributes.push((key.to_string(), decode(&value[1..end + 1])?.into_owned()));
+        text = &value[end + 2..];
+    }
+}
+
+/// Replace entity and character references with the characters they stand for
+fn decode(text: &str) -> Result<Cow<'_, str>, String> {
+    if !text.contains('&') {
+        return Ok(Cow::Borrowed(text));
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        let semi = rest[amp..]
+            .find(';')
+            .ok_or_else(|| format!("unterminated entity in {:?}", text))?;
+        let entity = &rest[amp + 1..amp + semi];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                .and_then(Result::ok)
+                .and_then(char::from_u32)
+                .ok_or_else(|| format!("unknown entity &{};", entity))?,
+        };
+        decoded.push(c);
+        rest = &rest[amp + semi + 1..];
+    }
+    decoded.push_str(rest);
+    Ok(Cow::Owned(decoded))
+}
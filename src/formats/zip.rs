@@ -0,0 +1,111 @@
+//! Just enough of the zip format to package KMZ files: stored (uncompressed) entries,
+//! without zip64, so each entry and the archive stay under 4 GiB
+use std::io::{self, Write};
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+/// Version 2.0, the first with folders and the least readers expect
+const VERSION: u16 = 20;
+/// General purpose flag bit 11, entry names are UTF-8
+const UTF8_NAMES: u16 = 0x0800;
+/// MS-DOS date of 1980-01-01, the earliest a zip entry can carry; entries are dated
+/// with it so the same logs always give the same archive
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+/// Write an archive holding each `(name, contents)` entry uncompressed
+pub fn write_stored(entries: &[(&str, &[u8])], out: &mut impl Write) -> io::Result<()> {
+    let too_large = || io::Error::new(io::ErrorKind::InvalidInput, "too large for a zip archive");
+    let mut central = Vec::new();
+    let mut offset: u32 = 0;
+    for (name, contents) in entries {
+        let size = u32::try_from(contents.len()).map_err(|_| too_large())?;
+        let name_len = u16::try_from(name.len()).map_err(|_| too_large())?;
+        let crc = crc32(contents);
+
+        let mut local = Vec::with_capacity(30 + name.len());
+        put_u32(&mut local, LOCAL_HEADER);
+        put_entry_fields(&mut local, crc, size, name_len);
+        local.extend_from_slice(name.as_bytes());
+        out.write_all(&local)?;
+        out.write_all(contents)?;
+
+        put_u32(&mut central, CENTRAL_HEADER);
+        put_u16(&mut central, VERSION);
+        put_entry_fields(&mut central, crc, size, name_len);
+        // Comment length, disk number, internal and external attributes
+        central.extend_from_slice(&[0; 10]);
+        put_u32(&mut central, offset);
+        central.extend_from_slice(name.as_bytes());
+
+        offset = u32::try_from(local.len())
+            .ok()
+            .and_then(|len| offset.checked_add(len)?.checked_add(size))
+            .ok_or_else(too_large)?;
+    }
+
+    let count = u16::try_from(entries.len()).map_err(|_| too_large())?;
+    let central_len = u32::try_from(central.len()).map_err(|_| too_large())?;
+    put_u32(&mut central, END_OF_CENTRAL_DIRECTORY);
+    // This disk and the disk the central directory starts on
+    central.extend_from_slice(&[0; 4]);
+    put_u16(&mut central, count);
+    put_u16(&mut central, count);
+    put_u32(&mut central, central_len);
+    put_u32(&mut central, offset);
+    // Archive comment length
+    put_u16(&mut central, 0);
+    out.write_all(&central)
+}
+
+/// The header fields local and central headers share, from the version needed on
+/// through the extra field length
+fn put_entry_fields(header: &mut Vec<u8>, crc: u32, size: u32, name_len: u16) {
+    put_u16(header, VERSION);
+    put_u16(header, UTF8_NAMES);
+    // Stored, no compression
+    put_u16(header, 0);
+    // Time of midnight, then the date
+    put_u16(header, 0);
+    put_u16(header, DOS_DATE);
+    put_u32(header, crc);
+    put_u32(header, size);
+    put_u32(header, size);
+    put_u16(header, name_len);
+    put_u16(header, 0);
+}
+
+fn put_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+/// CRC-32 as zip, gzip and PNG use it (reflected polynomial 0xEDB88320)
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                0xEDB8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
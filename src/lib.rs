@@ -1,53 +1,34 @@
 use diesel::prelude::*;
 
+pub mod db;
 pub mod error;
+pub mod formats;
 pub mod model;
 pub mod schema;
 pub mod source;
 pub mod spatial;
 pub mod tabs;
 
-pub use error::{DatabaseError, ValidationError};
-pub use model::{Log, NewLog, model::SignalMode};
-pub fn create_log(
-    conn: &mut PgConnection,
-    frequency: f32,
-    xcoord: f32,
-    ycoord: f32,
-    callsign: String,
-    mode: SignalMode,
-    comment: String,
-    recording_duration: f32,
-) -> Result<Log, diesel::result::Error> {
-    use crate::schema::logs;
+pub use db::filter::{LogCursor, LogFilter, LogPage, query_logs};
+pub use db::logs::{
+    BatchReport, RejectedRow, create_log, create_logs_batch, delete_log, get_log, get_logs,
+    get_logs_within_radius, update_log, update_logs,
+};
+pub use db::search::{SearchHit, search_logs};
+pub use error::{DatabaseError, Error, SourceError, ValidationError};
+pub use formats::LogFormat;
+pub use model::{
+    Band, EquipmentProfile, Frequency, FrequencyUnit, Log, LogChanges, LogDetails, LogRevision,
+    LogStats, LogTimeZone, LogUuid, NewEquipmentProfile, NewLog, NewSession, RevisionAction,
+    Session, SessionSummary, SignalMode, Tag, parse_timestamp,
+};
+pub use spatial::{BoundingBox, Coordinate, DbPoint, Layer, Measurement, aggregate_by_location};
 
-    let new_log = NewLog {
-        frequency,
-        xcoord,
-        ycoord,
-        callsign: &callsign,
-        mode: mode.to_str(),
-        comment: &comment,
-        recording_duration,
-        timestamp: chrono::Utc::now().naive_utc(),
-    };
-
-    diesel::insert_into(logs::table)
-        .values(&new_log)
-        .returning(Log::as_select())
-        .get_result(conn)
-}
-
-pub fn get_logs(conn: &mut PgConnection, limit: i64) -> Result<Vec<Log>, diesel::result::Error> {
-    use crate::schema::logs::dsl::*;
-
-    logs.order(timestamp.desc())
-        .limit(limit)
-        .select(Log::as_select())
-        .load(conn)
-}
-
-pub fn establish_connection(database_url: &str) -> PgConnection {
-    PgConnection::establish(database_url)
-        .unwrap_or_else(|_| panic!("Error connecting to {}", database_url))
+/// Open a single Postgres connection, e.g. for one-off commands
+///
+/// # Errors
+/// Returns `Error::Database` with `DatabaseError::ConnectionError` if the server
+/// cannot be reached or rejects the connection
+pub fn establish_connection(database_url: &str) -> Result<PgConnection, Error> {
+    Ok(PgConnection::establish(database_url)?)
 }
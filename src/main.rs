@@ -1,13 +1,40 @@
-use diesel::{Connection, PgConnection};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
 use ratatui::buffer::Buffer;
 use ratatui::style::{Color, Style};
 use ratatui::widgets::{Borders, Paragraph};
-use sdr_db::Log;
-use sdr_db::create_log;
+use sdr_db::db::geotag::{GeotagOptions, geotag_logs};
+use sdr_db::db::merge::{self, merge_logs};
+use sdr_db::db::repair::{ReferencePosition, repair_coordinates};
+use sdr_db::db::sessions::{active_session, start_session, stop_session, summarize_session};
+use sdr_db::db::{
+    DbCommand, DbReply, DbWorker, LogStore, MemoryLogStore, QueuedLog, StoreConfig, SyncStatus,
+    open_store,
+};
+use sdr_db::establish_connection;
+use sdr_db::formats::csv;
+use sdr_db::formats::geojson::FeatureWriter;
+use sdr_db::formats::gpx::read_track;
+use sdr_db::formats::kml::{self, KmlColours, KmlFolders, KmlOptions};
 use sdr_db::model::model::render_log;
-use sdr_db::tabs::{SelectedTab, create_log::NewLogInputForm, spectrum_view::SpectrumViewerState};
+use sdr_db::model::revision::default_operator;
+use sdr_db::tabs::{
+    SelectedTab,
+    create_log::NewLogInputForm,
+    spectrum_view::SpectrumViewerState,
+    view_logs::{self, ViewLogsState},
+};
+use sdr_db::{
+    Coordinate, Frequency, FrequencyUnit, Log, LogCursor, LogDetails, LogFilter, LogFormat,
+    LogStats, LogTimeZone, NewLog, NewSession, SignalMode, ValidationError, parse_timestamp,
+};
 
-use clap::Parser;
+use chrono::TimeDelta;
+use clap::{Parser, Subcommand, ValueEnum};
+use diesel::PgConnection;
 use tracing::{error, info};
 
 use color_eyre::Result;
@@ -25,43 +52,283 @@ use strum::IntoEnumIterator;
 #[derive(Parser, Debug)]
 #[command(name = "sdr_db")]
 #[command(about = "SDR Database - Collect and store SDR measurements with geospatial data", long_about = None)]
-struct Args {
-    /// Database URL (or use DATABASE_URL env var)
+struct Cli {
+    /// Database URL (or use DATABASE_URL env var), required by the queued and postgres stores
     #[arg(long, env = "DATABASE_URL")]
     database_url: Option<String>,
 
-    /// Latitude in decimal degrees [-90, 90]
-    #[arg(long)]
-    latitude: Option<f32>,
+    /// Where the TUI stores logs
+    #[arg(long, env = "SDR_DB_STORE", value_enum, default_value_t = StoreKind::Queued)]
+    store: StoreKind,
+
+    /// Directory of the embedded database used by the sled store
+    #[arg(long, env = "SDR_DB_SLED_PATH", default_value = "sdr_db.sled")]
+    sled_path: PathBuf,
+
+    /// Directory of the local queue used by the queued store
+    #[arg(long, env = "SDR_DB_QUEUE_PATH", default_value = "sdr_db_queue.sled")]
+    queue_path: PathBuf,
+
+    /// Zone timestamps typed without an offset are in: local, UTC or an offset like +02:00
+    #[arg(long, env = "SDR_DB_TIMEZONE", default_value = "local")]
+    timezone: LogTimeZone,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Storage backend selectable from the command line
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum StoreKind {
+    /// Local queue synced to Postgres in the background, keeps capturing offline
+    Queued,
+    /// Central Postgres/PostGIS server at DATABASE_URL
+    Postgres,
+    /// Embedded sled database, no server needed
+    Sled,
+    /// In memory, discarded on exit
+    Memory,
+}
+
+impl Cli {
+    /// Backend chosen on the command line
+    ///
+    /// clap skips `required_if` checks for a default value, so the URL the default
+    /// queued store needs is checked here.
+    fn store_config(&self) -> Result<StoreConfig, &'static str> {
+        let database_url = || {
+            self.database_url
+                .clone()
+                .filter(|url| !url.trim().is_empty())
+                .ok_or("DATABASE_URL is required for --store queued/postgres")
+        };
+        Ok(match self.store {
+            StoreKind::Queued => StoreConfig::Queued {
+                database_url: database_url()?,
+                queue_path: self.queue_path.clone(),
+            },
+            StoreKind::Postgres => StoreConfig::Postgres {
+                database_url: database_url()?,
+            },
+            StoreKind::Sled => StoreConfig::Sled {
+                path: self.sled_path.clone(),
+            },
+            StoreKind::Memory => StoreConfig::Memory,
+        })
+    }
+
+    /// Store the one-off commands use; the queued store goes straight to Postgres
+    fn direct_store_config(&self) -> Result<StoreConfig, &'static str> {
+        Ok(match self.store_config()? {
+            StoreConfig::Queued { database_url, .. } => StoreConfig::Postgres { database_url },
+            config => config,
+        })
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Open the terminal interface (the default without a subcommand)
+    Tui,
+    /// Add, list or show logs
+    #[command(subcommand)]
+    Log(LogCommand),
+    /// Read logs from a file, skipping ones already stored and reporting conflicts
+    Import {
+        path: PathBuf,
+
+        /// File format (defaults to the one the extension names)
+        #[arg(long)]
+        format: Option<LogFormat>,
+    },
+    /// Write the logs matching every filter to a file or standard output
+    Export {
+        /// Output format (defaults to the one the output extension names, else csv)
+        #[arg(long)]
+        format: Option<LogFormat>,
+
+        /// File to write (defaults to standard output)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// Criterion as key=value, e.g. mode=FM or since=2025-11-01; repeatable
+        #[arg(long)]
+        filter: Vec<String>,
+
+        /// KML and KMZ folders: one per session or per band
+        #[arg(long, default_value = "session")]
+        folders: KmlFolders,
 
-    /// Longitude in decimal degrees [-180, 180]
-    #[arg(long)]
-    longitude: Option<f32>,
+        /// KML and KMZ placemark colours: by signal mode or by received power
+        #[arg(long, default_value = "mode")]
+        colours: KmlColours,
+    },
+    /// Count the logs matching every filter by mode, band and tag
+    Stats {
+        /// Criterion as key=value, e.g. tag=weak; repeatable
+        #[arg(long)]
+        filter: Vec<String>,
 
-    /// Frequency in Hz (must be positive)
-    #[arg(long)]
-    frequency: Option<f32>,
+        #[arg(long, default_value = "table")]
+        format: LogFormat,
+    },
+    /// Find logs whose latitude/longitude are swapped or implausible, and optionally fix them
+    RepairCoords {
+        /// Rewrite fixable rows (default is a dry run that only reports)
+        #[arg(long)]
+        apply: bool,
 
-    /// Callsign or station identifier
-    #[arg(long)]
-    callsign: Option<String>,
+        /// Latitude of a known position the logs should be near
+        #[arg(long, requires = "reference_lon", allow_hyphen_values = true)]
+        reference_lat: Option<f64>,
 
-    /// Mode (e.g., FM, AM, SSB)
-    #[arg(long)]
-    mode: String,
+        /// Longitude of a known position the logs should be near
+        #[arg(long, requires = "reference_lat", allow_hyphen_values = true)]
+        reference_lon: Option<f64>,
 
-    /// Optional comment
-    #[arg(long)]
-    comment: Option<String>,
+        /// Maximum plausible distance from the reference position in kilometres
+        #[arg(long, default_value_t = 100.0)]
+        max_distance_km: f64,
+    },
+    /// Set the position of each log from a GPX track by its time, reporting logs the
+    /// track does not cover
+    Geotag {
+        /// GPX file with the track recorded during the survey
+        gpx: PathBuf,
 
-    #[arg(long)]
-    recording_duration: Option<f32>,
+        /// Seconds the logging clock runs ahead of the GPS clock (negative if behind)
+        #[arg(long, default_value_t = 0.0, allow_hyphen_values = true)]
+        clock_offset: f64,
+
+        /// Longest gap between track points to interpolate across, in seconds
+        #[arg(long, default_value_t = 60.0)]
+        max_gap: f64,
+
+        /// Criterion as key=value, e.g. session=3; repeatable
+        #[arg(long)]
+        filter: Vec<String>,
+
+        /// Move the logs (default is a dry run that only reports)
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Import another unit's logs, skipping ones already present and reporting conflicts
+    Merge {
+        /// Postgres URL or sled directory to read logs from
+        #[arg(long)]
+        from: String,
+    },
+    /// Start, stop or summarise survey sessions
+    #[command(subcommand)]
+    Session(SessionCommand),
+}
+
+#[derive(Subcommand, Debug)]
+enum LogCommand {
+    /// Store a log, attached to the running session if there is one
+    Add {
+        /// Frequency, e.g. 146.52M or 7074k; bare numbers are MHz
+        #[arg(long, value_parser = parse_frequency)]
+        freq: Frequency,
+
+        /// Latitude in decimal degrees [-90, 90]
+        #[arg(long, allow_hyphen_values = true)]
+        lat: f64,
+
+        /// Longitude in decimal degrees [-180, 180]
+        #[arg(long, allow_hyphen_values = true)]
+        lon: f64,
+
+        /// Mode, e.g. FM, USB or FT8
+        #[arg(long)]
+        mode: SignalMode,
+
+        #[arg(long)]
+        callsign: Option<String>,
+
+        #[arg(long)]
+        comment: Option<String>,
+
+        /// Recording length in seconds
+        #[arg(long, default_value_t = 0.0)]
+        duration: f32,
+
+        /// Time the signal was heard (defaults to now), read in --timezone without an offset
+        #[arg(long)]
+        time: Option<String>,
+
+        /// Tags, comma separated or repeated
+        #[arg(long, value_delimiter = ',')]
+        tag: Vec<String>,
+
+        #[arg(long, default_value = "table")]
+        format: LogFormat,
+    },
+    /// List the newest logs matching every filter
+    List {
+        /// Criterion as key=value, e.g. mode=FM, callsign=W1*, tag=weak, freq=144..148,
+        /// near=51.5,-0.1,10; repeatable
+        #[arg(long)]
+        filter: Vec<String>,
+
+        /// Most logs to list
+        #[arg(long, default_value_t = 50)]
+        limit: i64,
+
+        #[arg(long, default_value = "table")]
+        format: LogFormat,
+    },
+    /// Show every field of one log
+    Show {
+        id: i32,
+
+        #[arg(long, default_value = "table")]
+        format: LogFormat,
+    },
 }
 
+#[derive(Subcommand, Debug)]
+enum SessionCommand {
+    /// Start a session; logs created in the TUI are attached to it until it is stopped
+    Start {
+        /// Name of the outing, e.g. "Ridge survey"
+        name: String,
+
+        #[arg(long)]
+        notes: Option<String>,
+
+        /// Receiver, antenna and other equipment used
+        #[arg(long)]
+        equipment: Option<String>,
+    },
+    /// End a session, by default the running one
+    Stop {
+        /// Session id (defaults to the active session)
+        id: Option<i32>,
+    },
+    /// Show log count, duration, area and bands of a session, by default the running one
+    Summary {
+        /// Session id (defaults to the active session)
+        id: Option<i32>,
+    },
+}
+
+/// How long to wait for a key before checking for database replies
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Logs fetched per query by `export` and `stats`
+const EXPORT_PAGE_SIZE: i64 = 1_000;
+
 struct App {
     state: AppState,
+    db: DbWorker,
+    /// Last status reported by the store, None if it does not sync
+    sync_status: Option<SyncStatus>,
+    /// Most recent error, shown until the next key press
+    error_message: Option<String>,
     selected_tab: SelectedTab,
     new_log_form: NewLogInputForm,
+    view_logs_state: ViewLogsState,
     spectrum_viewer_state: SpectrumViewerState,
 }
 
@@ -73,21 +340,25 @@ enum AppState {
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(db: DbWorker) -> Self {
         Self {
             state: AppState::Running,
+            db,
+            sync_status: None,
+            error_message: None,
             selected_tab: SelectedTab::CreateLog,
             new_log_form: NewLogInputForm::default(),
+            view_logs_state: ViewLogsState::default(),
             spectrum_viewer_state: SpectrumViewerState::default(),
         }
     }
     //TODO: Tabs for Creating Logs, View Logs, Spectrum View + Source selector
-    pub fn run(mut self, mut terminal: DefaultTerminal, database_url: &str) -> Result<()> {
+    pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
+        self.load_active_session();
         while self.state == AppState::Running {
-            let conn = &mut PgConnection::establish(database_url)?;
-
             terminal.draw(|frame| frame.render_widget(&self, frame.area()))?;
-            self.handle_events(conn)?;
+            self.handle_events()?;
+            self.handle_db_replies();
         }
         Ok(())
     }
@@ -104,58 +375,181 @@ impl App {
             .render(area, buf);
     }
 
-    fn submit_log_entry(&mut self, conn: &mut PgConnection) {
+    /// Validate the Create Log form into an entry for the database worker
+    fn log_entry(&self) -> Result<QueuedLog, sdr_db::Error> {
         let form = &self.new_log_form;
+        let tags = form.tags()?;
+        let new_log = NewLog::new(
+            form.frequency()?,
+            form.position()?,
+            &form.callsign,
+            form.mode.clone(),
+            &form.comment,
+            form.recording_duration,
+            form.timestamp()?,
+            form.details()?,
+        )?
+        .with_tags(&tags)?
+        .in_session(form.active_session.as_ref().map(|s| s.id));
 
-        // Get validated coordinates
-        let latitude = match form.latitude() {
-            Ok(lat) => lat,
-            Err(_) => {
-                error!("Invalid latitude value");
-                return;
-            }
-        };
-        let longitude = match form.longitude() {
-            Ok(lon) => lon,
-            Err(_) => {
-                error!("Invalid longitude value");
-                return;
-            }
-        };
+        // Snapshot the receiver gains the spectrum viewer is streaming, or last streamed, with
+        Ok(QueuedLog::from(&new_log)
+            .with_equipment(self.spectrum_viewer_state.equipment_snapshot()))
+    }
 
-        if form.frequency > 0.0
-            && latitude.abs() <= 90.0
-            && longitude.abs() <= 180.0
-            && form.recording_duration >= 0.
-        {
-            match create_log(
-                conn,
-                form.frequency,
-                latitude,
-                longitude,
-                form.callsign.to_string(),
-                form.mode,
-                form.comment.clone(),
-                form.recording_duration,
-            ) {
-                Ok(log) => {
+    fn submit_log_entry(&mut self) {
+        let sent = self
+            .log_entry()
+            .and_then(|entry| Ok(self.db.send(DbCommand::CreateLog(entry))?));
+        if let Err(e) = sent {
+            self.show_error("Failed to create log entry", e);
+        }
+    }
+
+    /// Log an error and show it above the footer until the next key press
+    fn show_error(&mut self, context: &str, error: impl Into<sdr_db::Error>) {
+        let message = format!("{}: {}", context, error.into());
+        error!("{}", message);
+        self.error_message = Some(message);
+    }
+
+    /// Apply every reply the database worker has ready
+    fn handle_db_replies(&mut self) {
+        while let Some(reply) = self.db.try_recv() {
+            match reply {
+                DbReply::LogCreated(Ok(log)) => {
                     info!("✓ Log entry created successfully!");
                     self.new_log_form.created_log = Some(log);
+                    // Pick up the new entry next time View Logs is opened
+                    self.view_logs_state.loaded = false;
                 }
-                Err(e) => {
-                    error!("Failed to create log entry: {}", e);
+                DbReply::LogCreated(Err(e)) => self.show_error("Failed to create log entry", e),
+                // Pages of the plain list are stale once a search has started
+                DbReply::LogsLoaded { .. } if self.view_logs_state.search_query().is_some() => {}
+                DbReply::LogsLoaded { after, page } => match (page, after) {
+                    (Ok(page), None) => self.view_logs_state.load_first_page(page),
+                    (Ok(page), Some(_)) => self.view_logs_state.append_page(page),
+                    (Err(e), _) => {
+                        self.view_logs_state.loading = false;
+                        self.show_error("Failed to load logs", e);
+                    }
+                },
+                DbReply::SearchResults { query, hits } => match hits {
+                    Ok(hits) => {
+                        self.view_logs_state.load_search_results(&query, hits);
+                    }
+                    Err(e) => {
+                        self.view_logs_state.loading = false;
+                        self.show_error("Failed to search logs", e);
+                    }
+                },
+                DbReply::ActiveSession(Ok(session)) => self.new_log_form.active_session = session,
+                DbReply::ActiveSession(Err(e)) => {
+                    self.show_error("Failed to load the active session", e);
                 }
+                DbReply::SyncStatus(status) => self.sync_status = status,
             }
         }
     }
 
-    fn handle_events(&mut self, conn: &mut PgConnection) -> std::io::Result<()> {
+    /// Load data for the newly selected tab the first time it is shown, and stream the
+    /// spectrum viewer's source only while it is the selected tab
+    fn on_tab_changed(&mut self) {
+        if self.selected_tab != SelectedTab::SpectrumViewer {
+            self.spectrum_viewer_state.stop_streaming();
+        }
+        match self.selected_tab {
+            SelectedTab::CreateLog => self.load_active_session(),
+            SelectedTab::SpectrumViewer => self.spectrum_viewer_state.start_streaming(),
+            SelectedTab::ViewLogs if !self.view_logs_state.loaded => self.refresh_view_logs(),
+            _ => {}
+        }
+    }
+
+    /// Look up the session new entries go into, which may have been started elsewhere
+    fn load_active_session(&mut self) {
+        if let Err(e) = self.db.send(DbCommand::LoadActiveSession) {
+            self.show_error("Failed to load the active session", e);
+        }
+    }
+
+    /// Reload the View Logs list from the first page of its filter, or the matches of
+    /// its search
+    fn refresh_view_logs(&mut self) {
+        let Some(query) = self.view_logs_state.search_query() else {
+            self.request_view_logs_page(None);
+            return;
+        };
+        let command = DbCommand::SearchLogs {
+            query: query.to_string(),
+            limit: view_logs::PAGE_SIZE,
+        };
+        match self.db.send(command) {
+            Ok(()) => self.view_logs_state.loading = true,
+            Err(e) => self.show_error("Failed to search logs", e),
+        }
+    }
+
+    /// Edit the View Logs search prompt, searching again as the query changes
+    fn handle_search_key(&mut self, key: KeyCode) {
+        let state = &mut self.view_logs_state;
+        match key {
+            KeyCode::Enter => state.finish_search(),
+            KeyCode::Esc => {
+                state.clear_search();
+                self.refresh_view_logs();
+            }
+            KeyCode::Backspace => {
+                state.pop_search_char();
+                self.refresh_view_logs();
+            }
+            KeyCode::Char(c) => {
+                state.push_search_char(c);
+                self.refresh_view_logs();
+            }
+            _ => {}
+        }
+    }
+
+    /// Fetch the page after the loaded logs if the selection has reached the end
+    fn load_more_view_logs(&mut self) {
+        if self.view_logs_state.needs_next_page() {
+            self.request_view_logs_page(self.view_logs_state.next_cursor);
+        }
+    }
+
+    /// Ask the worker for the page after `after`, the first page if None
+    fn request_view_logs_page(&mut self, after: Option<LogCursor>) {
+        let command = DbCommand::QueryLogs {
+            filter: self.view_logs_state.filter.clone(),
+            after,
+            limit: view_logs::PAGE_SIZE,
+        };
+        match self.db.send(command) {
+            Ok(()) => self.view_logs_state.loading = true,
+            Err(e) => self.show_error("Failed to load logs", e),
+        }
+    }
+
+    fn handle_events(&mut self) -> std::io::Result<()> {
+        // Wake up regularly so database replies are shown without waiting for a key
+        if !crossterm::event::poll(EVENT_POLL_INTERVAL)? {
+            return Ok(());
+        }
         if let Event::Key(key) = crossterm::event::read()?
             && key.kind == crossterm::event::KeyEventKind::Press
         {
+            self.error_message = None;
+
             // If popup is showing, any key dismisses it
             if self.new_log_form.created_log.is_some() {
-                self.new_log_form = NewLogInputForm::default();
+                self.new_log_form.reset();
+                return Ok(());
+            }
+
+            // The search prompt takes every key, including the global ones
+            if self.selected_tab == SelectedTab::ViewLogs && self.view_logs_state.search_editing {
+                self.handle_search_key(key.code);
                 return Ok(());
             }
 
@@ -168,10 +562,12 @@ impl App {
                 }
                 KeyCode::Char('l') => {
                     self.next_tab();
+                    self.on_tab_changed();
                     return Ok(());
                 }
                 KeyCode::Char('h') => {
                     self.previous_tab();
+                    self.on_tab_changed();
                     return Ok(());
                 }
                 _ => {}
@@ -179,10 +575,10 @@ impl App {
             match &self.selected_tab {
                 SelectedTab::CreateLog => match key.code {
                     KeyCode::Enter => {
-                        self.submit_log_entry(conn);
+                        self.submit_log_entry();
                     }
                     KeyCode::Esc => {
-                        self.new_log_form = NewLogInputForm::default();
+                        self.new_log_form.reset();
                     }
                     KeyCode::Tab => {
                         if key
@@ -198,6 +594,46 @@ impl App {
                         self.new_log_form.handle_key_event(key);
                     }
                 },
+                SelectedTab::ViewLogs => {
+                    match key.code {
+                        KeyCode::Down | KeyCode::Char('j') => {
+                            self.view_logs_state.select_next();
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            self.view_logs_state.select_previous();
+                        }
+                        KeyCode::PageDown => {
+                            self.view_logs_state.select_next_page(20);
+                        }
+                        KeyCode::PageUp => {
+                            self.view_logs_state.select_previous_page(20);
+                        }
+                        KeyCode::Home => {
+                            self.view_logs_state.select_first();
+                        }
+                        KeyCode::End => {
+                            self.view_logs_state.select_last();
+                        }
+                        KeyCode::Char('r') => {
+                            self.refresh_view_logs();
+                        }
+                        KeyCode::Char('/') => {
+                            self.view_logs_state.start_search();
+                        }
+                        KeyCode::Char(c @ ('t' | 'T')) => {
+                            let changed = if c == 't' {
+                                self.view_logs_state.add_tag_filter_from_selected()
+                            } else {
+                                self.view_logs_state.remove_last_tag_filter()
+                            };
+                            if changed {
+                                self.refresh_view_logs();
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.load_more_view_logs();
+                }
                 SelectedTab::SpectrumViewer => match key.code {
                     KeyCode::Up => {
                         self.spectrum_viewer_state.increase_frequency();
@@ -230,18 +666,6 @@ impl App {
                     }
                     _ => {}
                 },
-                _ => match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => {
-                        self.quit();
-                    }
-                    KeyCode::Char('l') => {
-                        self.next_tab();
-                    }
-                    KeyCode::Char('h') => {
-                        self.previous_tab();
-                    }
-                    _ => {}
-                },
             }
         }
         Ok(())
@@ -262,10 +686,18 @@ impl App {
 fn render_title(buf: &mut Buffer, area: Rect) {
     "SDR DB".bold().render(area, buf);
 }
-fn render_footer(area: Rect, buf: &mut Buffer) {
-    Line::raw("◄ ► to change tab | Press q to quit")
-        .centered()
-        .render(area, buf);
+fn render_footer(area: Rect, buf: &mut Buffer, sync_status: Option<SyncStatus>) {
+    let mut footer = String::from("◄ ► to change tab | Press q to quit");
+    if let Some(status) = sync_status {
+        footer.push_str(&format!(" | Sync: {} pending", status.pending));
+        if status.rejected > 0 {
+            footer.push_str(&format!(", {} rejected", status.rejected));
+        }
+        if let Some(error) = status.last_error {
+            footer.push_str(&format!(" | Last sync error: {}", error));
+        }
+    }
+    Line::raw(footer).centered().render(area, buf);
 }
 fn create_log_exit_popup(area: Rect, buf: &mut Buffer, log: &Log) {
     let popup_block = Block::default()
@@ -291,8 +723,9 @@ fn create_log_exit_popup(area: Rect, buf: &mut Buffer, log: &Log) {
 impl Widget for &App {
     fn render(self, area: Rect, buf: &mut Buffer) {
         use Constraint::{Length, Min};
-        let vertical = Layout::vertical([Length(1), Min(0), Length(1)]);
-        let [header_area, inner_area, footer_area] = vertical.areas(area);
+        let error_height = if self.error_message.is_some() { 1 } else { 0 };
+        let vertical = Layout::vertical([Length(1), Min(0), Length(error_height), Length(1)]);
+        let [header_area, inner_area, error_area, footer_area] = vertical.areas(area);
 
         let horizontal = Layout::horizontal([Min(0), Length(20)]);
         let [tabs_area, title_area] = horizontal.areas(header_area);
@@ -308,7 +741,8 @@ impl Widget for &App {
                 }
             }
             SelectedTab::ViewLogs => {
-                self.selected_tab.render_view_logs_tab(inner_area, buf);
+                self.selected_tab
+                    .render_view_logs_tab(&self.view_logs_state, inner_area, buf);
             }
             SelectedTab::SpectrumViewer => {
                 self.selected_tab.render_spectrum_viewer_tab(
@@ -318,166 +752,342 @@ impl Widget for &App {
                 );
             }
         }
-        render_footer(footer_area, buf);
+        if let Some(message) = &self.error_message {
+            Line::raw(message.as_str())
+                .style(Style::default().fg(Color::Red).bold())
+                .centered()
+                .render(error_area, buf);
+        }
+        render_footer(footer_area, buf, self.sync_status.clone());
     }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     color_eyre::install()?;
     tracing_subscriber::fmt::init();
-    let database_url = dotenvy::var("DATABASE_URL").expect("DATABASE_URL must be set ");
-    // Initialize terminal
-    let terminal = ratatui::init();
+    dotenvy::dotenv().ok();
+    let cli = Cli::parse();
 
-    let result = App::new().run(terminal, &database_url);
-    ratatui::restore();
+    match cli.command {
+        Some(Command::RepairCoords {
+            apply,
+            reference_lat,
+            reference_lon,
+            max_distance_km,
+        }) => {
+            let reference = match (reference_lat, reference_lon) {
+                (Some(lat), Some(lon)) => Some(ReferencePosition {
+                    position: Coordinate::new(lat, lon)?,
+                    max_distance_m: max_distance_km * 1000.0,
+                }),
+                _ => None,
+            };
+            let database_url = cli
+                .database_url
+                .as_deref()
+                .ok_or("DATABASE_URL must be set for repair-coords")?;
+            let conn = &mut establish_connection(database_url)?;
+            let report = repair_coordinates(conn, reference, apply)?;
+            println!("{}", report);
+        }
+        Some(Command::Log(ref log_command)) => {
+            let mut store = open_store(&cli.direct_store_config()?)?;
+            run_log_command(store.as_mut(), log_command, cli.timezone)?;
+        }
+        Some(Command::Import { ref path, format }) => {
+            let format = format
+                .or_else(|| LogFormat::from_path(path))
+                .ok_or("cannot tell the format from the file name, pass --format")?;
+            let logs = format.read_logs(&std::fs::read(path)?, cli.timezone)?;
+            let mut target = open_store(&cli.direct_store_config()?)?;
+            let report = merge_logs(target.as_mut(), &mut MemoryLogStore::from_logs(logs))?;
+            println!("{}", report);
+        }
+        Some(Command::Export {
+            format,
+            ref output,
+            ref filter,
+            folders,
+            colours,
+        }) => {
+            let format = format
+                .or_else(|| output.as_deref().and_then(LogFormat::from_path))
+                .unwrap_or(LogFormat::Csv);
+            let filter = LogFilter::new().terms(filter, cli.timezone)?;
+            let mut store = open_store(&cli.direct_store_config()?)?;
+            let mut out: Box<dyn Write> = match output {
+                Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+                None => Box::new(io::stdout().lock()),
+            };
+            match format {
+                LogFormat::GeoJson => {
+                    // Streamed a page at a time, so large exports are not held in memory
+                    let mut features = FeatureWriter::new(&mut out)?;
+                    for_each_page(store.as_mut(), &filter, |logs| {
+                        logs.iter().try_for_each(|log| features.write_log(log))?;
+                        Ok(())
+                    })?;
+                    features.finish()?;
+                }
+                LogFormat::Kml | LogFormat::Kmz => {
+                    let logs = all_logs(store.as_mut(), &filter)?;
+                    let mut session_ids: Vec<i32> =
+                        logs.iter().filter_map(|log| log.session_id).collect();
+                    session_ids.sort_unstable();
+                    session_ids.dedup();
+                    let session_names = store
+                        .sessions(&session_ids)?
+                        .into_iter()
+                        .map(|session| (session.id, session.name))
+                        .collect();
+                    let options = KmlOptions {
+                        folders,
+                        colours,
+                        session_names,
+                    };
+                    if format == LogFormat::Kml {
+                        kml::write_kml(&logs, &options, &mut out)?;
+                    } else {
+                        kml::write_kmz(&logs, &options, &mut out)?;
+                    }
+                }
+                _ => {
+                    let logs = all_logs(store.as_mut(), &filter)?;
+                    format.write_logs(&logs, &mut out)?;
+                }
+            }
+            out.flush()?;
+        }
+        Some(Command::Stats { ref filter, format }) => {
+            let filter = LogFilter::new().terms(filter, cli.timezone)?;
+            let mut store = open_store(&cli.direct_store_config()?)?;
+            let stats = LogStats::from_logs(&all_logs(store.as_mut(), &filter)?);
+            write_stats(&stats, format, &mut io::stdout().lock())?;
+        }
+        Some(Command::Geotag {
+            ref gpx,
+            clock_offset,
+            max_gap,
+            ref filter,
+            apply,
+        }) => {
+            let track = read_track(&std::fs::read_to_string(gpx)?)?;
+            let options = GeotagOptions {
+                clock_offset: seconds(clock_offset)?,
+                max_gap: seconds(max_gap)?,
+            };
+            let filter = LogFilter::new().terms(filter, cli.timezone)?;
+            let mut store = open_store(&cli.direct_store_config()?)?;
+            let report = geotag_logs(store.as_mut(), &filter, &track, &options, apply)?;
+            println!("{}", report);
+        }
+        Some(Command::Merge { ref from }) => {
+            let mut target = open_store(&cli.direct_store_config()?)?;
+            let mut source = open_store(&merge::source_config(from))?;
+            let report = merge_logs(target.as_mut(), source.as_mut())?;
+            println!("{}", report);
+        }
+        Some(Command::Session(ref session_command)) => {
+            let database_url = cli
+                .database_url
+                .as_deref()
+                .ok_or("DATABASE_URL must be set for session")?;
+            let conn = &mut establish_connection(database_url)?;
+            run_session_command(conn, session_command)?;
+        }
+        None | Some(Command::Tui) => {
+            let store = open_store(&cli.store_config()?)?;
 
-    result?;
-    Ok(())
-}
+            // Initialize terminal
+            let terminal = ratatui::init();
 
-/*
-    // OLD CLI CODE - COMMENTED OUT FOR TUI DEVELOPMENT
-    // Parse command line arguments
-    let args = Args::parse();
+            let mut app = App::new(DbWorker::spawn(store)?);
+            app.new_log_form.time_zone = cli.timezone;
+            let result = app.run(terminal);
+            ratatui::restore();
 
-    // Get database URL from args or environment
-    let database_url = args
-        .database_url
-        .or_else(|| env::var("DATABASE_URL").ok())
-        .ok_or("DATABASE_URL must be set")?;
+            result?;
+        }
+    }
+    Ok(())
+}
 
-    info!("Connecting to database...");
-    let mut conn = establish_connection(&database_url);
-    info!("Connected successfully!");
+fn run_log_command(
+    store: &mut dyn LogStore,
+    command: &LogCommand,
+    zone: LogTimeZone,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut out = io::stdout().lock();
+    match command {
+        LogCommand::Add {
+            freq,
+            lat,
+            lon,
+            mode,
+            callsign,
+            comment,
+            duration,
+            time,
+            tag,
+            format,
+        } => {
+            let timestamp = time
+                .as_deref()
+                .map(|time| parse_timestamp(time, zone))
+                .transpose()?;
+            let session = store.active_session()?;
+            let new_log = NewLog::new(
+                *freq,
+                Coordinate::new(*lat, *lon)?,
+                callsign.as_deref().unwrap_or_default(),
+                mode.clone(),
+                comment.as_deref().unwrap_or_default(),
+                *duration,
+                timestamp,
+                LogDetails::default(),
+            )?
+            .with_tags(tag)?
+            .in_session(session.map(|s| s.id));
+            let log = store.create(&new_log)?;
+            format.write_log(&log, &mut out)?;
+        }
+        LogCommand::List {
+            filter,
+            limit,
+            format,
+        } => {
+            let filter = LogFilter::new().terms(filter, zone)?;
+            let page = store.query(&filter, None, *limit)?;
+            format.write_logs(&page.logs, &mut out)?;
+        }
+        LogCommand::Show { id, format } => {
+            let log = store.get(*id)?;
+            format.write_log(&log, &mut out)?;
+        }
+    }
+    Ok(())
+}
 
-    // Check if running in CLI mode (args provided) or interactive mode
-    let cli_mode = args.latitude.is_some() && args.frequency.is_some();
+/// Every log matching `filter`, newest first, fetched a page at a time
+fn all_logs(store: &mut dyn LogStore, filter: &LogFilter) -> Result<Vec<Log>, sdr_db::Error> {
+    let mut logs = Vec::new();
+    for_each_page(store, filter, |page| {
+        logs.extend(page);
+        Ok(())
+    })?;
+    Ok(logs)
+}
 
-    // Interactive mode: continuously read and write logs
-    //ratatui::run(|terminal|  {
+/// Pass each page of logs matching `filter` to `f`, newest first, holding one page
+/// in memory at a time
+fn for_each_page(
+    store: &mut dyn LogStore,
+    filter: &LogFilter,
+    mut f: impl FnMut(Vec<Log>) -> Result<(), sdr_db::Error>,
+) -> Result<(), sdr_db::Error> {
+    let mut after = None;
     loop {
-        if !cli_mode {
-            println!("\n=== SDR Database Entry ===");
-            println!("Enter 'q' to quit");
-        }
-
-        // Get coordinates
-        let latitude = if let Some(lat) = args.latitude {
-            lat
-        } else {
-            println!("Enter latitude [-90, 90]: ");
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
-            if input.trim() == "q" {
-                break;
-            }
-            input.trim().parse::<f32>()?
-        };
-
-        tracing::debug!("Latitude entered: {}", latitude);
+        let page = store.query(filter, after, EXPORT_PAGE_SIZE)?;
+        f(page.logs)?;
+        match page.next {
+            Some(next) => after = Some(next),
+            None => return Ok(()),
+        }
+    }
+}
 
-        let longitude = if let Some(lon) = args.longitude {
-            lon
-        } else {
-            println!("Enter longitude [-180, 180]: ");
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
-            if input.trim() == "q" {
-                break;
+/// Print `stats` as text, a JSON object or `group,name,count` rows
+fn write_stats(stats: &LogStats, format: LogFormat, out: &mut impl Write) -> io::Result<()> {
+    match format {
+        LogFormat::Table => writeln!(out, "{}", stats),
+        LogFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, stats)?;
+            writeln!(out)
+        }
+        LogFormat::Csv => {
+            writeln!(out, "group,name,count")?;
+            writeln!(out, "total,logs,{}", stats.log_count)?;
+            writeln!(out, "total,callsigns,{}", stats.callsign_count)?;
+            for (mode, count) in &stats.by_mode {
+                writeln!(out, "mode,{},{}", csv::escape(mode), count)?;
             }
-            input.trim().parse::<f32>()?
-        };
-
-        // Get frequency
-        let frequency = if let Some(freq) = args.frequency {
-            freq
-        } else {
-            println!("Enter frequency (Hz): ");
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
-            if input.trim() == "q" {
-                break;
+            for (band, count) in &stats.by_band {
+                writeln!(out, "band,{},{}", band, count)?;
             }
-            input.trim().parse::<f32>()?
-        };
-
-        // Get callsign
-        let callsign = if let Some(ref cs) = args.callsign {
-            cs.clone()
-        } else {
-            println!("Enter callsign: ");
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
-            if input.trim() == "q" {
-                break;
+            for (tag, count) in &stats.by_tag {
+                writeln!(out, "tag,{},{}", csv::escape(tag), count)?;
             }
-            input.trim().to_string()
-        };
-        let comment = if let Some(ref c) = args.comment {
-            c.clone()
-        } else {
-            println!("Enter comment (optional): ");
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
-            if input.trim() == "q" {
-                break;
-            }
-            input.trim().to_string()
-        };
+            Ok(())
+        }
+        LogFormat::Adif | LogFormat::Adx | LogFormat::GeoJson | LogFormat::Kml | LogFormat::Kmz => {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "stats are written as table, json or csv",
+            ))
+        }
+    }
+}
 
-        let mode = if !args.mode.is_empty() {
-            args.mode.clone()
-        } else {
-            println!("Enter mode (AM, FM, USB, LSB, CW) ");
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
-            if input.trim() == "q" {
-                break;
-            }
-            parse_mode(input.trim()).to_string()
-        };
-        let recording_duration = if let Some(duration) = args.recording_duration {
-            duration
-        } else {
-            println!("Enter recording duration (in seconds):  ");
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
-            if input.trim() == "q" {
-                break;
-            }
-            input.trim().parse::<f32>()?
-        };
+/// A whole number of milliseconds from a command line value in seconds
+fn seconds(value: f64) -> Result<TimeDelta, ValidationError> {
+    TimeDelta::try_milliseconds((value * 1000.0).round() as i64)
+        .filter(|_| value.is_finite())
+        .ok_or(ValidationError::InvalidNumber {
+            field: "seconds".to_string(),
+            input: value.to_string(),
+        })
+}
 
-        // Write to database
-        info!("Writing log entry to database...");
-        match create_log(
-            &mut conn,
-            frequency,
-            latitude,
-            longitude,
-            callsign.clone(),
-            mode.clone(),
-            Some(comment.clone()),
-            recording_duration,
-        ) {
-            Ok(log) => {
-                info!("✓ Log entry created successfully!");
-                render(&log);
-            }
-            Err(e) => {
-                error!("Failed to create log entry: {}", e);
-                continue;
-            }
-        }
+/// Parse a `--freq` value, reading bare numbers as MHz like the Create Log form
+fn parse_frequency(input: &str) -> Result<Frequency, ValidationError> {
+    Frequency::parse_with_default_unit(input, FrequencyUnit::MHz)
+}
 
-        // If command line args were provided, exit after one entry
-        if cli_mode {
-            break;
+fn run_session_command(
+    conn: &mut PgConnection,
+    command: &SessionCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let operator = default_operator();
+    match command {
+        SessionCommand::Start {
+            name,
+            notes,
+            equipment,
+        } => {
+            let new_session = NewSession::new(
+                name,
+                &operator,
+                chrono::Utc::now(),
+                notes.as_deref(),
+                equipment.as_deref(),
+            )?;
+            let session = start_session(conn, &new_session)?;
+            println!("Started session {} \"{}\"", session.id, session.name);
+        }
+        SessionCommand::Stop { id } => {
+            let id = session_or_active(conn, *id, &operator)?;
+            let session = stop_session(conn, id)?;
+            println!("Stopped session {} \"{}\"", session.id, session.name);
+        }
+        SessionCommand::Summary { id } => {
+            let id = session_or_active(conn, *id, &operator)?;
+            let summary = summarize_session(conn, id, chrono::Utc::now())?;
+            println!("{}", summary);
         }
     }
-
-    info!("Exiting...");
     Ok(())
 }
-*/
+
+/// `id`, or the operator's running session when no id is given
+fn session_or_active(
+    conn: &mut PgConnection,
+    id: Option<i32>,
+    operator: &str,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    match id {
+        Some(id) => Ok(id),
+        None => Ok(active_session(conn, operator)?
+            .ok_or_else(|| format!("{} has no active session", operator))?
+            .id),
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ValidationError;
+
+/// Longest SDR model or serial number `equipment_profiles` holds
+pub const MAX_SDR_FIELD_LEN: usize = 100;
+/// Longest antenna description `equipment_profiles.antenna` holds
+pub const MAX_ANTENNA_LEN: usize = 200;
+
+/// Receive chain a log was measured with, from the `equipment_profiles` table
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::equipment_profiles)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct EquipmentProfile {
+    pub id: i32,
+    pub sdr_model: String,
+    pub serial: Option<String>,
+    pub antenna: Option<String>,
+    /// Feed line loss between antenna and receiver in dB
+    pub cable_loss_db: f32,
+    /// Whether an external or built-in preamplifier was switched on
+    pub preamp: bool,
+    pub lna_gain_db: i32,
+    pub vga_gain_db: i32,
+    /// Frequency error of the receiver's reference oscillator in parts per million
+    pub calibration_offset_ppm: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl EquipmentProfile {
+    /// The settings of this profile, e.g. to compare with a new snapshot
+    pub fn settings(&self) -> NewEquipmentProfile {
+        NewEquipmentProfile {
+            sdr_model: self.sdr_model.clone(),
+            serial: self.serial.clone(),
+            antenna: self.antenna.clone(),
+            cable_loss_db: self.cable_loss_db,
+            preamp: self.preamp,
+            lna_gain_db: self.lna_gain_db,
+            vga_gain_db: self.vga_gain_db,
+            calibration_offset_ppm: self.calibration_offset_ppm,
+        }
+    }
+}
+
+/// Receive chain settings for insertion into the database
+///
+/// Owned so a snapshot can travel with a queued log until it reaches Postgres.
+#[derive(Debug, Clone, Default, PartialEq, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::equipment_profiles)]
+pub struct NewEquipmentProfile {
+    pub sdr_model: String,
+    pub serial: Option<String>,
+    pub antenna: Option<String>,
+    pub cable_loss_db: f32,
+    pub preamp: bool,
+    pub lna_gain_db: i32,
+    pub vga_gain_db: i32,
+    pub calibration_offset_ppm: f32,
+}
+
+impl NewEquipmentProfile {
+    /// A profile for `sdr_model` with no antenna details, losses or gain
+    pub fn new(sdr_model: impl Into<String>) -> Self {
+        NewEquipmentProfile {
+            sdr_model: sdr_model.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Check every field fits its column and holds a physical value
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let invalid = |reason: String| Err(ValidationError::InvalidEquipmentProfile(reason));
+        let model_len = self.sdr_model.trim().chars().count();
+        if model_len == 0 || self.sdr_model.chars().count() > MAX_SDR_FIELD_LEN {
+            return invalid(format!(
+                "SDR model {:?} must be 1-{} characters",
+                self.sdr_model, MAX_SDR_FIELD_LEN
+            ));
+        }
+        if let Some(serial) = &self.serial
+            && serial.chars().count() > MAX_SDR_FIELD_LEN
+        {
+            return invalid(format!(
+                "serial {:?} must be at most {} characters",
+                serial, MAX_SDR_FIELD_LEN
+            ));
+        }
+        if let Some(antenna) = &self.antenna
+            && antenna.chars().count() > MAX_ANTENNA_LEN
+        {
+            return invalid(format!(
+                "antenna {:?} must be at most {} characters",
+                antenna, MAX_ANTENNA_LEN
+            ));
+        }
+        if !self.cable_loss_db.is_finite() || self.cable_loss_db < 0.0 {
+            return invalid(format!(
+                "cable loss {} dB must be finite and not negative",
+                self.cable_loss_db
+            ));
+        }
+        if self.lna_gain_db < 0 || self.vga_gain_db < 0 {
+            return invalid(format!(
+                "gains LNA {} dB, VGA {} dB must not be negative",
+                self.lna_gain_db, self.vga_gain_db
+            ));
+        }
+        if !self.calibration_offset_ppm.is_finite() {
+            return invalid(format!(
+                "calibration offset {} ppm must be finite",
+                self.calibration_offset_ppm
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+use std::fmt;
+use std::str::FromStr;
+
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::BigInt;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ValidationError;
+
+/// Unit used when parsing or displaying a [`Frequency`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrequencyUnit {
+    Hz,
+    KHz,
+    MHz,
+    GHz,
+}
+
+impl FrequencyUnit {
+    /// Number of Hz in one of this unit
+    pub const fn multiplier(&self) -> i64 {
+        match self {
+            FrequencyUnit::Hz => 1,
+            FrequencyUnit::KHz => 1_000,
+            FrequencyUnit::MHz => 1_000_000,
+            FrequencyUnit::GHz => 1_000_000_000,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            FrequencyUnit::Hz => "Hz",
+            FrequencyUnit::KHz => "kHz",
+            FrequencyUnit::MHz => "MHz",
+            FrequencyUnit::GHz => "GHz",
+        }
+    }
+
+    /// Parse a unit suffix such as `M`, `mhz` or `kHz`
+    ///
+    /// Matching is case-insensitive, so `m` means MHz rather than millihertz.
+    fn from_suffix(suffix: &str) -> Option<Self> {
+        match suffix.to_ascii_lowercase().as_str() {
+            "hz" => Some(FrequencyUnit::Hz),
+            "k" | "khz" => Some(FrequencyUnit::KHz),
+            "m" | "mhz" => Some(FrequencyUnit::MHz),
+            "g" | "ghz" => Some(FrequencyUnit::GHz),
+            _ => None,
+        }
+    }
+
+    /// Largest unit in which `hz` is at least 1
+    fn auto(hz: i64) -> Self {
+        match hz.unsigned_abs() {
+            1_000_000_000.. => FrequencyUnit::GHz,
+            1_000_000.. => FrequencyUnit::MHz,
+            1_000.. => FrequencyUnit::KHz,
+            _ => FrequencyUnit::Hz,
+        }
+    }
+}
+
+/// A radio frequency with 1 Hz resolution, stored as integer Hz
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    FromSqlRow,
+    AsExpression,
+)]
+#[diesel(sql_type = BigInt)]
+pub struct Frequency(i64);
+
+impl Frequency {
+    pub const fn from_hz(hz: i64) -> Self {
+        Frequency(hz)
+    }
+
+    /// Create from a floating point value in Hz, rounding to the nearest Hz
+    pub fn from_hz_f64(hz: f64) -> Self {
+        Frequency(hz.round() as i64)
+    }
+
+    pub fn from_khz(khz: f64) -> Self {
+        Self::from_hz_f64(khz * 1e3)
+    }
+
+    pub fn from_mhz(mhz: f64) -> Self {
+        Self::from_hz_f64(mhz * 1e6)
+    }
+
+    pub const fn hz(&self) -> i64 {
+        self.0
+    }
+
+    pub fn as_khz(&self) -> f64 {
+        self.0 as f64 / 1e3
+    }
+
+    pub fn as_mhz(&self) -> f64 {
+        self.0 as f64 / 1e6
+    }
+
+    pub fn as_ghz(&self) -> f64 {
+        self.0 as f64 / 1e9
+    }
+
+    /// Parse a frequency, interpreting a bare number as `default_unit`
+    ///
+    /// Accepts an optional unit suffix (`Hz`, `k`/`kHz`, `M`/`MHz`, `G`/`GHz`, any case),
+    /// optionally separated from the number by whitespace, e.g. `146.52M`, `7.074 MHz`,
+    /// `433920k` or `2.45e9`.
+    ///
+    /// # Errors
+    /// Returns `ValidationError::InvalidFrequencyInput` if the text cannot be parsed and
+    /// `ValidationError::InvalidFrequency` if the value is negative or out of range
+    pub fn parse_with_default_unit(
+        input: &str,
+        default_unit: FrequencyUnit,
+    ) -> Result<Self, ValidationError> {
+        let trimmed = input.trim();
+        let invalid = || ValidationError::InvalidFrequencyInput(input.to_string());
+
+        // Split at the first alphabetic character that cannot be part of the number.
+        // An `e` only starts the unit if it is not followed by an exponent.
+        let bytes = trimmed.as_bytes();
+        let split = (0..bytes.len())
+            .find(|&i| {
+                let c = bytes[i];
+                let is_exponent = matches!(c, b'e' | b'E')
+                    && i > 0
+                    && bytes
+                        .get(i + 1)
+                        .is_some_and(|n| n.is_ascii_digit() || *n == b'-' || *n == b'+');
+                c.is_ascii_alphabetic() && !is_exponent
+            })
+            .unwrap_or(bytes.len());
+        let (number, suffix) = trimmed.split_at(split);
+
+        let unit = match suffix.trim() {
+            "" => default_unit,
+            s => FrequencyUnit::from_suffix(s).ok_or_else(invalid)?,
+        };
+        let value: f64 = number.trim().parse().map_err(|_| invalid())?;
+
+        let hz = value * unit.multiplier() as f64;
+        if !hz.is_finite() || hz < 0.0 || hz > i64::MAX as f64 {
+            return Err(ValidationError::InvalidFrequency(hz));
+        }
+        Ok(Self::from_hz_f64(hz))
+    }
+
+    /// Format in a specific unit, with trailing zeros trimmed
+    pub fn display_in(&self, unit: FrequencyUnit) -> String {
+        let multiplier = unit.multiplier();
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let whole = abs / multiplier as u64;
+        let frac = abs % multiplier as u64;
+        if frac == 0 {
+            return format!("{}{} {}", sign, whole, unit.as_str());
+        }
+        let digits = multiplier.ilog10() as usize;
+        let frac = format!("{:0width$}", frac, width = digits);
+        format!(
+            "{}{}.{} {}",
+            sign,
+            whole,
+            frac.trim_end_matches('0'),
+            unit.as_str()
+        )
+    }
+
+    /// ITU band the frequency falls in, None outside 3 kHz to 300 GHz
+    pub fn band(&self) -> Option<Band> {
+        Band::ALL.into_iter().find(|band| {
+            let (low, high) = band.range_hz();
+            (low..high).contains(&self.0)
+        })
+    }
+}
+
+/// ITU radio band designations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Band {
+    VLF,
+    LF,
+    MF,
+    HF,
+    VHF,
+    UHF,
+    SHF,
+    EHF,
+}
+
+impl Band {
+    pub const ALL: [Band; 8] = [
+        Band::VLF,
+        Band::LF,
+        Band::MF,
+        Band::HF,
+        Band::VHF,
+        Band::UHF,
+        Band::SHF,
+        Band::EHF,
+    ];
+
+    /// Lower bound (inclusive) and upper bound (exclusive) in Hz
+    pub const fn range_hz(&self) -> (i64, i64) {
+        match self {
+            Band::VLF => (3_000, 30_000),
+            Band::LF => (30_000, 300_000),
+            Band::MF => (300_000, 3_000_000),
+            Band::HF => (3_000_000, 30_000_000),
+            Band::VHF => (30_000_000, 300_000_000),
+            Band::UHF => (300_000_000, 3_000_000_000),
+            Band::SHF => (3_000_000_000, 30_000_000_000),
+            Band::EHF => (30_000_000_000, 300_000_000_000),
+        }
+    }
+}
+
+impl fmt::Display for Band {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = ValidationError;
+
+    /// Parse a frequency, treating a bare number as Hz
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_default_unit(s, FrequencyUnit::Hz)
+    }
+}
+
+/// Formats with an automatically chosen unit, e.g. `146.52 MHz`
+///
+/// Without a precision the value is exact to 1 Hz; with one (`{:.3}`) it is rounded.
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = FrequencyUnit::auto(self.0);
+        match f.precision() {
+            Some(precision) => write!(
+                f,
+                "{:.*} {}",
+                precision,
+                self.0 as f64 / unit.multiplier() as f64,
+                unit.as_str()
+            ),
+            None => write!(f, "{}", self.display_in(unit)),
+        }
+    }
+}
+
+impl ToSql<BigInt, Pg> for Frequency {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <i64 as ToSql<BigInt, Pg>>::to_sql(&self.0, out)
+    }
+}
+
+impl FromSql<BigInt, Pg> for Frequency {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        <i64 as FromSql<BigInt, Pg>>::from_sql(bytes).map(Frequency)
+    }
+}
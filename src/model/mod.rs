@@ -1,3 +1,21 @@
+pub mod equipment;
+pub mod frequency;
+pub mod mode;
 pub mod model;
+pub mod revision;
+pub mod session;
+pub mod stats;
+pub mod tag;
+pub mod timestamp;
+pub mod uuid;
 
-pub use model::{Log, NewLog};
+pub use equipment::{EquipmentProfile, NewEquipmentProfile};
+pub use frequency::{Band, Frequency, FrequencyUnit};
+pub use mode::SignalMode;
+pub use model::{Log, LogChanges, LogDetails, NewLog};
+pub use revision::{LogRevision, RevisionAction};
+pub use session::{NewSession, Session, SessionSummary};
+pub use stats::LogStats;
+pub use tag::Tag;
+pub use timestamp::{LogTimeZone, parse_timestamp};
+pub use uuid::LogUuid;
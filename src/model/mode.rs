@@ -0,0 +1,172 @@
+use std::fmt;
+use std::str::FromStr;
+
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::Text;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::ValidationError;
+
+/// Maximum length of a mode name, matching the `logs.mode` column
+pub const MAX_MODE_LEN: usize = 20;
+
+/// Modulation or digital mode of a logged signal
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromSqlRow, AsExpression)]
+#[diesel(sql_type = Text)]
+pub enum SignalMode {
+    FM,
+    AM,
+    USB,
+    LSB,
+    CW,
+    /// Wideband FM broadcast
+    WFM,
+    /// Narrowband FM
+    NFM,
+    DMR,
+    P25,
+    DStar,
+    FT8,
+    RTTY,
+    APRS,
+    ADSB,
+    /// Digital signal that has not been identified
+    Digital,
+    /// Any mode without a dedicated variant
+    Other(String),
+}
+
+impl SignalMode {
+    /// Every mode with a dedicated variant, in UI cycling order
+    pub const ALL: &'static [SignalMode] = &[
+        SignalMode::AM,
+        SignalMode::FM,
+        SignalMode::WFM,
+        SignalMode::NFM,
+        SignalMode::USB,
+        SignalMode::LSB,
+        SignalMode::CW,
+        SignalMode::RTTY,
+        SignalMode::FT8,
+        SignalMode::APRS,
+        SignalMode::DMR,
+        SignalMode::P25,
+        SignalMode::DStar,
+        SignalMode::ADSB,
+        SignalMode::Digital,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            SignalMode::FM => "FM",
+            SignalMode::AM => "AM",
+            SignalMode::USB => "USB",
+            SignalMode::LSB => "LSB",
+            SignalMode::CW => "CW",
+            SignalMode::WFM => "WFM",
+            SignalMode::NFM => "NFM",
+            SignalMode::DMR => "DMR",
+            SignalMode::P25 => "P25",
+            SignalMode::DStar => "D-STAR",
+            SignalMode::FT8 => "FT8",
+            SignalMode::RTTY => "RTTY",
+            SignalMode::APRS => "APRS",
+            SignalMode::ADSB => "ADS-B",
+            SignalMode::Digital => "DIGITAL",
+            SignalMode::Other(name) => name,
+        }
+    }
+
+    /// Next mode in [`SignalMode::ALL`], wrapping around; `Other` moves to the first mode
+    pub fn next(&self) -> SignalMode {
+        let idx = self.position().map_or(0, |i| (i + 1) % Self::ALL.len());
+        Self::ALL[idx].clone()
+    }
+
+    /// Previous mode in [`SignalMode::ALL`], wrapping around; `Other` moves to the last mode
+    pub fn previous(&self) -> SignalMode {
+        let idx = self.position().map_or(Self::ALL.len() - 1, |i| {
+            (i + Self::ALL.len() - 1) % Self::ALL.len()
+        });
+        Self::ALL[idx].clone()
+    }
+
+    fn position(&self) -> Option<usize> {
+        Self::ALL.iter().position(|m| m == self)
+    }
+}
+
+impl fmt::Display for SignalMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SignalMode {
+    type Err = ValidationError;
+
+    /// Parse a mode name, ignoring case and `-`/`_`/space separators
+    ///
+    /// Unrecognised names become `SignalMode::Other`, uppercased.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        if trimmed.is_empty() || trimmed.len() > MAX_MODE_LEN {
+            return Err(ValidationError::InvalidMode(s.to_string()));
+        }
+        let normalized: String = trimmed
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .collect::<String>()
+            .to_ascii_uppercase();
+
+        Ok(match normalized.as_str() {
+            "FM" => SignalMode::FM,
+            "AM" => SignalMode::AM,
+            "USB" => SignalMode::USB,
+            "LSB" => SignalMode::LSB,
+            "CW" => SignalMode::CW,
+            "WFM" => SignalMode::WFM,
+            "NFM" => SignalMode::NFM,
+            "DMR" => SignalMode::DMR,
+            "P25" => SignalMode::P25,
+            "DSTAR" => SignalMode::DStar,
+            "FT8" => SignalMode::FT8,
+            "RTTY" => SignalMode::RTTY,
+            "APRS" => SignalMode::APRS,
+            "ADSB" => SignalMode::ADSB,
+            "DIGITAL" => SignalMode::Digital,
+            _ => SignalMode::Other(trimmed.to_ascii_uppercase()),
+        })
+    }
+}
+
+impl Serialize for SignalMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SignalMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl ToSql<Text, Pg> for SignalMode {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <str as ToSql<Text, Pg>>::to_sql(self.as_str(), out)
+    }
+}
+
+/// Stored names [`SignalMode::from_str`] rejects, such as an empty one, are read back
+/// unchanged as `SignalMode::Other` rather than failing every query that reaches the row
+impl FromSql<Text, Pg> for SignalMode {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let name = <String as FromSql<Text, Pg>>::from_sql(bytes)?;
+        Ok(name.parse().unwrap_or(SignalMode::Other(name)))
+    }
+}
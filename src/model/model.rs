@@ -1,118 +1,511 @@
 use crate::error::ValidationError;
-use chrono::{DateTime, NaiveDateTime, Utc};
+use crate::model::tag::normalize_tags;
+use crate::model::timestamp::{deserialize_utc, validate_timestamp};
+use crate::model::{Frequency, LogUuid};
+use crate::spatial::{Coordinate, DbPoint};
+use chrono::{DateTime, Utc};
+use diesel::dsl::sql;
+use diesel::expression::SqlLiteral;
 use diesel::prelude::*;
-use serde::Serialize;
-//Frequency is in MHz
-#[derive(Serialize, Debug, Clone, Copy)]
-pub enum SignalMode {
-    FM,
-    AM,
-    USB,
-    LSB,
-    CW,
-}
+use diesel::sql_types::{Array, Text};
+use geo::Point;
+use serde::{Deserialize, Serialize};
+
+pub use crate::model::mode::SignalMode;
 
 /// Database representation of an SDR measurement log entry
-#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::logs)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Log {
     pub id: i32,
-    pub frequency: f32,
+    pub frequency: Frequency,
+    /// Latitude in decimal degrees
     pub xcoord: f32,
+    /// Longitude in decimal degrees
     pub ycoord: f32,
     pub callsign: Option<String>,
-    pub mode: String,
+    pub mode: SignalMode,
     pub comment: Option<String>,
-    pub timestamp: NaiveDateTime,
+    #[serde(deserialize_with = "deserialize_utc")]
+    pub timestamp: DateTime<Utc>,
     pub recording_duration: f32,
+    /// PostGIS position, None for legacy rows whose coordinates could not be placed
+    #[serde(skip)]
+    pub location: Option<DbPoint>,
+    pub power_dbm: Option<f32>,
+    pub snr_db: Option<f32>,
+    pub bandwidth_hz: Option<Frequency>,
+    pub altitude_m: Option<f32>,
+    pub position_accuracy_m: Option<f32>,
+    pub heading_deg: Option<f32>,
+    pub speed_mps: Option<f32>,
+    /// Client-generated identity, unique across databases
+    pub uuid: LogUuid,
+    /// Survey session the log was recorded in
+    pub session_id: Option<i32>,
+    /// Receive chain the log was measured with
+    pub equipment_profile_id: Option<i32>,
+    /// Names of the log's tags, sorted, gathered from `log_tags`
+    #[diesel(select_expression = tag_names())]
+    #[diesel(select_expression_type = SqlLiteral<Array<Text>>)]
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Sorted tag names of the `logs` row being selected, empty without tags
+fn tag_names() -> SqlLiteral<Array<Text>> {
+    sql::<Array<Text>>(
+        "COALESCE((SELECT array_agg(tags.name::text ORDER BY tags.name) \
+         FROM log_tags JOIN tags ON tags.id = log_tags.tag_id \
+         WHERE log_tags.log_id = logs.id), '{}'::text[])",
+    )
+}
+
+/// Optional signal and GPS measurements recorded alongside a log entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogDetails {
+    /// Received signal power in dBm
+    pub power_dbm: Option<f32>,
+    /// Signal-to-noise ratio in dB
+    pub snr_db: Option<f32>,
+    /// Occupied bandwidth of the signal
+    pub bandwidth_hz: Option<Frequency>,
+    /// Altitude above mean sea level in metres
+    pub altitude_m: Option<f32>,
+    /// Horizontal GPS accuracy in metres
+    pub position_accuracy_m: Option<f32>,
+    /// Direction of travel in degrees clockwise from true north, [0, 360)
+    pub heading_deg: Option<f32>,
+    /// Ground speed in metres per second
+    pub speed_mps: Option<f32>,
 }
 
 /// New log entry for insertion into database
-#[derive(Insertable)]
+#[derive(Clone, Insertable)]
 #[diesel(table_name = crate::schema::logs)]
 pub struct NewLog<'a> {
-    pub frequency: f32,
+    pub frequency: Frequency,
+    /// Latitude in decimal degrees
     pub xcoord: f32,
+    /// Longitude in decimal degrees
     pub ycoord: f32,
     pub callsign: &'a str,
-    pub mode: &'a str,
+    pub mode: SignalMode,
     pub comment: &'a str,
     pub recording_duration: f32,
-    pub timestamp: NaiveDateTime,
+    pub timestamp: DateTime<Utc>,
+    pub location: DbPoint,
+    pub power_dbm: Option<f32>,
+    pub snr_db: Option<f32>,
+    pub bandwidth_hz: Option<Frequency>,
+    pub altitude_m: Option<f32>,
+    pub position_accuracy_m: Option<f32>,
+    pub heading_deg: Option<f32>,
+    pub speed_mps: Option<f32>,
+    pub uuid: LogUuid,
+    pub session_id: Option<i32>,
+    pub equipment_profile_id: Option<i32>,
+    /// Written to `log_tags` after the row is inserted
+    #[diesel(skip_insertion)]
+    pub tags: Vec<String>,
 }
 
 impl Log {
-    /// Get timestamp as DateTime<Utc>
-    pub fn timestamp_utc(&self) -> DateTime<Utc> {
-        DateTime::<Utc>::from_naive_utc_and_offset(self.timestamp, Utc)
+    pub fn frequency_hz(&self) -> f64 {
+        self.frequency.hz() as f64
     }
 
-    pub fn frequency_hz(&self) -> f64 {
-        self.frequency as f64
+    /// Get position as a geo Point (x = longitude, y = latitude)
+    pub fn point(&self) -> Option<Point<f64>> {
+        self.location.map(Point::from)
+    }
+
+    /// Get the validated position from the `xcoord` (latitude) and `ycoord` (longitude) columns
+    pub fn coordinate(&self) -> Result<Coordinate, ValidationError> {
+        Coordinate::checked(self.xcoord as f64, self.ycoord as f64)
+    }
+
+    /// Get the optional signal and GPS measurements
+    pub fn details(&self) -> LogDetails {
+        LogDetails {
+            power_dbm: self.power_dbm,
+            snr_db: self.snr_db,
+            bandwidth_hz: self.bandwidth_hz,
+            altitude_m: self.altitude_m,
+            position_accuracy_m: self.position_accuracy_m,
+            heading_deg: self.heading_deg,
+            speed_mps: self.speed_mps,
+        }
+    }
+
+    /// The entry that would recreate this log, keeping its uuid, e.g. in another store
+    ///
+    /// Rows without a `location` are placed from their coordinate columns.
+    pub fn as_new_log(&self) -> NewLog<'_> {
+        let details = self.details();
+        NewLog {
+            frequency: self.frequency,
+            xcoord: self.xcoord,
+            ycoord: self.ycoord,
+            callsign: self.callsign.as_deref().unwrap_or_default(),
+            mode: self.mode.clone(),
+            comment: self.comment.as_deref().unwrap_or_default(),
+            recording_duration: self.recording_duration,
+            timestamp: self.timestamp,
+            location: self
+                .location
+                .unwrap_or_else(|| DbPoint(Point::new(self.ycoord as f64, self.xcoord as f64))),
+            power_dbm: details.power_dbm,
+            snr_db: details.snr_db,
+            bandwidth_hz: details.bandwidth_hz,
+            altitude_m: details.altitude_m,
+            position_accuracy_m: details.position_accuracy_m,
+            heading_deg: details.heading_deg,
+            speed_mps: details.speed_mps,
+            uuid: self.uuid,
+            session_id: self.session_id,
+            equipment_profile_id: self.equipment_profile_id,
+            tags: self.tags.clone(),
+        }
+    }
+}
+
+impl LogDetails {
+    /// Check every present value is finite and within its physical range
+    ///
+    /// # Errors
+    /// Returns the `ValidationError` variant for the first invalid value
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(power) = self.power_dbm
+            && !power.is_finite()
+        {
+            return Err(ValidationError::InvalidPower(power as f64));
+        }
+        if let Some(snr) = self.snr_db
+            && !snr.is_finite()
+        {
+            return Err(ValidationError::InvalidSnr(snr as f64));
+        }
+        if let Some(bandwidth) = self.bandwidth_hz
+            && bandwidth.hz() <= 0
+        {
+            return Err(ValidationError::InvalidBandwidth(bandwidth.hz() as f64));
+        }
+        if let Some(altitude) = self.altitude_m
+            && !(-1_000.0..=100_000.0).contains(&altitude)
+        {
+            return Err(ValidationError::InvalidAltitude(altitude as f64));
+        }
+        if let Some(accuracy) = self.position_accuracy_m
+            && !(accuracy.is_finite() && accuracy >= 0.0)
+        {
+            return Err(ValidationError::InvalidPositionAccuracy(accuracy as f64));
+        }
+        if let Some(heading) = self.heading_deg
+            && !(0.0..360.0).contains(&heading)
+        {
+            return Err(ValidationError::InvalidHeading(heading as f64));
+        }
+        if let Some(speed) = self.speed_mps
+            && !(speed.is_finite() && speed >= 0.0)
+        {
+            return Err(ValidationError::InvalidSpeed(speed as f64));
+        }
+        Ok(())
     }
 }
 
 impl<'a> NewLog<'a> {
+    /// Get the optional signal and GPS measurements
+    pub fn details(&self) -> LogDetails {
+        LogDetails {
+            power_dbm: self.power_dbm,
+            snr_db: self.snr_db,
+            bandwidth_hz: self.bandwidth_hz,
+            altitude_m: self.altitude_m,
+            position_accuracy_m: self.position_accuracy_m,
+            heading_deg: self.heading_deg,
+            speed_mps: self.speed_mps,
+        }
+    }
+
     /// Create a new NewLog with validation
     ///
+    /// The entry is given a freshly generated [`LogUuid`].
+    ///
     /// # Arguments
     /// * `frequency` - Signal frequency in Hz (must be positive)
-    /// * `location` - Geographic position (WGS84, as DbPoint)
+    /// * `position` - Geographic position (WGS84)
     /// * `callsign` - Station callsign
-    /// * `bandwidth` - Signal bandwidth in Hz
-    /// * `mode` - Operating mode (e.g., "FM", "AM", "SSB")
-    /// * `power` - Optional signal power in dBm
-    /// * `snr` - Optional Signal-to-Noise Ratio in dB
+    /// * `mode` - Operating mode
     /// * `comment` - Optional comment
+    /// * `recording_duration` - Recording length in seconds (must not be negative)
+    /// * `timestamp` - Time the signal was logged, now if None (1900 up to now)
+    /// * `details` - Optional signal and GPS measurements
     ///
     /// # Errors
-    /// Returns `ValidationError::InvalidFrequency` if frequency is not positive
+    /// Returns `ValidationError::InvalidFrequency` if frequency is not positive,
+    /// `ValidationError::InvalidTimestamp` for a time [`validate_timestamp`] rejects, or
+    /// the error from [`LogDetails::validate`]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        frequency: f32,
-        xcoord: f32,
-        ycoord: f32,
+        frequency: Frequency,
+        position: Coordinate,
         callsign: &'a str,
-        mode: &'a str,
+        mode: SignalMode,
         comment: &'a str,
         recording_duration: f32,
-        timestamp: NaiveDateTime,
+        timestamp: Option<DateTime<Utc>>,
+        details: LogDetails,
     ) -> Result<Self, ValidationError> {
-        // Validate frequency must be positive
-        if frequency <= 0.0 {
-            return Err(ValidationError::InvalidFrequency(frequency as f64));
+        let new_log = NewLog {
+            frequency,
+            xcoord: position.lat() as f32,
+            ycoord: position.lon() as f32,
+            callsign,
+            mode,
+            comment,
+            recording_duration,
+            timestamp: timestamp.unwrap_or_else(Utc::now),
+            location: position.into(),
+            power_dbm: details.power_dbm,
+            snr_db: details.snr_db,
+            bandwidth_hz: details.bandwidth_hz,
+            altitude_m: details.altitude_m,
+            position_accuracy_m: details.position_accuracy_m,
+            heading_deg: details.heading_deg,
+            speed_mps: details.speed_mps,
+            uuid: LogUuid::new_v7(),
+            session_id: None,
+            equipment_profile_id: None,
+            tags: Vec::new(),
+        };
+        new_log.validate()?;
+        Ok(new_log)
+    }
+
+    /// Attach the entry to a survey session
+    pub fn in_session(mut self, session_id: Option<i32>) -> Self {
+        self.session_id = session_id;
+        self
+    }
+
+    /// File the entry under `tags`, normalized with [`normalize_tag`](crate::model::tag::normalize_tag)
+    pub fn with_tags<S: AsRef<str>>(
+        mut self,
+        tags: impl IntoIterator<Item = S>,
+    ) -> Result<Self, ValidationError> {
+        self.tags = normalize_tags(tags)?;
+        Ok(self)
+    }
+
+    /// Record the receive chain the entry was measured with
+    pub fn with_equipment_profile(mut self, equipment_profile_id: Option<i32>) -> Self {
+        self.equipment_profile_id = equipment_profile_id;
+        self
+    }
+
+    /// The row this entry becomes once stored under `id`
+    pub fn to_log(&self, id: i32) -> Log {
+        Log {
+            id,
+            frequency: self.frequency,
+            xcoord: self.xcoord,
+            ycoord: self.ycoord,
+            callsign: Some(self.callsign.to_string()),
+            mode: self.mode.clone(),
+            comment: Some(self.comment.to_string()),
+            timestamp: self.timestamp,
+            recording_duration: self.recording_duration,
+            location: Some(self.location),
+            power_dbm: self.power_dbm,
+            snr_db: self.snr_db,
+            bandwidth_hz: self.bandwidth_hz,
+            altitude_m: self.altitude_m,
+            position_accuracy_m: self.position_accuracy_m,
+            heading_deg: self.heading_deg,
+            speed_mps: self.speed_mps,
+            uuid: self.uuid,
+            session_id: self.session_id,
+            equipment_profile_id: self.equipment_profile_id,
+            tags: normalize_tags(&self.tags).unwrap_or_else(|_| self.tags.clone()),
+        }
+    }
+
+    /// Check the fields hold the same invariants [`NewLog::new`] enforces, and that
+    /// `location` is the point in `xcoord` and `ycoord`
+    ///
+    /// Useful for entries built as struct literals, e.g. by importers.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validate_frequency(self.frequency)?;
+        Coordinate::checked(self.xcoord as f64, self.ycoord as f64)?;
+        validate_recording_duration(self.recording_duration)?;
+        validate_timestamp(self.timestamp)?;
+        normalize_tags(&self.tags)?;
+        self.details().validate()?;
+        validate_location(self.location, self.xcoord, self.ycoord)
+    }
+}
+
+/// Partial update of a log entry; `None` fields are left unchanged
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogChanges {
+    pub frequency: Option<Frequency>,
+    /// Rewrites `xcoord`, `ycoord` and `location` together
+    pub position: Option<Coordinate>,
+    pub callsign: Option<String>,
+    pub mode: Option<SignalMode>,
+    pub comment: Option<String>,
+    pub recording_duration: Option<f32>,
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Replaces every measurement column, clearing the ones that are None
+    pub details: Option<LogDetails>,
+    /// Moves the log to another session, or out of any with `Some(None)`
+    pub session_id: Option<Option<i32>>,
+    /// Points the log at another equipment profile, or clears it with `Some(None)`
+    pub equipment_profile_id: Option<Option<i32>>,
+    /// Replaces every tag of the log
+    pub tags: Option<Vec<String>>,
+}
+
+/// Changes that set every field to its value in `log`, e.g. to restore an earlier version
+impl TryFrom<&Log> for LogChanges {
+    type Error = ValidationError;
+
+    fn try_from(log: &Log) -> Result<Self, Self::Error> {
+        Ok(LogChanges {
+            frequency: Some(log.frequency),
+            position: Some(log.coordinate()?),
+            callsign: Some(log.callsign.clone().unwrap_or_default()),
+            mode: Some(log.mode.clone()),
+            comment: Some(log.comment.clone().unwrap_or_default()),
+            recording_duration: Some(log.recording_duration),
+            timestamp: Some(log.timestamp),
+            details: Some(log.details()),
+            session_id: Some(log.session_id),
+            equipment_profile_id: Some(log.equipment_profile_id),
+            tags: Some(log.tags.clone()),
+        })
+    }
+}
+
+impl LogChanges {
+    /// Whether no field would be changed
+    pub fn is_empty(&self) -> bool {
+        *self == LogChanges::default()
+    }
+
+    /// Apply the changes to a log held in memory
+    pub fn apply(&self, log: &mut Log) {
+        if let Some(frequency) = self.frequency {
+            log.frequency = frequency;
+        }
+        if let Some(position) = self.position {
+            log.xcoord = position.lat() as f32;
+            log.ycoord = position.lon() as f32;
+            log.location = Some(position.into());
+        }
+        if let Some(callsign) = &self.callsign {
+            log.callsign = Some(callsign.clone());
+        }
+        if let Some(mode) = &self.mode {
+            log.mode = mode.clone();
+        }
+        if let Some(comment) = &self.comment {
+            log.comment = Some(comment.clone());
         }
-        if !(-180.0..=180.).contains(&xcoord) {
-            return Err(ValidationError::InvalidLatitude(xcoord as f64));
+        if let Some(duration) = self.recording_duration {
+            log.recording_duration = duration;
         }
-        if !(-90. ..=90.).contains(&ycoord) {
-            return Err(ValidationError::InvalidLongitude(ycoord as f64));
+        if let Some(timestamp) = self.timestamp {
+            log.timestamp = timestamp;
         }
-        if recording_duration < 0. {
-            return Err(ValidationError::InvalidRecordingDuration(
-                recording_duration,
-            ));
+        if let Some(details) = self.details {
+            log.power_dbm = details.power_dbm;
+            log.snr_db = details.snr_db;
+            log.bandwidth_hz = details.bandwidth_hz;
+            log.altitude_m = details.altitude_m;
+            log.position_accuracy_m = details.position_accuracy_m;
+            log.heading_deg = details.heading_deg;
+            log.speed_mps = details.speed_mps;
         }
+        if let Some(session_id) = self.session_id {
+            log.session_id = session_id;
+        }
+        if let Some(equipment_profile_id) = self.equipment_profile_id {
+            log.equipment_profile_id = equipment_profile_id;
+        }
+        if let Some(tags) = &self.tags {
+            log.tags = normalize_tags(tags).unwrap_or_else(|_| tags.clone());
+        }
+    }
 
-        Ok(NewLog {
-            frequency,
+    /// Validate the changed fields with the same rules as [`NewLog::new`]
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(frequency) = self.frequency {
+            validate_frequency(frequency)?;
+        }
+        if let Some(duration) = self.recording_duration {
+            validate_recording_duration(duration)?;
+        }
+        if let Some(timestamp) = self.timestamp {
+            validate_timestamp(timestamp)?;
+        }
+        if let Some(details) = &self.details {
+            details.validate()?;
+        }
+        if let Some(tags) = &self.tags {
+            normalize_tags(tags)?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_frequency(frequency: Frequency) -> Result<(), ValidationError> {
+    if frequency.hz() <= 0 {
+        return Err(ValidationError::InvalidFrequency(frequency.hz() as f64));
+    }
+    Ok(())
+}
+
+/// How far in degrees `location` may be from the coordinate columns, which only hold
+/// `f32` precision
+const LOCATION_TOLERANCE_DEG: f64 = 1e-4;
+
+/// Check `location` is the point in the `xcoord` (latitude) and `ycoord` (longitude) columns
+fn validate_location(location: DbPoint, xcoord: f32, ycoord: f32) -> Result<(), ValidationError> {
+    let lat_diff = (location.0.y() - xcoord as f64).abs();
+    // 180° and -180° are the same meridian
+    let lon_diff = (location.0.x() - ycoord as f64).abs() % 360.0;
+    let lon_diff = lon_diff.min(360.0 - lon_diff);
+    if !(lat_diff <= LOCATION_TOLERANCE_DEG && lon_diff <= LOCATION_TOLERANCE_DEG) {
+        return Err(ValidationError::InvalidGeometry(format!(
+            "location ({}, {}) does not match the coordinates ({}, {})",
+            location.0.y(),
+            location.0.x(),
             xcoord,
-            ycoord,
-            callsign,
-            mode,
-            comment,
+            ycoord
+        )));
+    }
+    Ok(())
+}
+
+fn validate_recording_duration(recording_duration: f32) -> Result<(), ValidationError> {
+    if recording_duration < 0. {
+        return Err(ValidationError::InvalidRecordingDuration(
             recording_duration,
-            timestamp,
-        })
+        ));
     }
+    Ok(())
 }
 
 /// Render a log entry to the console
 pub fn render_log(log: &Log) -> String {
     let log_string = format!(
-        "{} MHz | Callsign: {} | Coordinate: ({}, {}) \n
+        "{} | Callsign: {} | Coordinate: ({}, {}) \n
         | Comment: {:?} | Mode: {} | Recorded at: {} \n
-        | Duration: {:.2} sec",
+        | Duration: {:.2} sec{}",
         log.frequency,
         log.callsign.as_deref().unwrap_or("").to_uppercase(),
         log.xcoord,
@@ -121,15 +514,16 @@ pub fn render_log(log: &Log) -> String {
         log.mode,
         log.timestamp,
         log.recording_duration,
+        render_details(&log.details()),
     );
     log_string
 }
 
 pub fn render_new_log(new_log: &NewLog) -> String {
     let log_string = format!(
-        "{} MHz | Callsign: {} | Coordinate: ({}, {}) \n 
+        "{} | Callsign: {} | Coordinate: ({}, {}) \n 
         | Comment: {:?} | Mode: {} | Recorded at: {} \n
-        | Duration: {:.2} sec",
+        | Duration: {:.2} sec{}",
         new_log.frequency,
         new_log.callsign.to_uppercase(),
         new_log.xcoord,
@@ -138,30 +532,43 @@ pub fn render_new_log(new_log: &NewLog) -> String {
         new_log.mode,
         new_log.timestamp,
         new_log.recording_duration,
+        render_details(&new_log.details()),
     );
     log_string
 }
 
-impl SignalMode {
-    pub fn to_str(&self) -> &str {
-        match self {
-            SignalMode::FM => "FM",
-            SignalMode::AM => "AM",
-            SignalMode::USB => "USB",
-            SignalMode::LSB => "LSB",
-            SignalMode::CW => "CW",
-        }
+/// Render the measurements that are present, one `| Name: value` segment each
+fn render_details(details: &LogDetails) -> String {
+    let parts = detail_lines(details);
+    if parts.is_empty() {
+        return String::new();
     }
+    format!(" \n\n        | {}", parts.join(" | "))
 }
 
-// WIP: parsing mode from input
-pub fn parse_mode(mode: &str) -> &str {
-    match mode.to_lowercase().as_str() {
-        "fm" => "FM",
-        "am" => "AM",
-        "usb" => "USB",
-        "lsb" => "LSB",
-        "cw" => "CW",
-        _ => "UNKNOWN",
+/// The measurements that are present as `Name: value` text, e.g. `SNR: 11.5 dB`
+pub(crate) fn detail_lines(details: &LogDetails) -> Vec<String> {
+    let mut parts = Vec::new();
+    if let Some(power) = details.power_dbm {
+        parts.push(format!("Power: {:.1} dBm", power));
+    }
+    if let Some(snr) = details.snr_db {
+        parts.push(format!("SNR: {:.1} dB", snr));
+    }
+    if let Some(bandwidth) = details.bandwidth_hz {
+        parts.push(format!("Bandwidth: {}", bandwidth));
+    }
+    if let Some(altitude) = details.altitude_m {
+        parts.push(format!("Altitude: {:.1} m", altitude));
+    }
+    if let Some(accuracy) = details.position_accuracy_m {
+        parts.push(format!("Accuracy: ±{:.1} m", accuracy));
+    }
+    if let Some(heading) = details.heading_deg {
+        parts.push(format!("Heading: {:.0}°", heading));
+    }
+    if let Some(speed) = details.speed_mps {
+        parts.push(format!("Speed: {:.1} m/s", speed));
     }
+    parts
 }
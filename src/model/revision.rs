@@ -0,0 +1,123 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::prelude::*;
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::Text;
+use serde::Deserialize;
+
+use crate::error::{DatabaseError, ValidationError};
+use crate::model::Log;
+
+/// Longest operator name `log_revisions.operator` holds
+pub const MAX_OPERATOR_LEN: usize = 50;
+
+/// What a revision did to its log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromSqlRow, AsExpression)]
+#[diesel(sql_type = Text)]
+pub enum RevisionAction {
+    Update,
+    /// Soft delete; the row stays in `logs` with `deleted_at` set
+    Delete,
+    /// Rewrite to the values before an earlier revision, undeleting the log if needed
+    Restore,
+}
+
+impl RevisionAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RevisionAction::Update => "update",
+            RevisionAction::Delete => "delete",
+            RevisionAction::Restore => "restore",
+        }
+    }
+}
+
+impl fmt::Display for RevisionAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ToSql<Text, Pg> for RevisionAction {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <str as ToSql<Text, Pg>>::to_sql(self.as_str(), out)
+    }
+}
+
+impl FromSql<Text, Pg> for RevisionAction {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match <String as FromSql<Text, Pg>>::from_sql(bytes)?.as_str() {
+            "update" => Ok(RevisionAction::Update),
+            "delete" => Ok(RevisionAction::Delete),
+            "restore" => Ok(RevisionAction::Restore),
+            other => Err(format!("Unknown revision action: {:?}", other).into()),
+        }
+    }
+}
+
+/// A recorded change to a log, from the `log_revisions` table
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = crate::schema::log_revisions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct LogRevision {
+    pub id: i32,
+    pub log_id: i32,
+    pub action: RevisionAction,
+    /// Snapshot of the log before the change
+    pub old_values: serde_json::Value,
+    /// Snapshot after the change, None for deletes
+    pub new_values: Option<serde_json::Value>,
+    /// Who made the change
+    pub operator: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl LogRevision {
+    /// The log as it was before this change
+    pub fn old_log(&self) -> Result<Log, DatabaseError> {
+        snapshot_to_log(&self.old_values)
+    }
+
+    /// The log as this change left it, None for deletes
+    pub fn new_log(&self) -> Result<Option<Log>, DatabaseError> {
+        self.new_values.as_ref().map(snapshot_to_log).transpose()
+    }
+}
+
+/// Serialize a log for `old_values` or `new_values`
+pub fn log_snapshot(log: &Log) -> Result<serde_json::Value, DatabaseError> {
+    serde_json::to_value(log).map_err(|e| DatabaseError::QueryError(e.to_string()))
+}
+
+fn snapshot_to_log(snapshot: &serde_json::Value) -> Result<Log, DatabaseError> {
+    Log::deserialize(snapshot).map_err(|e| DatabaseError::QueryError(e.to_string()))
+}
+
+/// Check an operator name fits `log_revisions.operator`
+pub fn validate_operator(operator: &str) -> Result<(), ValidationError> {
+    if operator.trim().is_empty() || operator.chars().count() > MAX_OPERATOR_LEN {
+        return Err(ValidationError::InvalidOperator(operator.to_string()));
+    }
+    Ok(())
+}
+
+/// Operator recorded when none is given: `SDR_DB_OPERATOR`, else the login name
+///
+/// Cut to [`MAX_OPERATOR_LEN`] characters so it always passes [`validate_operator`].
+pub fn default_operator() -> String {
+    ["SDR_DB_OPERATOR", "USER", "USERNAME"]
+        .iter()
+        .find_map(|var| {
+            std::env::var(var)
+                .ok()
+                .filter(|name| !name.trim().is_empty())
+        })
+        .map_or_else(
+            || "unknown".to_string(),
+            |name| name.chars().take(MAX_OPERATOR_LEN).collect(),
+        )
+}
@@ -0,0 +1,192 @@
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+
+use crate::error::ValidationError;
+use crate::model::revision::validate_operator;
+use crate::model::{Band, Frequency};
+use crate::spatial::{BoundingBox, Coordinate};
+
+/// Longest session name `sessions.name` holds
+pub const MAX_SESSION_NAME_LEN: usize = 100;
+/// Longest equipment description `sessions.equipment` holds
+pub const MAX_EQUIPMENT_LEN: usize = 200;
+
+/// A survey outing that logs are grouped under, from the `sessions` table
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = crate::schema::sessions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Session {
+    pub id: i32,
+    pub name: String,
+    pub operator: String,
+    pub started_at: DateTime<Utc>,
+    /// None while the session is running
+    pub ended_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+    /// Free-text description of the receiver, antenna and so on
+    pub equipment: Option<String>,
+}
+
+impl Session {
+    pub fn is_active(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    /// Time from start to end, or to `now` while the session is running
+    pub fn duration(&self, now: DateTime<Utc>) -> Duration {
+        self.ended_at.unwrap_or(now) - self.started_at
+    }
+}
+
+/// New session for insertion into the database
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = crate::schema::sessions)]
+pub struct NewSession<'a> {
+    pub name: &'a str,
+    pub operator: &'a str,
+    pub started_at: DateTime<Utc>,
+    pub notes: Option<&'a str>,
+    pub equipment: Option<&'a str>,
+}
+
+impl<'a> NewSession<'a> {
+    /// Create a validated session starting at `started_at`
+    ///
+    /// # Errors
+    /// Returns `ValidationError::InvalidSessionName` for an empty or over-long name,
+    /// `ValidationError::InvalidOperator` for a bad operator and
+    /// `ValidationError::InvalidEquipment` for an over-long equipment description
+    pub fn new(
+        name: &'a str,
+        operator: &'a str,
+        started_at: DateTime<Utc>,
+        notes: Option<&'a str>,
+        equipment: Option<&'a str>,
+    ) -> Result<Self, ValidationError> {
+        let new_session = NewSession {
+            name,
+            operator,
+            started_at,
+            notes,
+            equipment,
+        };
+        new_session.validate()?;
+        Ok(new_session)
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.name.trim().is_empty() || self.name.chars().count() > MAX_SESSION_NAME_LEN {
+            return Err(ValidationError::InvalidSessionName(self.name.to_string()));
+        }
+        validate_operator(self.operator)?;
+        if let Some(equipment) = self.equipment
+            && equipment.chars().count() > MAX_EQUIPMENT_LEN
+        {
+            return Err(ValidationError::InvalidEquipment(equipment.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Overview of the logs recorded in one session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSummary {
+    pub session: Session,
+    pub log_count: usize,
+    /// Session length, up to the time of the summary while it is running
+    pub duration: Duration,
+    /// Box around every log position, None without logs
+    pub bounding_box: Option<BoundingBox>,
+    /// ITU bands of the logged frequencies, lowest first
+    pub bands: Vec<Band>,
+}
+
+impl SessionSummary {
+    /// Summarise `session` from the frequency and position of each of its logs
+    ///
+    /// Positions that are not valid coordinates are left out of the bounding box.
+    pub fn from_logs(
+        session: Session,
+        logs: impl IntoIterator<Item = (Frequency, f32, f32)>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut log_count = 0;
+        let mut bands = Vec::new();
+        let mut corners: Option<(Coordinate, Coordinate)> = None;
+        for (frequency, xcoord, ycoord) in logs {
+            log_count += 1;
+            if let Some(band) = frequency.band()
+                && !bands.contains(&band)
+            {
+                bands.push(band);
+            }
+            let Ok(position) = Coordinate::new(xcoord as f64, ycoord as f64) else {
+                continue;
+            };
+            corners = Some(match corners {
+                None => (position, position),
+                Some((min, max)) => (
+                    Coordinate::new(min.lat().min(position.lat()), min.lon().min(position.lon()))
+                        .unwrap_or(min),
+                    Coordinate::new(max.lat().max(position.lat()), max.lon().max(position.lon()))
+                        .unwrap_or(max),
+                ),
+            });
+        }
+        bands.sort();
+
+        SessionSummary {
+            duration: session.duration(now),
+            session,
+            log_count,
+            bounding_box: corners.and_then(|(min, max)| BoundingBox::new(min, max).ok()),
+            bands,
+        }
+    }
+}
+
+impl fmt::Display for SessionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let session = &self.session;
+        write!(
+            f,
+            "Session {} \"{}\" by {}, started {}",
+            session.id,
+            session.name,
+            session.operator,
+            session.started_at.format("%Y-%m-%d %H:%M:%S")
+        )?;
+        match session.ended_at {
+            Some(end) => write!(f, ", ended {}", end.format("%Y-%m-%d %H:%M:%S"))?,
+            None => write!(f, ", still running")?,
+        }
+        let minutes = self.duration.num_minutes();
+        write!(
+            f,
+            "\n  {} logs over {}h{:02}m",
+            self.log_count,
+            minutes / 60,
+            minutes % 60
+        )?;
+        if let Some(bbox) = &self.bounding_box {
+            write!(
+                f,
+                "\n  area {:.5},{:.5} to {:.5},{:.5}",
+                bbox.min().lat(),
+                bbox.min().lon(),
+                bbox.max().lat(),
+                bbox.max().lon()
+            )?;
+        }
+        if !self.bands.is_empty() {
+            let bands: Vec<String> = self.bands.iter().map(Band::to_string).collect();
+            write!(f, "\n  bands {}", bands.join(", "))?;
+        }
+        if let Some(equipment) = &session.equipment {
+            write!(f, "\n  equipment {}", equipment)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::model::{Band, Log};
+
+/// Counts over a set of logs, for `sdr_db stats`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LogStats {
+    pub log_count: usize,
+    /// Earliest log time in UTC, None without logs
+    pub first: Option<DateTime<Utc>>,
+    /// Latest log time in UTC, None without logs
+    pub last: Option<DateTime<Utc>>,
+    /// Distinct callsigns, ignoring case
+    pub callsign_count: usize,
+    pub by_mode: BTreeMap<String, usize>,
+    /// Logs per ITU band; frequencies outside every band are not counted
+    pub by_band: BTreeMap<Band, usize>,
+    pub by_tag: BTreeMap<String, usize>,
+}
+
+impl LogStats {
+    pub fn from_logs<'a>(logs: impl IntoIterator<Item = &'a Log>) -> Self {
+        let mut stats = LogStats::default();
+        let mut callsigns = HashSet::new();
+        for log in logs {
+            stats.log_count += 1;
+            stats.first = Some(stats.first.map_or(log.timestamp, |t| t.min(log.timestamp)));
+            stats.last = Some(stats.last.map_or(log.timestamp, |t| t.max(log.timestamp)));
+            if let Some(callsign) = log.callsign.as_deref().map(str::trim)
+                && !callsign.is_empty()
+            {
+                callsigns.insert(callsign.to_uppercase());
+            }
+            *stats.by_mode.entry(log.mode.to_string()).or_default() += 1;
+            if let Some(band) = log.frequency.band() {
+                *stats.by_band.entry(band).or_default() += 1;
+            }
+            for tag in &log.tags {
+                *stats.by_tag.entry(tag.clone()).or_default() += 1;
+            }
+        }
+        stats.callsign_count = callsigns.len();
+        stats
+    }
+}
+
+/// `name count` pairs, most frequent first, e.g. `FM 12, AM 3`
+fn write_counts<K: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    label: &str,
+    counts: &BTreeMap<K, usize>,
+) -> fmt::Result {
+    if counts.is_empty() {
+        return Ok(());
+    }
+    let mut counts: Vec<_> = counts.iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(a.1));
+    let counts: Vec<String> = counts
+        .into_iter()
+        .map(|(name, count)| format!("{} {}", name, count))
+        .collect();
+    write!(f, "\n  {}: {}", label, counts.join(", "))
+}
+
+impl fmt::Display for LogStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} logs from {} callsigns",
+            self.log_count, self.callsign_count
+        )?;
+        if let (Some(first), Some(last)) = (self.first, self.last) {
+            write!(
+                f,
+                "\n  {} to {} UTC",
+                first.format("%Y-%m-%d %H:%M:%S"),
+                last.format("%Y-%m-%d %H:%M:%S")
+            )?;
+        }
+        write_counts(f, "modes", &self.by_mode)?;
+        write_counts(f, "bands", &self.by_band)?;
+        write_counts(f, "tags", &self.by_tag)
+    }
+}
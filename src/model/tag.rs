@@ -0,0 +1,52 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ValidationError;
+
+/// Longest tag name `tags.name` holds
+pub const MAX_TAG_LEN: usize = 50;
+
+/// A label logs can be filed under, from the `tags` table
+#[derive(Debug, Clone, PartialEq, Eq, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::tags)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Tag {
+    pub id: i32,
+    pub name: String,
+}
+
+/// Canonical form of a tag: trimmed and lowercase, so `Weak` and `weak ` are one tag
+///
+/// # Errors
+/// Returns `ValidationError::InvalidTag` for an empty or over-long tag, or one
+/// containing whitespace or a comma
+pub fn normalize_tag(tag: &str) -> Result<String, ValidationError> {
+    let tag = tag.trim().to_lowercase();
+    let len = tag.chars().count();
+    if len == 0 || len > MAX_TAG_LEN || tag.chars().any(|c| c.is_whitespace() || c == ',') {
+        return Err(ValidationError::InvalidTag(tag));
+    }
+    Ok(tag)
+}
+
+/// Normalize several tags, dropping duplicates and sorting them by name
+pub fn normalize_tags<S: AsRef<str>>(
+    tags: impl IntoIterator<Item = S>,
+) -> Result<Vec<String>, ValidationError> {
+    let mut normalized = tags
+        .into_iter()
+        .map(|tag| normalize_tag(tag.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+    normalized.sort();
+    normalized.dedup();
+    Ok(normalized)
+}
+
+/// Parse tags typed as a comma or space separated list, e.g. `repeater, weak`
+pub fn parse_tags(input: &str) -> Result<Vec<String>, ValidationError> {
+    normalize_tags(
+        input
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|tag| !tag.is_empty()),
+    )
+}
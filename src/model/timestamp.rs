@@ -0,0 +1,160 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer};
+
+use crate::error::ValidationError;
+
+/// Earliest year a log can be timestamped in
+pub const MIN_LOG_YEAR: i32 = 1900;
+
+/// How far ahead of this machine's clock a timestamp may be, for units whose clocks drift
+pub const MAX_CLOCK_SKEW: Duration = Duration::minutes(5);
+
+/// Layouts accepted for times without an offset, read in the configured [`LogTimeZone`]
+const LOCAL_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Zone that timestamps typed without an offset are read in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogTimeZone {
+    /// This machine's zone, following its daylight saving changes
+    #[default]
+    Local,
+    /// A fixed offset from UTC
+    Fixed(FixedOffset),
+}
+
+impl LogTimeZone {
+    pub fn utc() -> Self {
+        LogTimeZone::Fixed(FixedOffset::east_opt(0).unwrap())
+    }
+
+    /// The instant `time` names in this zone
+    ///
+    /// A time repeated when clocks go back is read as its first occurrence.
+    ///
+    /// # Errors
+    /// Returns `ValidationError::InvalidTimestamp` for a time skipped when clocks go
+    /// forward
+    pub fn to_utc(&self, time: NaiveDateTime) -> Result<DateTime<Utc>, ValidationError> {
+        let instant = match self {
+            LogTimeZone::Local => Local
+                .from_local_datetime(&time)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc)),
+            LogTimeZone::Fixed(offset) => offset
+                .from_local_datetime(&time)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc)),
+        };
+        instant.ok_or_else(|| {
+            ValidationError::InvalidTimestamp(format!("{} does not exist in {}", time, self))
+        })
+    }
+}
+
+impl FromStr for LogTimeZone {
+    type Err = ValidationError;
+
+    /// Parse `local`, `UTC` or an offset such as `+02:00`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(LogTimeZone::Local);
+        }
+        if s.eq_ignore_ascii_case("utc") || s.eq_ignore_ascii_case("z") {
+            return Ok(LogTimeZone::utc());
+        }
+        s.parse::<FixedOffset>()
+            .map(LogTimeZone::Fixed)
+            .map_err(|_| {
+                ValidationError::InvalidTimestamp(format!(
+                    "unknown time zone {:?} (expected local, UTC or an offset like +02:00)",
+                    s
+                ))
+            })
+    }
+}
+
+impl fmt::Display for LogTimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogTimeZone::Local => write!(f, "local"),
+            LogTimeZone::Fixed(offset) if offset.local_minus_utc() == 0 => write!(f, "UTC"),
+            LogTimeZone::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+/// Parse a log time typed by hand or read from a notebook
+///
+/// Accepts RFC 3339 / ISO 8601 with an offset, e.g. `2025-11-14T09:30:00Z`, or a date
+/// and time without one, e.g. `2025-11-14 09:30`, read in `zone`.
+///
+/// # Errors
+/// Returns `ValidationError::InvalidTimestamp` if the text is not a time in one of
+/// those layouts or fails [`validate_timestamp`]
+pub fn parse_timestamp(input: &str, zone: LogTimeZone) -> Result<DateTime<Utc>, ValidationError> {
+    let input = input.trim();
+    let timestamp = match DateTime::parse_from_rfc3339(input) {
+        Ok(timestamp) => timestamp.with_timezone(&Utc),
+        Err(_) => {
+            let time = LOCAL_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+                .ok_or_else(|| {
+                    ValidationError::InvalidTimestamp(format!(
+                        "{:?} (expected e.g. 2025-11-14 09:30 or 2025-11-14T09:30:00Z)",
+                        input
+                    ))
+                })?;
+            zone.to_utc(time)?
+        }
+    };
+    validate_timestamp(timestamp)?;
+    Ok(timestamp)
+}
+
+/// Check a UTC log time is no earlier than [`MIN_LOG_YEAR`] and not in the future
+///
+/// # Errors
+/// Returns `ValidationError::InvalidTimestamp` for a time before 1900 or more than
+/// [`MAX_CLOCK_SKEW`] ahead of now
+pub fn validate_timestamp(timestamp: DateTime<Utc>) -> Result<(), ValidationError> {
+    if timestamp.year() < MIN_LOG_YEAR {
+        return Err(ValidationError::InvalidTimestamp(format!(
+            "{} is before {}",
+            timestamp, MIN_LOG_YEAR
+        )));
+    }
+    if timestamp > Utc::now() + MAX_CLOCK_SKEW {
+        return Err(ValidationError::InvalidTimestamp(format!(
+            "{} is in the future",
+            timestamp
+        )));
+    }
+    Ok(())
+}
+
+/// Deserialize a UTC time written with an offset, or without one as logs saved
+/// before they carried their zone were
+pub(crate) fn deserialize_utc<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<DateTime<Utc>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    parse_stored_utc(&text).map_err(serde::de::Error::custom)
+}
+
+/// A stored RFC 3339 time, or a bare date and time taken as UTC
+pub(crate) fn parse_stored_utc(text: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+    match DateTime::parse_from_rfc3339(text) {
+        Ok(time) => Ok(time.with_timezone(&Utc)),
+        Err(_) => text.parse::<NaiveDateTime>().map(|time| time.and_utc()),
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::Uuid;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::ValidationError;
+
+/// Client-generated identity of a log entry, a time-ordered UUID (version 7)
+///
+/// Generated where the log is captured, so entries from different databases never
+/// collide and replaying the same entry twice can be detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, FromSqlRow, AsExpression)]
+#[diesel(sql_type = Uuid)]
+pub struct LogUuid([u8; 16]);
+
+impl LogUuid {
+    /// Generate a UUIDv7 from the current time and OS randomness
+    pub fn new_v7() -> Self {
+        let unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        Self::new_v7_at(unix_ms)
+    }
+
+    /// Generate a UUIDv7 for an earlier moment, e.g. when backfilling old entries
+    pub fn new_v7_at(unix_ms: u64) -> Self {
+        let mut random = [0u8; 10];
+        getrandom::fill(&mut random).expect("OS random number generator unavailable");
+        Self::from_parts(unix_ms, random)
+    }
+
+    /// Build a UUIDv7 from a Unix timestamp in milliseconds and 74 bits of randomness
+    ///
+    /// Only the low 48 bits of `unix_ms` are used; the version and variant bits of
+    /// `random` are overwritten.
+    pub fn from_parts(unix_ms: u64, random: [u8; 10]) -> Self {
+        let mut bytes = [0u8; 16];
+        bytes[..6].copy_from_slice(&unix_ms.to_be_bytes()[2..]);
+        bytes[6..].copy_from_slice(&random);
+        bytes[6] = 0x70 | (bytes[6] & 0x0f);
+        bytes[8] = 0x80 | (bytes[8] & 0x3f);
+        LogUuid(bytes)
+    }
+
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        LogUuid(bytes)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// UUID version number, 7 for generated ids
+    pub const fn version(&self) -> u8 {
+        self.0[6] >> 4
+    }
+
+    /// Unix timestamp in milliseconds embedded in a version 7 UUID
+    pub fn timestamp_ms(&self) -> u64 {
+        let mut ms = [0u8; 8];
+        ms[2..].copy_from_slice(&self.0[..6]);
+        u64::from_be_bytes(ms)
+    }
+}
+
+/// Formats in the canonical hyphenated lowercase form
+impl fmt::Display for LogUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                f.write_str("-")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for LogUuid {
+    type Err = ValidationError;
+
+    /// Parse 32 hex digits, with or without hyphens
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ValidationError::InvalidUuid(s.to_string());
+        let digits: Vec<u8> = s.trim().bytes().filter(|&b| b != b'-').collect();
+        // from_str_radix alone would let a sign through, as in "+f"
+        if digits.len() != 32 || !digits.iter().all(u8::is_ascii_hexdigit) {
+            return Err(invalid());
+        }
+
+        let mut bytes = [0u8; 16];
+        for (byte, pair) in bytes.iter_mut().zip(digits.chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+        }
+        Ok(LogUuid(bytes))
+    }
+}
+
+impl Serialize for LogUuid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LogUuid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl ToSql<Uuid, Pg> for LogUuid {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        out.write_all(&self.0)?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Uuid, Pg> for LogUuid {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes: [u8; 16] = bytes
+            .as_bytes()
+            .try_into()
+            .map_err(|_| "invalid UUID length")?;
+        Ok(LogUuid(bytes))
+    }
+}
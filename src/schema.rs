@@ -1,9 +1,41 @@
 // @generated automatically by Diesel CLI.
 
+pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "geography"))]
+    pub struct Geography;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "tsvector", schema = "pg_catalog"))]
+    pub struct Tsvector;
+}
+
 diesel::table! {
+    equipment_profiles (id) {
+        id -> Int4,
+        #[max_length = 100]
+        sdr_model -> Varchar,
+        #[max_length = 100]
+        serial -> Nullable<Varchar>,
+        #[max_length = 200]
+        antenna -> Nullable<Varchar>,
+        cable_loss_db -> Float4,
+        preamp -> Bool,
+        lna_gain_db -> Int4,
+        vga_gain_db -> Int4,
+        calibration_offset_ppm -> Float4,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Geography;
+    use super::sql_types::Tsvector;
+
     logs (id) {
         id -> Int4,
-        frequency -> Float4,
+        frequency -> Int8,
         xcoord -> Float4,
         ycoord -> Float4,
         #[max_length = 50]
@@ -12,7 +44,79 @@ diesel::table! {
         comment -> Nullable<Varchar>,
         #[max_length = 20]
         mode -> Varchar,
-        timestamp -> Timestamp,
+        timestamp -> Timestamptz,
         recording_duration -> Float4,
+        location -> Nullable<Geography>,
+        power_dbm -> Nullable<Float4>,
+        snr_db -> Nullable<Float4>,
+        bandwidth_hz -> Nullable<Int8>,
+        altitude_m -> Nullable<Float4>,
+        position_accuracy_m -> Nullable<Float4>,
+        heading_deg -> Nullable<Float4>,
+        speed_mps -> Nullable<Float4>,
+        uuid -> Uuid,
+        deleted_at -> Nullable<Timestamptz>,
+        session_id -> Nullable<Int4>,
+        equipment_profile_id -> Nullable<Int4>,
+        search_vector -> Nullable<Tsvector>,
+    }
+}
+
+diesel::table! {
+    log_revisions (id) {
+        id -> Int4,
+        log_id -> Int4,
+        #[max_length = 20]
+        action -> Varchar,
+        old_values -> Jsonb,
+        new_values -> Nullable<Jsonb>,
+        #[max_length = 50]
+        operator -> Varchar,
+        changed_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    log_tags (log_id, tag_id) {
+        log_id -> Int4,
+        tag_id -> Int4,
+    }
+}
+
+diesel::table! {
+    sessions (id) {
+        id -> Int4,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 50]
+        operator -> Varchar,
+        started_at -> Timestamptz,
+        ended_at -> Nullable<Timestamptz>,
+        notes -> Nullable<Text>,
+        #[max_length = 200]
+        equipment -> Nullable<Varchar>,
     }
 }
+
+diesel::table! {
+    tags (id) {
+        id -> Int4,
+        #[max_length = 50]
+        name -> Varchar,
+    }
+}
+
+diesel::joinable!(log_revisions -> logs (log_id));
+diesel::joinable!(log_tags -> logs (log_id));
+diesel::joinable!(log_tags -> tags (tag_id));
+diesel::joinable!(logs -> equipment_profiles (equipment_profile_id));
+diesel::joinable!(logs -> sessions (session_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    equipment_profiles,
+    log_revisions,
+    log_tags,
+    logs,
+    sessions,
+    tags,
+);
@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::source::{Source, SourceError, spectrum::SpectrumDataSource};
 use num_complex::Complex;
 use tokio::sync::mpsc;
@@ -16,15 +17,15 @@ struct FileSource {
 
 #[async_trait::async_trait]
 impl Source for FileSource {
-    async fn next_samples(&mut self) -> Result<Option<Vec<Complex<f32>>>, SourceError> {
+    async fn next_samples(&mut self) -> Result<Option<Vec<Complex<f32>>>, Error> {
         todo!()
     }
 
-    async fn start(&mut self) -> Result<(), SourceError> {
+    async fn start(&mut self) -> Result<(), Error> {
         Ok(())
     }
 
-    async fn stop(&mut self) -> Result<(), SourceError> {
+    async fn stop(&mut self) -> Result<(), Error> {
         Ok(())
     }
     fn get_receiver(&mut self) -> &mut mpsc::Receiver<Vec<u8>> {
@@ -44,7 +45,7 @@ impl SpectrumDataSource for FileSource {
         &mut self,
         _center_freq: f64,
         _span: f64,
-    ) -> Result<Vec<(f64, f64)>, SourceError> {
+    ) -> Result<Vec<(f64, f64)>, Error> {
         todo!("FileSource is for audio files, use FileSpectrum for spectrum data")
     }
 
@@ -52,7 +53,7 @@ impl SpectrumDataSource for FileSource {
         format!("Audio file: {}", self.file_name)
     }
 
-    fn set_center_frequency(&mut self, _freq: f64) -> Result<(), SourceError> {
+    fn set_center_frequency(&mut self, _freq: f64) -> Result<(), Error> {
         Ok(())
     }
 
@@ -82,7 +83,7 @@ impl FileSpectrum {
     /// 162000000.0,-65.2
     /// 162001000.0,-68.1
     /// ```
-    pub fn from_csv(file_path: String) -> Result<Self, SourceError> {
+    pub fn from_csv(file_path: String) -> Result<Self, Error> {
         use std::fs::File;
         use std::io::{BufRead, BufReader};
 
@@ -115,7 +116,8 @@ impl FileSpectrum {
                 return Err(SourceError::StreamError(format!(
                     "Invalid CSV format at line {}: expected 2 columns",
                     line_num + 1
-                )));
+                ))
+                .into());
             }
 
             let freq: f64 = parts[0].trim().parse().map_err(|_| {
@@ -132,9 +134,7 @@ impl FileSpectrum {
         }
 
         if data.is_empty() {
-            return Err(SourceError::StreamError(
-                "No data found in file".to_string(),
-            ));
+            return Err(SourceError::StreamError("No data found in file".to_string()).into());
         }
 
         // Sort by frequency
@@ -165,11 +165,7 @@ impl FileSpectrum {
 }
 
 impl SpectrumDataSource for FileSpectrum {
-    fn get_spectrum_data(
-        &mut self,
-        center_freq: f64,
-        span: f64,
-    ) -> Result<Vec<(f64, f64)>, SourceError> {
+    fn get_spectrum_data(&mut self, center_freq: f64, span: f64) -> Result<Vec<(f64, f64)>, Error> {
         Ok(self.filter_range(center_freq, span))
     }
 
@@ -182,7 +178,7 @@ impl SpectrumDataSource for FileSpectrum {
         )
     }
 
-    fn set_center_frequency(&mut self, _freq: f64) -> Result<(), SourceError> {
+    fn set_center_frequency(&mut self, _freq: f64) -> Result<(), Error> {
         // File sources have fixed frequency range, so this is a no-op
         Ok(())
     }
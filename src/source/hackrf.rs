@@ -2,7 +2,8 @@ use num_complex::Complex;
 use soapysdr::RxStream;
 use tokio::sync::mpsc;
 
-use crate::source::{Source, SourceError};
+use crate::error::Error;
+use crate::source::Source;
 
 pub struct HackRFSource {
     rx_stream: RxStream<f32>,
@@ -10,14 +11,14 @@ pub struct HackRFSource {
 
 #[async_trait::async_trait]
 impl Source for HackRFSource {
-    async fn next_samples(&mut self) -> Result<Option<Vec<Complex<f32>>>, SourceError> {
+    async fn next_samples(&mut self) -> Result<Option<Vec<Complex<f32>>>, Error> {
         todo!()
     }
-    async fn start(&mut self) -> Result<(), SourceError> {
+    async fn start(&mut self) -> Result<(), Error> {
         Ok(())
     }
 
-    async fn stop(&mut self) -> Result<(), SourceError> {
+    async fn stop(&mut self) -> Result<(), Error> {
         Ok(())
     }
     fn get_receiver(&mut self) -> &mut mpsc::Receiver<Vec<u8>> {
@@ -1,4 +1,4 @@
-use crate::source::SourceError;
+use crate::error::Error;
 
 /// Trait for sources that provide spectrum data for visualization
 ///
@@ -13,18 +13,14 @@ pub trait SpectrumDataSource {
     ///
     /// # Returns
     /// Vector of (frequency_hz, power_dbm) tuples
-    fn get_spectrum_data(
-        &mut self,
-        center_freq: f64,
-        span: f64,
-    ) -> Result<Vec<(f64, f64)>, SourceError>;
+    fn get_spectrum_data(&mut self, center_freq: f64, span: f64) -> Result<Vec<(f64, f64)>, Error>;
 
     /// Get human-readable information about this source
     fn get_info(&self) -> String;
 
     /// Set the center frequency for live sources
     /// For file sources, this may have no effect
-    fn set_center_frequency(&mut self, freq: f64) -> Result<(), SourceError>;
+    fn set_center_frequency(&mut self, freq: f64) -> Result<(), Error>;
 
     /// Get the valid frequency range for this source
     /// Returns (min_freq_hz, max_freq_hz)
@@ -1 +1,152 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 
+use crate::spatial::{Coordinate, Layer, Measurement};
+
+/// Summary statistics of a single measured quantity
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Stats {
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+    /// Population standard deviation
+    pub stddev: f64,
+}
+
+impl Stats {
+    /// Compute statistics over `values`, or None if there are none
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let n = values.len() as f64;
+        let avg = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - avg).powi(2)).sum::<f64>() / n;
+
+        Some(Stats {
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            avg,
+            stddev: variance.sqrt(),
+        })
+    }
+}
+
+/// Measurements clustered around a single location
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocationAggregate {
+    /// Centroid of the clustered measurements
+    pub location: Coordinate,
+    pub measurement_count: usize,
+    /// Received power in dBm
+    pub power: Stats,
+    /// Signal-to-noise ratio in dB
+    pub snr: Stats,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// Distinct frequencies heard at this location in Hz, ascending
+    pub frequencies: Vec<f64>,
+}
+
+/// Measurements assigned to a cluster, with a running centroid
+struct Cluster<'a> {
+    lat_sum: f64,
+    /// Longitude of the seed measurement; the others are summed as offsets from it, so
+    /// a cluster straddling ±180° does not average to 0°
+    seed_lon: f64,
+    lon_offset_sum: f64,
+    members: Vec<&'a Measurement>,
+}
+
+impl<'a> Cluster<'a> {
+    fn new(m: &'a Measurement) -> Self {
+        Self {
+            lat_sum: m.location.lat(),
+            seed_lon: m.location.lon(),
+            lon_offset_sum: 0.0,
+            members: vec![m],
+        }
+    }
+
+    fn centroid(&self) -> Coordinate {
+        let n = self.members.len() as f64;
+        let mut lon = self.seed_lon + self.lon_offset_sum / n;
+        if lon > 180.0 {
+            lon -= 360.0;
+        } else if lon < -180.0 {
+            lon += 360.0;
+        }
+        Coordinate::new(self.lat_sum / n, lon).expect("centroid of valid coordinates is valid")
+    }
+
+    fn push(&mut self, m: &'a Measurement) {
+        self.lat_sum += m.location.lat();
+        // Take the short way round from the seed
+        let mut offset = m.location.lon() - self.seed_lon;
+        if offset > 180.0 {
+            offset -= 360.0;
+        } else if offset < -180.0 {
+            offset += 360.0;
+        }
+        self.lon_offset_sum += offset;
+        self.members.push(m);
+    }
+
+    fn summarise(self) -> LocationAggregate {
+        let location = self.centroid();
+        let power: Vec<f64> = self.members.iter().map(|m| m.power).collect();
+        let snr: Vec<f64> = self.members.iter().map(|m| m.snr).collect();
+
+        let mut frequencies: Vec<f64> = self.members.iter().map(|m| m.frequency).collect();
+        frequencies.sort_by(f64::total_cmp);
+        frequencies.dedup();
+
+        // Clusters always hold at least their seed measurement
+        let first_seen = self.members.iter().map(|m| m.timestamp).min().unwrap();
+        let last_seen = self.members.iter().map(|m| m.timestamp).max().unwrap();
+
+        LocationAggregate {
+            location,
+            measurement_count: self.members.len(),
+            power: Stats::from_values(&power).unwrap(),
+            snr: Stats::from_values(&snr).unwrap(),
+            first_seen,
+            last_seen,
+            frequencies,
+        }
+    }
+}
+
+/// Cluster measurements taken within `tolerance_m` metres of each other and summarise each cluster
+///
+/// Measurements are visited in input order; each joins the nearest existing cluster whose
+/// centroid is within `tolerance_m` (great-circle distance), otherwise it seeds a new one.
+/// Aggregates are returned in the order their clusters were created.
+pub fn aggregate_by_location(
+    measurements: &[Measurement],
+    tolerance_m: f64,
+) -> Vec<LocationAggregate> {
+    let mut clusters: Vec<Cluster> = Vec::new();
+
+    for m in measurements {
+        let nearest = clusters
+            .iter_mut()
+            .map(|c| (c.centroid().distance_m(&m.location), c))
+            .filter(|(distance, _)| *distance <= tolerance_m)
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        match nearest {
+            Some((_, cluster)) => cluster.push(m),
+            None => clusters.push(Cluster::new(m)),
+        }
+    }
+
+    clusters.into_iter().map(Cluster::summarise).collect()
+}
+
+impl Layer {
+    /// Summarise this layer by location, see [`aggregate_by_location`]
+    pub fn aggregate_by_location(&self, tolerance_m: f64) -> Vec<LocationAggregate> {
+        aggregate_by_location(self.measurements(), tolerance_m)
+    }
+}
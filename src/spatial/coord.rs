@@ -1,8 +1,253 @@
-use diesel::FromSqlRow;
-use geo::Point as GeoPoint;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use geo::{Distance, Haversine, Point as GeoPoint};
+use serde::Serialize;
+use std::io::Write;
 
-pub type Coordinate = (f32, f32);
+use crate::error::{Error, ValidationError};
+use crate::schema::sql_types::Geography;
 
-#[derive(Debug, Clone, FromSqlRow)]
-#[diesel(sql_type = Coordinate)]
-pub struct DbPoint(pub GeoPoint<f32>);
+/// SRID of the WGS84 lon/lat coordinate system
+pub const WGS84_SRID: u32 = 4326;
+
+const EWKB_POINT: u32 = 1;
+const EWKB_Z_FLAG: u32 = 0x8000_0000;
+const EWKB_M_FLAG: u32 = 0x4000_0000;
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+
+/// A validated WGS84 position in decimal degrees
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Coordinate {
+    lat: f64,
+    lon: f64,
+}
+
+impl Coordinate {
+    /// Create a new Coordinate with validation
+    ///
+    /// # Errors
+    /// Returns `ValidationError::InvalidLatitude` if `lat` is outside [-90, 90]
+    /// and `ValidationError::InvalidLongitude` if `lon` is outside [-180, 180]
+    pub fn new(lat: f64, lon: f64) -> Result<Self, Error> {
+        Ok(Self::checked(lat, lon)?)
+    }
+
+    /// [`Coordinate::new`] for validation that reports a bare `ValidationError`
+    pub(crate) fn checked(lat: f64, lon: f64) -> Result<Self, ValidationError> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(ValidationError::InvalidLatitude(lat));
+        }
+        if !(-180.0..=180.0).contains(&lon) {
+            return Err(ValidationError::InvalidLongitude(lon));
+        }
+        Ok(Self { lat, lon })
+    }
+
+    /// Latitude in decimal degrees
+    pub fn lat(&self) -> f64 {
+        self.lat
+    }
+
+    /// Longitude in decimal degrees
+    pub fn lon(&self) -> f64 {
+        self.lon
+    }
+
+    /// Great-circle distance to `other` in metres
+    pub fn distance_m(&self, other: &Coordinate) -> f64 {
+        Haversine.distance(GeoPoint::from(*self), GeoPoint::from(*other))
+    }
+}
+
+impl From<Coordinate> for GeoPoint<f64> {
+    fn from(coord: Coordinate) -> Self {
+        GeoPoint::new(coord.lon, coord.lat)
+    }
+}
+
+/// Axis-aligned bounding box defined by its south-west and north-east corners
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BoundingBox {
+    min: Coordinate,
+    max: Coordinate,
+}
+
+impl BoundingBox {
+    /// Create a new BoundingBox from its south-west (`min`) and north-east (`max`) corners
+    ///
+    /// # Errors
+    /// Returns `ValidationError::InvalidBoundingBox` if `min` lies north or east of `max`
+    pub fn new(min: Coordinate, max: Coordinate) -> Result<Self, Error> {
+        Ok(Self::checked(min, max)?)
+    }
+
+    /// [`BoundingBox::new`] for validation that reports a bare `ValidationError`
+    pub(crate) fn checked(min: Coordinate, max: Coordinate) -> Result<Self, ValidationError> {
+        if min.lat > max.lat {
+            return Err(ValidationError::InvalidBoundingBox(format!(
+                "min latitude {} is greater than max latitude {}",
+                min.lat, max.lat
+            )));
+        }
+        if min.lon > max.lon {
+            return Err(ValidationError::InvalidBoundingBox(format!(
+                "min longitude {} is greater than max longitude {}",
+                min.lon, max.lon
+            )));
+        }
+        Ok(Self { min, max })
+    }
+
+    pub fn min(&self) -> Coordinate {
+        self.min
+    }
+
+    pub fn max(&self) -> Coordinate {
+        self.max
+    }
+
+    /// Returns true if `coord` lies inside the box (edges inclusive)
+    pub fn contains(&self, coord: &Coordinate) -> bool {
+        (self.min.lat..=self.max.lat).contains(&coord.lat)
+            && (self.min.lon..=self.max.lon).contains(&coord.lon)
+    }
+}
+
+/// A WGS84 point stored in a PostGIS `geography(Point, 4326)` column
+///
+/// The point's x is longitude and y is latitude, as in PostGIS.
+#[derive(Debug, Clone, Copy, PartialEq, FromSqlRow, AsExpression)]
+#[diesel(sql_type = Geography)]
+pub struct DbPoint(pub GeoPoint<f64>);
+
+impl DbPoint {
+    /// Encode as little-endian EWKB with the WGS84 SRID
+    pub fn to_ewkb(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(25);
+        buf.push(1);
+        buf.extend_from_slice(&(EWKB_POINT | EWKB_SRID_FLAG).to_le_bytes());
+        buf.extend_from_slice(&WGS84_SRID.to_le_bytes());
+        buf.extend_from_slice(&self.0.x().to_le_bytes());
+        buf.extend_from_slice(&self.0.y().to_le_bytes());
+        buf
+    }
+
+    /// Decode a point from WKB or EWKB in either byte order
+    ///
+    /// Z and M ordinates are accepted and discarded.
+    ///
+    /// # Errors
+    /// Returns `ValidationError::InvalidGeometry` for bytes that are not a WGS84 point
+    pub fn from_ewkb(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = EwkbReader { bytes, pos: 0 };
+        let little_endian = match reader.take::<1>()?[0] {
+            0 => false,
+            1 => true,
+            other => return Err(invalid_geometry(format!("WKB byte order marker {}", other))),
+        };
+        let geometry_type = reader.u32(little_endian)?;
+        if geometry_type & 0xffff != EWKB_POINT {
+            return Err(invalid_geometry(format!(
+                "expected a WKB point, found geometry type {}",
+                geometry_type & 0xffff
+            )));
+        }
+        if geometry_type & EWKB_SRID_FLAG != 0 {
+            let srid = reader.u32(little_endian)?;
+            if srid != WGS84_SRID {
+                return Err(invalid_geometry(format!(
+                    "expected SRID {}, found {}",
+                    WGS84_SRID, srid
+                )));
+            }
+        }
+        let x = reader.f64(little_endian)?;
+        let y = reader.f64(little_endian)?;
+        if geometry_type & EWKB_Z_FLAG != 0 {
+            reader.f64(little_endian)?;
+        }
+        if geometry_type & EWKB_M_FLAG != 0 {
+            reader.f64(little_endian)?;
+        }
+        Ok(DbPoint(GeoPoint::new(x, y)))
+    }
+}
+
+struct EwkbReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl EwkbReader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let chunk = self
+            .bytes
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| invalid_geometry(format!("WKB truncated at byte {}", self.pos)))?;
+        self.pos += N;
+        Ok(chunk.try_into().expect("slice has length N"))
+    }
+
+    fn u32(&mut self, little_endian: bool) -> Result<u32, Error> {
+        let b = self.take::<4>()?;
+        Ok(if little_endian {
+            u32::from_le_bytes(b)
+        } else {
+            u32::from_be_bytes(b)
+        })
+    }
+
+    fn f64(&mut self, little_endian: bool) -> Result<f64, Error> {
+        let b = self.take::<8>()?;
+        Ok(if little_endian {
+            f64::from_le_bytes(b)
+        } else {
+            f64::from_be_bytes(b)
+        })
+    }
+}
+
+fn invalid_geometry(reason: String) -> Error {
+    ValidationError::InvalidGeometry(reason).into()
+}
+
+impl ToSql<Geography, Pg> for DbPoint {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        out.write_all(&self.to_ewkb())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Geography, Pg> for DbPoint {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        DbPoint::from_ewkb(bytes.as_bytes()).map_err(Into::into)
+    }
+}
+
+impl From<GeoPoint<f64>> for DbPoint {
+    fn from(point: GeoPoint<f64>) -> Self {
+        DbPoint(point)
+    }
+}
+
+impl From<DbPoint> for GeoPoint<f64> {
+    fn from(point: DbPoint) -> Self {
+        point.0
+    }
+}
+
+impl From<Coordinate> for DbPoint {
+    fn from(coord: Coordinate) -> Self {
+        DbPoint(coord.into())
+    }
+}
+
+impl TryFrom<DbPoint> for Coordinate {
+    type Error = ValidationError;
+
+    fn try_from(point: DbPoint) -> Result<Self, Self::Error> {
+        Coordinate::checked(point.0.y(), point.0.x())
+    }
+}
@@ -0,0 +1,73 @@
+//! Maidenhead grid locators, the `JO01ab`-style positions used by amateur radio
+
+use crate::error::{Error, ValidationError};
+use crate::spatial::Coordinate;
+
+/// Locator of `coord` with `pairs` letter/digit pairs, 1 to 4, e.g. 3 for `IO91wm`
+///
+/// Each pair narrows the square: 20°×10° fields, 2°×1° squares, 5'×2.5' subsquares
+/// and 30"×15" extended squares.
+pub fn grid_square(coord: &Coordinate, pairs: usize) -> String {
+    let pairs = pairs.clamp(1, 4);
+    // Shift to 0..360 and 0..180, keeping the east and north edges in the last square
+    let mut lon = (coord.lon() + 180.0).min(360.0 - 1e-9);
+    let mut lat = (coord.lat() + 90.0).min(180.0 - 1e-9);
+    let mut locator = String::with_capacity(pairs * 2);
+    for (pair, (lon_size, lat_size)) in PAIR_SIZES.iter().take(pairs).enumerate() {
+        let x = (lon / lon_size).floor() as u8;
+        let y = (lat / lat_size).floor() as u8;
+        let base = match pair {
+            0 => b'A',
+            2 => b'a',
+            _ => b'0',
+        };
+        let limit = PAIR_STEPS[pair] - 1;
+        locator.push((base + x.min(limit)) as char);
+        locator.push((base + y.min(limit)) as char);
+        lon -= x as f64 * lon_size;
+        lat -= y as f64 * lat_size;
+    }
+    locator
+}
+
+/// Centre of the square a 2, 4, 6 or 8 character locator names
+///
+/// # Errors
+/// Returns `ValidationError::InvalidGridSquare` for any other length or a character
+/// outside the range of its position
+pub fn grid_square_center(locator: &str) -> Result<Coordinate, Error> {
+    let invalid = || ValidationError::InvalidGridSquare(locator.to_string());
+    let chars: Vec<u8> = locator.trim().bytes().collect();
+    if chars.is_empty() || !chars.len().is_multiple_of(2) || chars.len() > 8 {
+        return Err(invalid().into());
+    }
+
+    let (mut lon, mut lat) = (0.0, 0.0);
+    for (pair, chunk) in chars.chunks(2).enumerate() {
+        let (lon_size, lat_size) = PAIR_SIZES[pair];
+        let value = |c: u8| -> Result<f64, ValidationError> {
+            let value = match pair {
+                0 | 2 => c.to_ascii_uppercase().checked_sub(b'A'),
+                _ => c.checked_sub(b'0'),
+            }
+            .filter(|&v| v < PAIR_STEPS[pair])
+            .ok_or_else(invalid)?;
+            Ok(value as f64)
+        };
+        lon += value(chunk[0])? * lon_size;
+        lat += value(chunk[1])? * lat_size;
+    }
+    let (lon_size, lat_size) = PAIR_SIZES[chars.len() / 2 - 1];
+    Coordinate::new(lat + lat_size / 2.0 - 90.0, lon + lon_size / 2.0 - 180.0)
+}
+
+/// Longitude and latitude span in degrees of one step of each pair
+const PAIR_SIZES: [(f64, f64); 4] = [
+    (20.0, 10.0),
+    (2.0, 1.0),
+    (2.0 / 24.0, 1.0 / 24.0),
+    (2.0 / 240.0, 1.0 / 240.0),
+];
+
+/// Values each pair takes: 18 letters, 10 digits, 24 letters, 10 digits
+const PAIR_STEPS: [u8; 4] = [18, 10, 24, 10];
@@ -0,0 +1,57 @@
+use serde::Serialize;
+
+use crate::spatial::{BoundingBox, Coordinate, Measurement};
+
+/// An in-memory collection of measurements that can be queried spatially
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Layer {
+    measurements: Vec<Measurement>,
+}
+
+impl Layer {
+    pub fn new(measurements: Vec<Measurement>) -> Self {
+        Self { measurements }
+    }
+
+    pub fn measurements(&self) -> &[Measurement] {
+        &self.measurements
+    }
+
+    pub fn measurement_count(&self) -> usize {
+        self.measurements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.measurements.is_empty()
+    }
+
+    pub fn push(&mut self, measurement: Measurement) {
+        self.measurements.push(measurement);
+    }
+
+    /// Smallest bounding box containing every measurement, or None for an empty layer
+    pub fn spatial_extent(&self) -> Option<BoundingBox> {
+        let first = self.measurements.first()?.location;
+        let (mut min_lat, mut min_lon) = (first.lat(), first.lon());
+        let (mut max_lat, mut max_lon) = (min_lat, min_lon);
+
+        for m in &self.measurements[1..] {
+            min_lat = min_lat.min(m.location.lat());
+            min_lon = min_lon.min(m.location.lon());
+            max_lat = max_lat.max(m.location.lat());
+            max_lon = max_lon.max(m.location.lon());
+        }
+
+        BoundingBox::new(
+            Coordinate::new(min_lat, min_lon).ok()?,
+            Coordinate::new(max_lat, max_lon).ok()?,
+        )
+        .ok()
+    }
+}
+
+impl From<Vec<Measurement>> for Layer {
+    fn from(measurements: Vec<Measurement>) -> Self {
+        Self::new(measurements)
+    }
+}
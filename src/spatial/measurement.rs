@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::error::{Error, ValidationError};
+use crate::spatial::Coordinate;
+
+/// A single geolocated SDR measurement
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Measurement {
+    pub location: Coordinate,
+    pub timestamp: DateTime<Utc>,
+    /// Centre frequency in Hz
+    pub frequency: f64,
+    /// Received power in dBm
+    pub power: f64,
+    /// Signal bandwidth in Hz
+    pub bandwidth: f64,
+    /// Signal-to-noise ratio in dB
+    pub snr: f64,
+}
+
+impl Measurement {
+    /// Create a new Measurement with validation
+    ///
+    /// # Arguments
+    /// * `location` - Position where the measurement was taken
+    /// * `timestamp` - Time of the measurement
+    /// * `frequency` - Centre frequency in Hz (must be positive)
+    /// * `power` - Received power in dBm
+    /// * `bandwidth` - Signal bandwidth in Hz (must be positive)
+    /// * `snr` - Signal-to-Noise Ratio in dB
+    ///
+    /// # Errors
+    /// Returns `ValidationError::InvalidFrequency` if frequency is not positive and
+    /// `ValidationError::InvalidBandwidth` if bandwidth is not positive
+    pub fn new(
+        location: Coordinate,
+        timestamp: DateTime<Utc>,
+        frequency: f64,
+        power: f64,
+        bandwidth: f64,
+        snr: f64,
+    ) -> Result<Self, Error> {
+        if !(frequency.is_finite() && frequency > 0.0) {
+            return Err(ValidationError::InvalidFrequency(frequency).into());
+        }
+        if !(bandwidth.is_finite() && bandwidth > 0.0) {
+            return Err(ValidationError::InvalidBandwidth(bandwidth).into());
+        }
+
+        Ok(Self {
+            location,
+            timestamp,
+            frequency,
+            power,
+            bandwidth,
+            snr,
+        })
+    }
+}
@@ -1,3 +1,15 @@
 pub mod aggregate;
 pub mod coord;
+pub mod grid;
+pub mod layer;
+pub mod measurement;
+pub mod postgis;
 pub mod query;
+pub mod track;
+
+pub use aggregate::{LocationAggregate, Stats, aggregate_by_location};
+pub use coord::{BoundingBox, Coordinate, DbPoint};
+pub use grid::{grid_square, grid_square_center};
+pub use layer::Layer;
+pub use measurement::Measurement;
+pub use track::{Track, TrackMiss, TrackPoint};
@@ -0,0 +1,17 @@
+//! PostGIS SQL functions used for server-side spatial queries
+use diesel::define_sql_function;
+use diesel::sql_types::Double;
+
+use crate::schema::sql_types::Geography;
+
+define_sql_function! {
+    /// True if the two geographies are within `distance_m` metres of each other
+    #[sql_name = "ST_DWithin"]
+    fn st_dwithin(a: Geography, b: Geography, distance_m: Double) -> Bool;
+}
+
+define_sql_function! {
+    /// Great-circle distance between two geographies in metres
+    #[sql_name = "ST_Distance"]
+    fn st_distance(a: Geography, b: Geography) -> Double;
+}
@@ -1 +1,39 @@
+use std::borrow::Borrow;
 
+use chrono::{DateTime, Utc};
+
+use crate::spatial::{BoundingBox, Layer, Measurement};
+
+impl Layer {
+    /// Measurements located inside `bbox` (edges inclusive)
+    pub fn query_by_bbox(&self, bbox: impl Borrow<BoundingBox>) -> Vec<&Measurement> {
+        let bbox = bbox.borrow();
+        self.filter(|m| bbox.contains(&m.location))
+    }
+
+    /// Measurements taken between `start` and `end` (inclusive)
+    pub fn query_by_time_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<&Measurement> {
+        self.filter(|m| m.timestamp >= start && m.timestamp <= end)
+    }
+
+    /// Measurements whose frequency in Hz lies between `min_hz` and `max_hz` (inclusive)
+    pub fn query_by_frequency_range(&self, min_hz: f64, max_hz: f64) -> Vec<&Measurement> {
+        self.filter(|m| m.frequency >= min_hz && m.frequency <= max_hz)
+    }
+
+    /// Measurements with power at or above `min_dbm`
+    pub fn query_by_power_threshold(&self, min_dbm: f64) -> Vec<&Measurement> {
+        self.filter(|m| m.power >= min_dbm)
+    }
+
+    fn filter(&self, predicate: impl Fn(&Measurement) -> bool) -> Vec<&Measurement> {
+        self.measurements()
+            .iter()
+            .filter(|m| predicate(m))
+            .collect()
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+
+use crate::spatial::Coordinate;
+
+/// A timed GPS fix on a recorded track
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TrackPoint {
+    pub position: Coordinate,
+    pub time: DateTime<Utc>,
+    /// Elevation in metres, if the receiver reported one
+    pub elevation_m: Option<f64>,
+}
+
+/// Why a time has no position on a track
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackMiss {
+    /// Earlier than the first fix, by the given time
+    BeforeStart(TimeDelta),
+    /// Later than the last fix, by the given time
+    AfterEnd(TimeDelta),
+    /// Between fixes further apart than the allowed gap, or in different segments
+    InGap(TimeDelta),
+}
+
+impl fmt::Display for TrackMiss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrackMiss::BeforeStart(by) => {
+                write!(f, "{} before the track starts", format_delta(*by))
+            }
+            TrackMiss::AfterEnd(by) => write!(f, "{} after the track ends", format_delta(*by)),
+            TrackMiss::InGap(gap) => write!(f, "in a {} gap between fixes", format_delta(*gap)),
+        }
+    }
+}
+
+/// `1h 02m 05s`, `2m 05s` or `5.0s`
+fn format_delta(delta: TimeDelta) -> String {
+    let seconds = delta.num_milliseconds().abs() as f64 / 1000.0;
+    let whole = seconds as i64;
+    match whole {
+        3600.. => format!(
+            "{}h {:02}m {:02}s",
+            whole / 3600,
+            whole % 3600 / 60,
+            whole % 60
+        ),
+        60.. => format!("{}m {:02}s", whole / 60, whole % 60),
+        _ => format!("{:.1}s", seconds),
+    }
+}
+
+/// A recorded track, as segments of fixes in time order
+///
+/// A segment ends where the receiver lost its fix, so positions are never interpolated
+/// from one segment into the next.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Track {
+    segments: Vec<Vec<TrackPoint>>,
+}
+
+impl Track {
+    /// Sort each segment by time and drop empty ones; segments are ordered by their
+    /// first fix
+    pub fn new(segments: Vec<Vec<TrackPoint>>) -> Self {
+        let mut segments: Vec<Vec<TrackPoint>> = segments
+            .into_iter()
+            .filter(|segment| !segment.is_empty())
+            .map(|mut segment| {
+                segment.sort_by_key(|point| point.time);
+                segment
+            })
+            .collect();
+        segments.sort_by_key(|segment| segment[0].time);
+        Self { segments }
+    }
+
+    pub fn segments(&self) -> &[Vec<TrackPoint>] {
+        &self.segments
+    }
+
+    pub fn point_count(&self) -> usize {
+        self.segments.iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Time of the first and last fix, or None for an empty track
+    pub fn time_range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let start = self.segments.first()?.first()?.time;
+        let end = self
+            .segments
+            .iter()
+            .filter_map(|s| s.last())
+            .map(|p| p.time)
+            .max()?;
+        Some((start, end))
+    }
+
+    /// Position at `time`, interpolated linearly between the fixes either side
+    ///
+    /// # Errors
+    /// Returns the [`TrackMiss`] saying why `time` has no position: it is outside the
+    /// track, between segments, or between fixes more than `max_gap` apart. An empty
+    /// track misses every time with a zero-length gap.
+    pub fn position_at(
+        &self,
+        time: DateTime<Utc>,
+        max_gap: TimeDelta,
+    ) -> Result<Coordinate, TrackMiss> {
+        let (start, end) = self
+            .time_range()
+            .ok_or(TrackMiss::InGap(TimeDelta::zero()))?;
+        if time < start {
+            return Err(TrackMiss::BeforeStart(start - time));
+        }
+        if time > end {
+            return Err(TrackMiss::AfterEnd(time - end));
+        }
+
+        let Some(segment) = self
+            .segments
+            .iter()
+            .find(|segment| segment[0].time <= time && time <= segment[segment.len() - 1].time)
+        else {
+            // Between two segments: the gap runs from the last fix before to the next
+            let before = self
+                .segments
+                .iter()
+                .filter_map(|s| s.last())
+                .filter(|p| p.time < time)
+                .map(|p| p.time)
+                .max()
+                .unwrap_or(start);
+            let after = self
+                .segments
+                .iter()
+                .map(|s| s[0].time)
+                .filter(|t| *t > time)
+                .min()
+                .unwrap_or(end);
+            return Err(TrackMiss::InGap(after - before));
+        };
+
+        let next = segment.partition_point(|point| point.time < time);
+        let b = &segment[next];
+        if b.time == time {
+            return Ok(b.position);
+        }
+        let a = &segment[next - 1];
+        let gap = b.time - a.time;
+        if gap > max_gap {
+            return Err(TrackMiss::InGap(gap));
+        }
+        Ok(interpolate(a, b, time))
+    }
+}
+
+/// The position a constant speed from `a` to `b` reaches at `time`
+fn interpolate(a: &TrackPoint, b: &TrackPoint, time: DateTime<Utc>) -> Coordinate {
+    let span = (b.time - a.time).num_nanoseconds().unwrap_or(i64::MAX) as f64;
+    let fraction = (time - a.time).num_nanoseconds().unwrap_or(0) as f64 / span;
+    let lat = a.position.lat() + (b.position.lat() - a.position.lat()) * fraction;
+    // Take the short way round across the antimeridian
+    let mut lon_step = b.position.lon() - a.position.lon();
+    if lon_step > 180.0 {
+        lon_step -= 360.0;
+    } else if lon_step < -180.0 {
+        lon_step += 360.0;
+    }
+    let mut lon = a.position.lon() + lon_step * fraction;
+    if lon > 180.0 {
+        lon -= 360.0;
+    } else if lon < -180.0 {
+        lon += 360.0;
+    }
+    Coordinate::new(lat, lon).expect("a point between two valid positions is valid")
+}
@@ -1,4 +1,12 @@
-use crate::{Log, model::model::SignalMode};
+use crate::{
+    Coordinate, Log,
+    error::ValidationError,
+    model::{
+        Frequency, FrequencyUnit, LogDetails, LogTimeZone, Session, model::SignalMode,
+        parse_timestamp, tag::parse_tags,
+    },
+};
+use chrono::{DateTime, Utc};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     buffer::Buffer,
@@ -17,63 +25,6 @@ const LOG_ENTRY_HEADER_STYLE: ratatui::style::Style = Style::new()
     .fg(Color::Rgb(14, 15, 23))
     .bg(Color::Rgb(54, 68, 96));
 const NORMAL_ROW_BG: Color = Color::Rgb(14, 15, 23);
-#[derive(Debug, Serialize)]
-struct ModeSelectField {
-    #[serde(skip)]
-    label: &'static str,
-    value: Option<SignalMode>,
-}
-
-impl ModeSelectField {
-    const fn new(label: &'static str) -> Self {
-        Self { label, value: None }
-    }
-
-    fn on_key_press(&mut self, event: KeyEvent) {
-        match event.code {
-            //Round robin through modes
-            KeyCode::Up => {
-                self.value = match self.value {
-                    Some(SignalMode::AM) => Some(SignalMode::FM),
-                    Some(SignalMode::FM) => Some(SignalMode::USB),
-                    Some(SignalMode::USB) => Some(SignalMode::LSB),
-                    Some(SignalMode::LSB) => Some(SignalMode::CW),
-                    Some(SignalMode::CW) => Some(SignalMode::AM),
-                    None => Some(SignalMode::AM),
-                }
-            }
-            KeyCode::Down => {
-                self.value = match self.value {
-                    Some(SignalMode::AM) => Some(SignalMode::CW),
-                    Some(SignalMode::CW) => Some(SignalMode::LSB),
-                    Some(SignalMode::LSB) => Some(SignalMode::USB),
-                    Some(SignalMode::USB) => Some(SignalMode::FM),
-                    Some(SignalMode::FM) => Some(SignalMode::AM),
-                    None => Some(SignalMode::AM),
-                }
-            }
-            _ => {}
-        }
-    }
-}
-
-impl Widget for ModeSelectField {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let layout = Layout::horizontal([
-            Constraint::Length(self.label.len() as u16 + 2),
-            Constraint::Fill(1),
-        ]);
-        let chunks = layout.split(area);
-        let label = Line::from_iter([self.label, ":"]).bold();
-        let value = match self.value {
-            Some(v) => Line::from(format!("{:?}", v)),
-            None => Line::from("_____"),
-        };
-        label.render(chunks[0], buf);
-        value.render(chunks[1], buf);
-    }
-}
-
 #[derive(Debug, Clone, Serialize)]
 struct CoordinatesField {
     #[serde(skip)]
@@ -124,14 +75,14 @@ impl CoordinatesField {
         self.latitude_valid = self
             .latitude_input
             .value()
-            .parse::<f32>()
+            .parse::<f64>()
             .map(|lat| (-90.0..=90.0).contains(&lat))
             .unwrap_or(false);
 
         self.longitude_valid = self
             .longitude_input
             .value()
-            .parse::<f32>()
+            .parse::<f64>()
             .map(|lon| (-180.0..=180.0).contains(&lon))
             .unwrap_or(false);
     }
@@ -191,13 +142,13 @@ impl CoordinatesField {
     }
 
     /// Get the parsed latitude value
-    fn get_latitude(&self) -> Result<f32, std::num::ParseFloatError> {
-        self.latitude_input.value().parse::<f32>()
+    fn get_latitude(&self) -> Result<f64, std::num::ParseFloatError> {
+        self.latitude_input.value().parse::<f64>()
     }
 
     /// Get the parsed longitude value
-    fn get_longitude(&self) -> Result<f32, std::num::ParseFloatError> {
-        self.longitude_input.value().parse::<f32>()
+    fn get_longitude(&self) -> Result<f64, std::num::ParseFloatError> {
+        self.longitude_input.value().parse::<f64>()
     }
 
     /// Get cursor offset for rendering
@@ -283,14 +234,27 @@ impl Widget for CoordinatesField {
 pub struct NewLogInputForm {
     #[serde(skip)]
     pub focus: LogEntryFocus,
-    pub frequency: f32,
+    /// Raw frequency text; bare numbers are MHz
+    pub frequency_input: String,
     #[serde(skip)]
     pub coordinates_field: CoordinatesField,
     pub callsign: String,
     pub mode: SignalMode,
     pub comment: String,
+    /// Raw tag list, comma or space separated
+    pub tags_input: String,
+    /// Raw time the signal was logged; blank means when the entry is submitted
+    pub timestamp_input: String,
     pub recording_duration: f32,
+    /// Raw text for each optional measurement, indexed by `DetailField`
+    pub detail_inputs: [String; DetailField::ALL.len()],
     pub created_log: Option<Log>,
+    /// Session new entries are attached to; kept when the form is reset
+    #[serde(skip)]
+    pub active_session: Option<Session>,
+    /// Zone timestamps without an offset are read in; kept when the form is reset
+    #[serde(skip)]
+    pub time_zone: LogTimeZone,
 }
 
 impl Default for NewLogInputForm {
@@ -303,38 +267,131 @@ impl NewLogInputForm {
     pub fn new() -> Self {
         Self {
             focus: LogEntryFocus::default(),
-            frequency: 0.0,
+            frequency_input: String::new(),
             coordinates_field: CoordinatesField::new("Coordinates"),
             callsign: "____".to_string(),
             mode: SignalMode::AM,
             comment: "______".to_string(),
+            tags_input: String::new(),
+            timestamp_input: String::new(),
             recording_duration: 0.0,
+            detail_inputs: Default::default(),
             created_log: None,
+            active_session: None,
+            time_zone: LogTimeZone::default(),
         }
     }
 
+    /// Clear the entry fields for the next log, staying in the same session and zone
+    pub fn reset(&mut self) {
+        let active_session = self.active_session.take();
+        *self = Self {
+            active_session,
+            time_zone: self.time_zone,
+            ..Self::new()
+        };
+    }
+
     pub fn next_field(&mut self) {
         self.focus = self.focus.next();
     }
 
     pub fn previous_field(&mut self) {
         self.focus = match self.focus {
-            LogEntryFocus::Frequency => LogEntryFocus::RecordingDuration,
+            LogEntryFocus::Frequency => LogEntryFocus::Detail(DetailField::Speed),
             LogEntryFocus::Coordinates => LogEntryFocus::Frequency,
             LogEntryFocus::Callsign => LogEntryFocus::Coordinates,
             LogEntryFocus::Mode => LogEntryFocus::Callsign,
             LogEntryFocus::Comment => LogEntryFocus::Mode,
-            LogEntryFocus::RecordingDuration => LogEntryFocus::Comment,
+            LogEntryFocus::Tags => LogEntryFocus::Comment,
+            LogEntryFocus::Timestamp => LogEntryFocus::Tags,
+            LogEntryFocus::RecordingDuration => LogEntryFocus::Timestamp,
+            LogEntryFocus::Detail(DetailField::Power) => LogEntryFocus::RecordingDuration,
+            LogEntryFocus::Detail(field) => {
+                LogEntryFocus::Detail(DetailField::ALL[field as usize - 1])
+            }
+        }
+    }
+
+    /// Parse the frequency field, treating a bare number as MHz
+    pub fn frequency(&self) -> Result<Frequency, ValidationError> {
+        Frequency::parse_with_default_unit(&self.frequency_input, FrequencyUnit::MHz)
+    }
+
+    /// Parse the tag field into normalized tag names
+    pub fn tags(&self) -> Result<Vec<String>, ValidationError> {
+        parse_tags(&self.tags_input)
+    }
+
+    /// Parse the timestamp field in the form's zone, None if it is blank
+    pub fn timestamp(&self) -> Result<Option<DateTime<Utc>>, ValidationError> {
+        match self.timestamp_input.trim() {
+            "" => Ok(None),
+            input => parse_timestamp(input, self.time_zone).map(Some),
         }
     }
 
+    /// Parse the optional measurement fields, leaving blank ones as None
+    pub fn details(&self) -> Result<LogDetails, ValidationError> {
+        let number = |field: DetailField| -> Result<Option<f32>, ValidationError> {
+            let input = self.detail_inputs[field as usize].trim();
+            if input.is_empty() {
+                return Ok(None);
+            }
+            input
+                .parse::<f32>()
+                .map(Some)
+                .map_err(|_| ValidationError::InvalidNumber {
+                    field: field.label().to_string(),
+                    input: input.to_string(),
+                })
+        };
+        let bandwidth = match self.detail_inputs[DetailField::Bandwidth as usize].trim() {
+            "" => None,
+            input => Some(Frequency::parse_with_default_unit(
+                input,
+                FrequencyUnit::KHz,
+            )?),
+        };
+
+        let details = LogDetails {
+            power_dbm: number(DetailField::Power)?,
+            snr_db: number(DetailField::Snr)?,
+            bandwidth_hz: bandwidth,
+            altitude_m: number(DetailField::Altitude)?,
+            position_accuracy_m: number(DetailField::PositionAccuracy)?,
+            heading_deg: number(DetailField::Heading)?,
+            speed_mps: number(DetailField::Speed)?,
+        };
+        details.validate()?;
+        Ok(details)
+    }
+
+    /// Parse the coordinates field into a validated position
+    pub fn position(&self) -> Result<Coordinate, ValidationError> {
+        let number = |field: &str, input: &Input| {
+            input
+                .value()
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| ValidationError::InvalidNumber {
+                    field: field.to_string(),
+                    input: input.value().to_string(),
+                })
+        };
+        Coordinate::checked(
+            number("latitude", &self.coordinates_field.latitude_input)?,
+            number("longitude", &self.coordinates_field.longitude_input)?,
+        )
+    }
+
     /// Get the validated latitude value from the coordinates field
-    pub fn latitude(&self) -> Result<f32, std::num::ParseFloatError> {
+    pub fn latitude(&self) -> Result<f64, std::num::ParseFloatError> {
         self.coordinates_field.get_latitude()
     }
 
     /// Get the validated longitude value from the coordinates field
-    pub fn longitude(&self) -> Result<f32, std::num::ParseFloatError> {
+    pub fn longitude(&self) -> Result<f64, std::num::ParseFloatError> {
         self.coordinates_field.get_longitude()
     }
 
@@ -342,21 +399,11 @@ impl NewLogInputForm {
         match self.focus {
             LogEntryFocus::Frequency => {
                 if let KeyCode::Char(c) = event.code {
-                    if c.is_ascii_digit() || c == '.' {
-                        let current = self.frequency.to_string();
-                        let new_val = format!("{}{}", current, c);
-                        self.frequency = new_val.parse::<f32>().unwrap_or_default();
+                    if c.is_ascii_alphanumeric() || matches!(c, '.' | ' ' | '-' | '+') {
+                        self.frequency_input.push(c);
                     }
-                } else if event.code == KeyCode::Backspace
-                    && let current = self.frequency
-                {
-                    let mut current_str = current.to_string();
-                    current_str.pop();
-                    self.frequency = if current_str.is_empty() {
-                        0.0
-                    } else {
-                        current_str.parse::<f32>().unwrap_or_default()
-                    };
+                } else if event.code == KeyCode::Backspace {
+                    self.frequency_input.pop();
                 }
             }
             LogEntryFocus::Coordinates => {
@@ -382,22 +429,10 @@ impl NewLogInputForm {
             }
             LogEntryFocus::Mode => match event.code {
                 KeyCode::Up | KeyCode::Right => {
-                    self.mode = match self.mode {
-                        SignalMode::AM => SignalMode::FM,
-                        SignalMode::FM => SignalMode::USB,
-                        SignalMode::USB => SignalMode::LSB,
-                        SignalMode::LSB => SignalMode::CW,
-                        SignalMode::CW => SignalMode::AM,
-                    };
+                    self.mode = self.mode.next();
                 }
                 KeyCode::Down | KeyCode::Left => {
-                    self.mode = match self.mode {
-                        SignalMode::AM => SignalMode::CW,
-                        SignalMode::CW => SignalMode::LSB,
-                        SignalMode::LSB => SignalMode::USB,
-                        SignalMode::USB => SignalMode::FM,
-                        SignalMode::FM => SignalMode::AM,
-                    };
+                    self.mode = self.mode.previous();
                 }
                 _ => {}
             },
@@ -418,6 +453,22 @@ impl NewLogInputForm {
                     };
                 }
             }
+            LogEntryFocus::Tags => {
+                if let KeyCode::Char(c) = event.code {
+                    self.tags_input.push(c);
+                } else if event.code == KeyCode::Backspace {
+                    self.tags_input.pop();
+                }
+            }
+            LogEntryFocus::Timestamp => {
+                if let KeyCode::Char(c) = event.code {
+                    if c.is_ascii_alphanumeric() || matches!(c, '-' | ':' | '.' | '+' | ' ') {
+                        self.timestamp_input.push(c);
+                    }
+                } else if event.code == KeyCode::Backspace {
+                    self.timestamp_input.pop();
+                }
+            }
             LogEntryFocus::RecordingDuration => {
                 if let KeyCode::Char(c) = event.code {
                     if c.is_ascii_digit() || c == '.' {
@@ -437,6 +488,16 @@ impl NewLogInputForm {
                     };
                 }
             }
+            LogEntryFocus::Detail(field) => {
+                let input = &mut self.detail_inputs[field as usize];
+                if let KeyCode::Char(c) = event.code {
+                    if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | ' ') {
+                        input.push(c);
+                    }
+                } else if event.code == KeyCode::Backspace {
+                    input.pop();
+                }
+            }
         }
         /*match event.code {
              KeyCode::Enter => {
@@ -467,7 +528,10 @@ impl NewLogInputForm {
             LogEntryFocus::Callsign => 3,
             LogEntryFocus::Mode => 4,
             LogEntryFocus::Comment => 5,
-            LogEntryFocus::RecordingDuration => 6,
+            LogEntryFocus::Tags => 6,
+            LogEntryFocus::Timestamp => 7,
+            LogEntryFocus::RecordingDuration => 8,
+            LogEntryFocus::Detail(field) => 9 + field as u16,
         };
 
         if matches!(self.focus, LogEntryFocus::Coordinates) {
@@ -481,15 +545,21 @@ impl NewLogInputForm {
             LogEntryFocus::Callsign => "Callsign: ".len(),
             LogEntryFocus::Mode => "Mode: ".len(),
             LogEntryFocus::Comment => "Comment: ".len(),
+            LogEntryFocus::Tags => "Tags: ".len(),
+            LogEntryFocus::Timestamp => "Timestamp: ".len(),
             LogEntryFocus::RecordingDuration => "Recording duration: ".len(),
+            LogEntryFocus::Detail(field) => field.label().chars().count() + 2,
         };
         let value_len = match self.focus {
-            LogEntryFocus::Frequency => self.frequency.to_string().len(),
+            LogEntryFocus::Frequency => self.frequency_input.len(),
             LogEntryFocus::Coordinates => 0, // Handled above
             LogEntryFocus::Callsign => self.callsign.len(),
             LogEntryFocus::Mode => 0, // Mode doesn't show cursor
             LogEntryFocus::Comment => self.comment.len(),
+            LogEntryFocus::Tags => self.tags_input.chars().count(),
+            LogEntryFocus::Timestamp => self.timestamp_input.len(),
             LogEntryFocus::RecordingDuration => self.recording_duration.to_string().len(),
+            LogEntryFocus::Detail(field) => self.detail_inputs[field as usize].chars().count(),
         };
         Some((
             area.x + label_len as u16 + 2 + value_len as u16,
@@ -506,7 +576,10 @@ enum LogEntryFocus {
     Callsign,
     Mode,
     Comment,
+    Tags,
+    Timestamp,
     RecordingDuration,
+    Detail(DetailField),
 }
 
 impl LogEntryFocus {
@@ -516,8 +589,50 @@ impl LogEntryFocus {
             LogEntryFocus::Coordinates => LogEntryFocus::Callsign,
             LogEntryFocus::Callsign => LogEntryFocus::Mode,
             LogEntryFocus::Mode => LogEntryFocus::Comment,
-            LogEntryFocus::Comment => LogEntryFocus::RecordingDuration,
-            LogEntryFocus::RecordingDuration => LogEntryFocus::Frequency,
+            LogEntryFocus::Comment => LogEntryFocus::Tags,
+            LogEntryFocus::Tags => LogEntryFocus::Timestamp,
+            LogEntryFocus::Timestamp => LogEntryFocus::RecordingDuration,
+            LogEntryFocus::RecordingDuration => LogEntryFocus::Detail(DetailField::Power),
+            LogEntryFocus::Detail(DetailField::Speed) => LogEntryFocus::Frequency,
+            LogEntryFocus::Detail(field) => {
+                LogEntryFocus::Detail(DetailField::ALL[*field as usize + 1])
+            }
+        }
+    }
+}
+
+/// Optional measurement inputs shown below the required fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DetailField {
+    Power,
+    Snr,
+    Bandwidth,
+    Altitude,
+    PositionAccuracy,
+    Heading,
+    Speed,
+}
+
+impl DetailField {
+    pub const ALL: [DetailField; 7] = [
+        DetailField::Power,
+        DetailField::Snr,
+        DetailField::Bandwidth,
+        DetailField::Altitude,
+        DetailField::PositionAccuracy,
+        DetailField::Heading,
+        DetailField::Speed,
+    ];
+
+    pub const fn label(&self) -> &'static str {
+        match self {
+            DetailField::Power => "Power (dBm)",
+            DetailField::Snr => "SNR (dB)",
+            DetailField::Bandwidth => "Bandwidth (kHz)",
+            DetailField::Altitude => "Altitude (m)",
+            DetailField::PositionAccuracy => "GPS accuracy (m)",
+            DetailField::Heading => "Heading (°)",
+            DetailField::Speed => "Speed (m/s)",
         }
     }
 }
@@ -536,12 +651,32 @@ pub fn render_create_log_form(form: &NewLogInputForm, area: Rect, buf: &mut Buff
         Constraint::Length(1), // Callsign
         Constraint::Length(1), // Mode
         Constraint::Length(1), // Comment
+        Constraint::Length(1), // Tags
+        Constraint::Length(1), // Timestamp
         Constraint::Length(1), // Recording Duration
+        Constraint::Length(1), // Power
+        Constraint::Length(1), // SNR
+        Constraint::Length(1), // Bandwidth
+        Constraint::Length(1), // Altitude
+        Constraint::Length(1), // GPS accuracy
+        Constraint::Length(1), // Heading
+        Constraint::Length(1), // Speed
         Constraint::Fill(1),   // Rest of space
     ]);
     let chunks = layout.split(area);
+    let header = block.inner(chunks[0]);
     block.render(chunks[0], buf);
 
+    let session_line = match &form.active_session {
+        Some(session) => format!(
+            "Session: {} (since {})",
+            session.name,
+            session.started_at.format("%Y-%m-%d %H:%M")
+        ),
+        None => "No active session".to_string(),
+    };
+    Paragraph::new(Line::from(session_line).centered()).render(header, buf);
+
     let field_style = |is_focused: bool| {
         if is_focused {
             Style::default().fg(Color::Yellow).bold()
@@ -549,7 +684,13 @@ pub fn render_create_log_form(form: &NewLogInputForm, area: Rect, buf: &mut Buff
             Style::default().fg(Color::White)
         }
     };
-    let freq_field = format!("Frequency: {} MHz", form.frequency);
+    let freq_field = match form.frequency() {
+        Ok(freq) => format!("Frequency: {} ({})", form.frequency_input, freq),
+        Err(_) if form.frequency_input.is_empty() => {
+            "Frequency: (e.g. 146.52, 433920k)".to_string()
+        }
+        Err(_) => format!("Frequency: {} (invalid)", form.frequency_input),
+    };
 
     Paragraph::new(Line::from(freq_field))
         .style(field_style(form.focus == LogEntryFocus::Frequency))
@@ -575,7 +716,7 @@ pub fn render_create_log_form(form: &NewLogInputForm, area: Rect, buf: &mut Buff
         .style(field_style(form.focus == LogEntryFocus::Callsign))
         .render(chunks[3], buf);
 
-    let mode_field = format!("Mode: {:?}", form.mode);
+    let mode_field = format!("Mode: {}", form.mode);
 
     Paragraph::new(Line::from(mode_field))
         .style(field_style(form.focus == LogEntryFocus::Mode))
@@ -587,9 +728,49 @@ pub fn render_create_log_form(form: &NewLogInputForm, area: Rect, buf: &mut Buff
         .style(field_style(form.focus == LogEntryFocus::Comment))
         .render(chunks[5], buf);
 
+    let tags_field = match form.tags() {
+        _ if form.tags_input.is_empty() => "Tags: (optional, e.g. repeater, weak)".to_string(),
+        Ok(_) => format!("Tags: {}", form.tags_input),
+        Err(_) => format!("Tags: {} (invalid)", form.tags_input),
+    };
+
+    Paragraph::new(Line::from(tags_field))
+        .style(field_style(form.focus == LogEntryFocus::Tags))
+        .render(chunks[6], buf);
+
+    let timestamp_field = match form.timestamp() {
+        Ok(None) => format!(
+            "Timestamp: (now, or e.g. 2025-11-14 09:30 {})",
+            form.time_zone
+        ),
+        Ok(Some(timestamp)) => format!(
+            "Timestamp: {} ({} UTC)",
+            form.timestamp_input,
+            timestamp.format("%Y-%m-%d %H:%M:%S")
+        ),
+        Err(e) => format!("Timestamp: {} ({})", form.timestamp_input, e),
+    };
+
+    Paragraph::new(Line::from(timestamp_field))
+        .style(field_style(form.focus == LogEntryFocus::Timestamp))
+        .render(chunks[7], buf);
+
     let duration_field = format!("Recording duration: {} seconds", form.recording_duration);
 
     Paragraph::new(Line::from(duration_field))
         .style(field_style(form.focus == LogEntryFocus::RecordingDuration))
-        .render(chunks[6], buf);
+        .render(chunks[8], buf);
+
+    for (i, field) in DetailField::ALL.into_iter().enumerate() {
+        let input = &form.detail_inputs[i];
+        let detail_field = if input.is_empty() {
+            format!("{}: (optional)", field.label())
+        } else {
+            format!("{}: {}", field.label(), input)
+        };
+
+        Paragraph::new(Line::from(detail_field))
+            .style(field_style(form.focus == LogEntryFocus::Detail(field)))
+            .render(chunks[9 + i], buf);
+    }
 }
@@ -1,6 +1,6 @@
 pub mod create_log;
 pub mod spectrum_view;
-//pub mod view_logs;
+pub mod view_logs;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -67,10 +67,13 @@ impl SelectedTab {
     ) {
         create_log::render_create_log_form(form, area, buf);
     }
-    pub fn render_view_logs_tab(self, area: Rect, buf: &mut Buffer) {
-        Paragraph::new("View Logs")
-            .block(self.block())
-            .render(area, buf);
+    pub fn render_view_logs_tab(
+        self,
+        state: &view_logs::ViewLogsState,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        view_logs::render_view_logs(state, area, buf);
     }
     pub fn render_spectrum_viewer_tab(
         self,
@@ -86,7 +89,9 @@ impl Widget for SelectedTab {
     fn render(self, area: Rect, buf: &mut Buffer) {
         match self {
             SelectedTab::ViewLogs => {
-                self.render_view_logs_tab(area, buf);
+                Paragraph::new("View Logs (use render_view_logs_tab)")
+                    .block(self.block())
+                    .render(area, buf);
             }
             SelectedTab::CreateLog => {
                 Paragraph::new("Create Log (use render_create_log_tab)")
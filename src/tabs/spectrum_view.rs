@@ -1,3 +1,4 @@
+use crate::model::{Frequency, NewEquipmentProfile};
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
@@ -25,6 +26,14 @@ impl SpectrumSource {
     pub fn all() -> Vec<SpectrumSource> {
         vec![SpectrumSource::HackRF, SpectrumSource::File]
     }
+
+    /// Receiver recorded in equipment profiles
+    pub fn sdr_model(&self) -> &'static str {
+        match self {
+            SpectrumSource::HackRF => "HackRF One",
+            SpectrumSource::File => "File playback",
+        }
+    }
 }
 
 enum SelectedControl {
@@ -49,6 +58,11 @@ pub struct SpectrumViewerState {
 
     pub lna_gain: usize,
     pub vga_gain: usize,
+    /// Whether the source is streaming, which it does while the viewer is shown
+    pub streaming: bool,
+    /// Receive chain the source last streamed with, kept once the viewer is left so
+    /// logs created on other tabs still record it
+    streamed_with: Option<NewEquipmentProfile>,
 }
 
 impl Default for SpectrumViewerState {
@@ -61,6 +75,8 @@ impl Default for SpectrumViewerState {
             spectrum_data: Vec::new(),
             lna_gain: 0,
             vga_gain: 0,
+            streaming: false,
+            streamed_with: None,
         };
         state.generate_sample_data();
         state
@@ -78,6 +94,37 @@ impl SpectrumViewerState {
         state
     }
 
+    /// Start streaming from the source, when the viewer is shown
+    pub fn start_streaming(&mut self) {
+        self.streaming = true;
+    }
+
+    /// Stop streaming, when the viewer is left, remembering the settings it streamed with
+    pub fn stop_streaming(&mut self) {
+        if self.streaming {
+            self.streamed_with = Some(self.receiver_settings());
+        }
+        self.streaming = false;
+    }
+
+    /// Receive chain settings to store with a new log: the current ones while the
+    /// source is streaming, else the last ones it streamed with, None if it never has
+    pub fn equipment_snapshot(&self) -> Option<NewEquipmentProfile> {
+        if self.streaming {
+            Some(self.receiver_settings())
+        } else {
+            self.streamed_with.clone()
+        }
+    }
+
+    fn receiver_settings(&self) -> NewEquipmentProfile {
+        NewEquipmentProfile {
+            lna_gain_db: self.lna_gain as i32,
+            vga_gain_db: self.vga_gain as i32,
+            ..NewEquipmentProfile::new(self.source.sdr_model())
+        }
+    }
+
     /// Move frequency up by one step
     pub fn increase_frequency(&mut self) {
         self.center_frequency += self.frequency_step;
@@ -270,9 +317,12 @@ fn render_spectrum_chart(state: &SpectrumViewerState, area: Rect, buf: &mut Buff
 
     // Create x-axis labels
     let x_labels = vec![
-        Span::raw(format!("{:.1} M", freq_min_mhz)),
-        Span::raw(format!("{:.1} M", (freq_min_mhz + freq_max_mhz) / 2.0)),
-        Span::raw(format!("{:.1} M", freq_max_mhz)),
+        Span::raw(format!("{:.1}", Frequency::from_hz_f64(freq_min))),
+        Span::raw(format!(
+            "{:.1}",
+            Frequency::from_hz_f64(state.center_frequency)
+        )),
+        Span::raw(format!("{:.1}", Frequency::from_hz_f64(freq_max))),
     ];
 
     // Create y-axis labels (power in dBm)
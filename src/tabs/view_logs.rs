@@ -1,9 +1,12 @@
 use crate::Log;
+use crate::db::filter::{LogCursor, LogFilter, LogPage};
+use crate::db::search::{SearchHit, search_terms};
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Rect},
-    style::{Color, Modifier, Style, Stylize},
-    widgets::{Block, Borders, Cell, Row, Table, Widget},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, StatefulWidget, Table, TableState, Widget},
 };
 
 // Theme colors
@@ -12,20 +15,33 @@ const HEADER_FG: Color = Color::Rgb(14, 15, 23);
 const SELECTED_STYLE_FG: Color = Color::Rgb(138, 173, 244);
 const NORMAL_ROW_COLOR: Color = Color::Rgb(14, 15, 23);
 const ALT_ROW_COLOR: Color = Color::Rgb(20, 21, 29);
+const CHIP_BG: Color = Color::Rgb(138, 173, 244);
 
 // Column widths
 const COL_WIDTH_ID: u16 = 5;
-const COL_WIDTH_FREQUENCY: u16 = 12;
+const COL_WIDTH_FREQUENCY: u16 = 16;
 const COL_WIDTH_LAT: u16 = 10;
 const COL_WIDTH_LON: u16 = 10;
 const COL_WIDTH_CALLSIGN: u16 = 12;
-const COL_WIDTH_MODE: u16 = 6;
+const COL_WIDTH_MODE: u16 = 8;
 const COL_WIDTH_DURATION: u16 = 10;
+const COL_WIDTH_POWER: u16 = 10;
+const COL_WIDTH_SNR: u16 = 8;
+const COL_WIDTH_BANDWIDTH: u16 = 12;
+const COL_WIDTH_ALTITUDE: u16 = 9;
+const COL_WIDTH_ACCURACY: u16 = 8;
+const COL_WIDTH_HEADING: u16 = 6;
+const COL_WIDTH_SPEED: u16 = 9;
+const COL_WIDTH_TAGS: u16 = 20;
 const COL_WIDTH_TIMESTAMP: u16 = 16;
 
+/// Number of logs fetched per page
+pub const PAGE_SIZE: i64 = 200;
+
 // Format strings
 const DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
 const PLACEHOLDER_CALLSIGN: &str = "N/A";
+const PLACEHOLDER_MEASUREMENT: &str = "-";
 
 /// Table theme configuration for consistent styling
 struct TableTheme {
@@ -50,22 +66,41 @@ impl Default for TableTheme {
 
 /// Extension trait for Log to provide table formatting
 trait LogTableFormatter {
-    fn to_table_row(&self) -> [String; 8];
+    fn to_table_row(&self) -> [String; 16];
+}
+
+/// Format an optional measurement, or a placeholder if it was not recorded
+fn format_optional(value: Option<f32>, precision: usize, unit: &str) -> String {
+    value.map_or_else(
+        || PLACEHOLDER_MEASUREMENT.to_string(),
+        |v| format!("{:.*}{}", precision, v, unit),
+    )
 }
 
 impl LogTableFormatter for Log {
-    fn to_table_row(&self) -> [String; 8] {
+    fn to_table_row(&self) -> [String; 16] {
         [
             self.id.to_string(),
-            format!("{:.2} MHz", self.frequency),
+            self.frequency.to_string(),
             format!("{:.4}°", self.xcoord),
             format!("{:.4}°", self.ycoord),
             self.callsign
                 .as_deref()
                 .unwrap_or(PLACEHOLDER_CALLSIGN)
                 .to_string(),
-            self.mode.clone(),
+            self.mode.to_string(),
             format!("{:.1}s", self.recording_duration),
+            format_optional(self.power_dbm, 1, " dBm"),
+            format_optional(self.snr_db, 1, " dB"),
+            self.bandwidth_hz.map_or_else(
+                || PLACEHOLDER_MEASUREMENT.to_string(),
+                |bw| format!("{:.1}", bw),
+            ),
+            format_optional(self.altitude_m, 0, " m"),
+            format_optional(self.position_accuracy_m, 1, " m"),
+            format_optional(self.heading_deg, 0, "°"),
+            format_optional(self.speed_mps, 1, " m/s"),
+            self.tags.join(", "),
             self.timestamp.format(DATE_FORMAT).to_string(),
         ]
     }
@@ -77,17 +112,51 @@ pub struct ViewLogsState {
     pub selected_index: usize,
     // TODO: Implement scrolling to utilize this field
     pub scroll_offset: usize,
+    /// Criteria the listed logs were queried with
+    pub filter: LogFilter,
+    /// Cursor for the page after the loaded logs, None once the last page is loaded
+    pub next_cursor: Option<LogCursor>,
+    /// Whether the first page has been loaded
+    pub loaded: bool,
+    /// Whether a page has been requested and not yet arrived
+    pub loading: bool,
+    /// Text typed at the `/` prompt; while it has words the list shows its matches
+    pub search: Option<String>,
+    /// Whether keys go to the search prompt
+    pub search_editing: bool,
 }
 
 impl ViewLogsState {
     pub fn new(logs: Vec<Log>) -> Self {
         Self {
             logs,
-            selected_index: 0,
-            scroll_offset: 0,
+            loaded: true,
+            ..Default::default()
         }
     }
 
+    /// Replace the list with the first page of a query
+    pub fn load_first_page(&mut self, page: LogPage) {
+        self.refresh_logs(page.logs);
+        self.next_cursor = page.next;
+        self.loaded = true;
+        self.loading = false;
+    }
+
+    /// Append the next page, keeping the selection
+    pub fn append_page(&mut self, page: LogPage) {
+        self.logs.extend(page.logs);
+        self.next_cursor = page.next;
+        self.loading = false;
+    }
+
+    /// Whether the selection has reached the end of the loaded logs and more are available
+    ///
+    /// False while a page is already on its way.
+    pub fn needs_next_page(&self) -> bool {
+        !self.loading && self.next_cursor.is_some() && self.selected_index + 1 >= self.logs.len()
+    }
+
     /// Move selection to the next item
     pub fn select_next(&mut self) {
         if self.logs.is_empty() {
@@ -141,6 +210,87 @@ impl ViewLogsState {
         self.scroll_offset = 0;
     }
 
+    /// Also require the first tag of the selected log that the filter does not yet
+    /// require, returning whether the filter changed
+    pub fn add_tag_filter_from_selected(&mut self) -> bool {
+        let Some(tag) = self.selected_log().and_then(|log| {
+            log.tags
+                .iter()
+                .find(|tag| !self.filter.required_tags().contains(tag))
+                .cloned()
+        }) else {
+            return false;
+        };
+        self.filter = std::mem::take(&mut self.filter).tag(&tag);
+        true
+    }
+
+    /// Drop the most recently added tag filter, returning whether there was one
+    pub fn remove_last_tag_filter(&mut self) -> bool {
+        let Some(tag) = self.filter.required_tags().last().cloned() else {
+            return false;
+        };
+        self.filter = std::mem::take(&mut self.filter).without_tag(&tag);
+        true
+    }
+
+    /// Open the search prompt, keeping any query already typed
+    pub fn start_search(&mut self) {
+        self.search.get_or_insert_default();
+        self.search_editing = true;
+    }
+
+    /// Type a character at the search prompt
+    pub fn push_search_char(&mut self, c: char) {
+        self.search.get_or_insert_default().push(c);
+    }
+
+    /// Delete the last character at the search prompt
+    pub fn pop_search_char(&mut self) {
+        if let Some(search) = &mut self.search {
+            search.pop();
+        }
+    }
+
+    /// Leave the prompt and keep browsing the matches, dropping a query without words
+    pub fn finish_search(&mut self) {
+        self.search_editing = false;
+        if self.search_query().is_none() {
+            self.search = None;
+        }
+    }
+
+    /// Leave the prompt and go back to the filtered list
+    pub fn clear_search(&mut self) {
+        self.search = None;
+        self.search_editing = false;
+    }
+
+    /// The query to search for, None while it has no words and the filtered list is shown
+    pub fn search_query(&self) -> Option<&str> {
+        self.search
+            .as_deref()
+            .filter(|search| !search_terms(search).is_empty())
+    }
+
+    /// Show the matches for `query` that pass the filter, returning false if the query
+    /// has changed since they were requested
+    pub fn load_search_results(&mut self, query: &str, hits: Vec<SearchHit>) -> bool {
+        if self.search_query() != Some(query) {
+            return false;
+        }
+        let logs = hits
+            .into_iter()
+            .map(|hit| hit.log)
+            .filter(|log| self.filter.matches(log))
+            .collect();
+        self.refresh_logs(logs);
+        self.next_cursor = None;
+        self.loaded = true;
+        self.loading = false;
+        true
+    }
+
     /// Get the currently selected log, if any
     pub fn selected_log(&self) -> Option<&Log> {
         self.logs.get(self.selected_index)
@@ -148,20 +298,37 @@ impl ViewLogsState {
 }
 
 /// Create the table header
-fn create_header(theme: &TableTheme) -> Row {
+fn create_header(theme: &TableTheme) -> Row<'static> {
     let header_style = Style::default().fg(theme.header_fg).bg(theme.header_bg);
 
-    ["ID", "Frequency", "Lat", "Lon", "Callsign", "Mode", "Duration", "Timestamp"]
-        .into_iter()
-        .map(Cell::from)
-        .collect::<Row>()
-        .style(header_style)
-        .height(1)
+    [
+        "ID",
+        "Frequency",
+        "Lat",
+        "Lon",
+        "Callsign",
+        "Mode",
+        "Duration",
+        "Power",
+        "SNR",
+        "Bandwidth",
+        "Altitude",
+        "Accuracy",
+        "Hdg",
+        "Speed",
+        "Tags",
+        "Timestamp",
+    ]
+    .into_iter()
+    .map(Cell::from)
+    .collect::<Row>()
+    .style(header_style)
+    .height(1)
 }
 
 /// Create a styled row for a log entry
-fn create_row(log: &Log, index: usize, is_selected: bool, theme: &TableTheme) -> Row {
-    let bg_color = if index % 2 == 0 {
+fn create_row(log: &Log, index: usize, is_selected: bool, theme: &TableTheme) -> Row<'static> {
+    let bg_color = if index.is_multiple_of(2) {
         theme.normal_row
     } else {
         theme.alt_row
@@ -184,7 +351,7 @@ fn create_row(log: &Log, index: usize, is_selected: bool, theme: &TableTheme) ->
 fn create_rows<'a>(
     state: &'a ViewLogsState,
     theme: &'a TableTheme,
-) -> impl Iterator<Item = Row> + 'a {
+) -> impl Iterator<Item = Row<'static>> + 'a {
     state
         .logs
         .iter()
@@ -193,7 +360,12 @@ fn create_rows<'a>(
 }
 
 /// Create the complete table widget
-fn create_table(header: Row, rows: impl Iterator<Item = Row>, theme: &TableTheme) -> Table {
+fn create_table<'a>(
+    header: Row<'a>,
+    rows: impl IntoIterator<Item = Row<'a>>,
+    title: String,
+    theme: &TableTheme,
+) -> Table<'a> {
     let widths = [
         Constraint::Length(COL_WIDTH_ID),
         Constraint::Length(COL_WIDTH_FREQUENCY),
@@ -202,27 +374,99 @@ fn create_table(header: Row, rows: impl Iterator<Item = Row>, theme: &TableTheme
         Constraint::Length(COL_WIDTH_CALLSIGN),
         Constraint::Length(COL_WIDTH_MODE),
         Constraint::Length(COL_WIDTH_DURATION),
+        Constraint::Length(COL_WIDTH_POWER),
+        Constraint::Length(COL_WIDTH_SNR),
+        Constraint::Length(COL_WIDTH_BANDWIDTH),
+        Constraint::Length(COL_WIDTH_ALTITUDE),
+        Constraint::Length(COL_WIDTH_ACCURACY),
+        Constraint::Length(COL_WIDTH_HEADING),
+        Constraint::Length(COL_WIDTH_SPEED),
+        Constraint::Length(COL_WIDTH_TAGS),
         Constraint::Min(COL_WIDTH_TIMESTAMP),
     ];
 
     let block = Block::default()
         .borders(Borders::ALL)
-        .title("View Logs")
+        .title(title)
         .style(Style::default().bg(theme.normal_row));
 
     Table::new(rows, widths)
         .header(header)
         .block(block)
-        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .row_highlight_style(Style::default().add_modifier(Modifier::BOLD))
+}
+
+/// One line of chips for the tags the list is filtered by
+fn tag_chips(tags: &[String]) -> Line<'static> {
+    let chip_style = Style::default().fg(HEADER_FG).bg(CHIP_BG);
+    let mut spans = vec![Span::raw("Tags: ")];
+    for tag in tags {
+        spans.push(Span::styled(format!(" {} ", tag), chip_style));
+        spans.push(Span::raw(" "));
+    }
+    spans.push(Span::raw("(t: add selected log's tag, T: remove last)"));
+    Line::from(spans)
 }
 
-/// Render the view logs table
+/// The search prompt line, with a cursor while it is being edited
+fn search_prompt(search: &str, editing: bool) -> Line<'static> {
+    let cursor = if editing { "█" } else { "" };
+    let help = if editing {
+        "  (Enter: browse matches, Esc: clear)"
+    } else {
+        "  (/: edit search)"
+    };
+    Line::from(vec![
+        Span::styled(
+            format!("/{}{}", search, cursor),
+            Style::default().fg(CHIP_BG),
+        ),
+        Span::raw(help),
+    ])
+}
+
+/// Render the view logs table, with the search prompt and tag filter chips above it
+/// when they are in use
 pub fn render_view_logs(state: &ViewLogsState, area: Rect, buf: &mut Buffer) {
     let theme = TableTheme::default();
+    let line_style = Style::default().bg(theme.normal_row);
+
+    let mut area = area;
+    let mut render_line = |line: Line<'static>| {
+        let [line_area, rest] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        Paragraph::new(line)
+            .style(line_style)
+            .render(line_area, buf);
+        area = rest;
+    };
+    if let Some(search) = &state.search {
+        render_line(search_prompt(search, state.search_editing));
+    }
+    let required_tags = state.filter.required_tags();
+    if !required_tags.is_empty() {
+        render_line(tag_chips(required_tags));
+    }
 
     let header = create_header(&theme);
     let rows = create_rows(state, &theme);
-    let table = create_table(header, rows, &theme);
+    let loading = if state.loading { ", loading…" } else { "" };
+    let title = match state.search_query() {
+        Some(query) => format!(
+            "View Logs ({} matching \"{}\"{})",
+            state.logs.len(),
+            query,
+            loading
+        ),
+        None => {
+            let more = if state.next_cursor.is_some() { "+" } else { "" };
+            format!("View Logs ({}{} loaded{})", state.logs.len(), more, loading)
+        }
+    };
+    let table = create_table(header, rows, title, &theme);
 
-    table.render(area, buf);
+    let mut table_state = TableState::default()
+        .with_offset(state.scroll_offset)
+        .with_selected(Some(state.selected_index));
+    StatefulWidget::render(table, area, buf, &mut table_state);
 }
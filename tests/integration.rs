@@ -1,10 +1,33 @@
 // Integration test module - makes tests in integration/ directory visible to cargo test
 
 mod integration {
+    mod common;
+
     mod coordinate_tests;
+    mod frequency_tests;
     mod measurement_tests;
     mod layer_tests;
+    mod mode_tests;
     mod query_tests;
     mod aggregate_tests;
     mod quickstart_tests;
+    mod repair_tests;
+    mod log_tests;
+    mod filter_tests;
+    mod store_tests;
+    mod queue_tests;
+    mod worker_tests;
+    mod merge_tests;
+    mod revision_tests;
+    mod session_tests;
+    mod equipment_tests;
+    mod tag_tests;
+    mod search_tests;
+    mod error_tests;
+    mod db_tests;
+    mod format_tests;
+    mod adif_tests;
+    mod geojson_tests;
+    mod kml_tests;
+    mod geotag_tests;
 }
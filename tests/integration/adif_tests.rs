@@ -0,0 +1,267 @@
+use chrono::{DateTime, Utc};
+use sdr_db::formats::adif::{
+    adif_band, adif_location, adif_mode, parse_adif_location, signal_mode,
+};
+use sdr_db::db::{MemoryLogStore, merge_logs};
+use sdr_db::{
+    Coordinate, Frequency, Log, LogFormat, LogTimeZone, LogUuid, SignalMode, ValidationError,
+};
+
+use super::common::utc_at;
+
+fn base_time() -> DateTime<Utc> {
+    utc_at(2025, 11, 8, 21, 4, 30)
+}
+
+fn log(id: i32, mhz: f64, mode: SignalMode) -> Log {
+    let position = Coordinate::new(-33.868_82, 151.209_29).unwrap();
+    Log {
+        id,
+        frequency: Frequency::from_mhz(mhz),
+        xcoord: position.lat() as f32,
+        ycoord: position.lon() as f32,
+        callsign: Some("vk2abc".to_string()),
+        mode,
+        comment: Some(format!("<loud> & clear\nlog {}", id)),
+        timestamp: base_time() + chrono::Duration::minutes(id as i64),
+        recording_duration: 0.0,
+        location: Some(position.into()),
+        power_dbm: None,
+        snr_db: None,
+        bandwidth_hz: None,
+        altitude_m: None,
+        position_accuracy_m: None,
+        heading_deg: None,
+        speed_mps: None,
+        uuid: LogUuid::new_v7(),
+        session_id: None,
+        equipment_profile_id: None,
+        tags: Vec::new(),
+    }
+}
+
+/// One log in each mode, some with every optional field
+fn sample_logs() -> Vec<Log> {
+    let modes = [
+        SignalMode::FM,
+        SignalMode::NFM,
+        SignalMode::WFM,
+        SignalMode::AM,
+        SignalMode::USB,
+        SignalMode::LSB,
+        SignalMode::CW,
+        SignalMode::DMR,
+        SignalMode::P25,
+        SignalMode::DStar,
+        SignalMode::FT8,
+        SignalMode::RTTY,
+        SignalMode::APRS,
+        SignalMode::ADSB,
+        SignalMode::Digital,
+        SignalMode::Other("JS8".to_string()),
+    ];
+    let mut logs: Vec<Log> = modes
+        .into_iter()
+        .enumerate()
+        .map(|(i, mode)| log(i as i32 + 1, 3.5 + i as f64 * 27.123_456, mode))
+        .collect();
+    let full = &mut logs[0];
+    full.timestamp += chrono::Duration::microseconds(250_001);
+    full.recording_duration = 42.5;
+    full.power_dbm = Some(-87.25);
+    full.snr_db = Some(11.5);
+    full.bandwidth_hz = Some(Frequency::from_khz(12.5));
+    full.altitude_m = Some(58.0);
+    full.position_accuracy_m = Some(3.2);
+    full.heading_deg = Some(271.0);
+    full.speed_mps = Some(13.9);
+    full.tags = vec!["mobile".to_string(), "weak".to_string()];
+    logs[1].callsign = None;
+    logs[1].comment = None;
+    logs
+}
+
+fn round_trip(format: LogFormat) {
+    let logs = sample_logs();
+    let mut out = Vec::new();
+    format.write_logs(&logs, &mut out).unwrap();
+    let read = format.read_logs(&out, LogTimeZone::utc()).unwrap();
+
+    assert_eq!(read.len(), logs.len());
+    for (read, written) in read.iter().zip(&logs) {
+        assert_eq!(read.uuid, written.uuid);
+        assert_eq!(
+            sdr_db::db::merge::differing_fields(read, written),
+            Vec::<&str>::new(),
+            "{}",
+            written.mode
+        );
+    }
+}
+
+#[test]
+fn test_adi_round_trip() {
+    round_trip(LogFormat::Adif);
+}
+
+#[test]
+fn test_adx_round_trip() {
+    round_trip(LogFormat::Adx);
+}
+
+#[test]
+fn test_adi_uses_standard_fields() {
+    let mut out = Vec::new();
+    LogFormat::Adif
+        .write_logs(&[log(1, 146.52, SignalMode::USB)], &mut out)
+        .unwrap();
+    let adi = String::from_utf8(out).unwrap();
+
+    assert!(adi.contains("<ADIF_VER:5>3.1.4"));
+    assert!(adi.contains("<EOH>"));
+    for field in [
+        "<CALL:6>vk2abc",
+        "<QSO_DATE:8>20251108",
+        "<TIME_ON:6>210530",
+        "<FREQ:6>146.52",
+        "<BAND:2>2m",
+        "<MODE:3>SSB",
+        "<SUBMODE:3>USB",
+        "<MY_LAT:11>S033 52.129",
+        "<MY_LON:11>E151 12.557",
+        "<MY_GRIDSQUARE:6>QF56od",
+    ] {
+        assert!(adi.contains(field), "{} in {}", field, adi);
+    }
+    // The comment's length counts its line break
+    assert!(adi.contains("<COMMENT:20><loud> & clear\nlog 1 <APP_SDRDB_UUID:36>"));
+    assert!(!adi.contains("APP_SDRDB_MODE"));
+}
+
+#[test]
+fn test_read_adi_from_logging_software() {
+    let adi = b"Generated by another logger\n\
+        <adif_ver:5>3.1.0 <programid:6>WSJT-X <EOH>\n\
+        <call:5>K1ABC <gridsquare:4>FN42 <mode:4>MFSK <submode:3>FT4 <qso_date:8>20251101\n\
+        <time_on:4>1405 <freq:9>14.080000 <band:3>20m <my_gridsquare:6>IO91wm <eor>\n\
+        <CALL:4>G3XY <MODE:3>SSB <QSO_DATE:8>20251102 <TIME_ON:6>090000 <FREQ:5>3.760\n\
+        <MY_LAT:11>N051 30.444 <MY_LON:11>W000 07.668 <COMMENT:4>nice <EOR>\n";
+    let logs = LogFormat::Adif.read_logs(adi, LogTimeZone::utc()).unwrap();
+    assert_eq!(logs.len(), 2);
+
+    assert_eq!(logs[0].callsign.as_deref(), Some("K1ABC"));
+    assert_eq!(logs[0].mode, SignalMode::Other("FT4".to_string()));
+    assert_eq!(logs[0].frequency, Frequency::from_khz(14_080.0));
+    assert_eq!(logs[0].timestamp, utc_at(2025, 11, 1, 14, 5, 0));
+    // Positioned at the centre of the grid square
+    let london = Coordinate::new(51.5074, -0.1278).unwrap();
+    let position = Coordinate::new(logs[0].xcoord as f64, logs[0].ycoord as f64).unwrap();
+    assert!(position.distance_m(&london) < 5_000.0);
+
+    // SSB below 10 MHz is lower sideband
+    assert_eq!(logs[1].mode, SignalMode::LSB);
+    assert!((logs[1].xcoord - 51.5074).abs() < 1e-4);
+    assert!((logs[1].ycoord + 0.1278).abs() < 1e-4);
+    assert_eq!(logs[1].comment.as_deref(), Some("nice"));
+}
+
+#[test]
+fn test_reimported_adi_from_logging_software_is_skipped() {
+    // No APP_SDRDB_UUID, so the uuids come from the records themselves
+    let adi = b"<EOH>\n\
+        <CALL:5>K1ABC <MODE:3>FT8 <QSO_DATE:8>20251101 <TIME_ON:4>1405 <FREQ:6>14.074\n\
+        <MY_GRIDSQUARE:6>IO91wm <EOR>\n\
+        <CALL:4>G3XY <MODE:2>CW <QSO_DATE:8>20251101 <TIME_ON:4>1405 <FREQ:5>7.030\n\
+        <MY_GRIDSQUARE:6>IO91wm <EOR>\n";
+    let mut target = MemoryLogStore::new();
+
+    for expected_imported in [2, 0] {
+        let read = LogFormat::Adif.read_logs(adi, LogTimeZone::utc()).unwrap();
+        let report = merge_logs(&mut target, &mut MemoryLogStore::from_logs(read)).unwrap();
+        assert_eq!(report.imported, expected_imported);
+    }
+    assert_eq!(target.len(), 2);
+}
+
+#[test]
+fn test_adi_qsos_in_the_same_minute_are_both_imported() {
+    // A contest log: same minute, frequency, mode and station position
+    let adi = b"<EOH>\n\
+        <CALL:5>K1ABC <MODE:3>FT8 <QSO_DATE:8>20251101 <TIME_ON:4>1200 <FREQ:6>14.074\n\
+        <MY_LAT:11>N051 30.444 <MY_LON:11>W000 07.668 <EOR>\n\
+        <CALL:5>W2XYZ <MODE:3>FT8 <QSO_DATE:8>20251101 <TIME_ON:4>1200 <FREQ:6>14.074\n\
+        <MY_LAT:11>N051 30.444 <MY_LON:11>W000 07.668 <EOR>\n";
+    let read = LogFormat::Adif.read_logs(adi, LogTimeZone::utc()).unwrap();
+    assert_ne!(read[0].uuid, read[1].uuid);
+
+    let mut target = MemoryLogStore::new();
+    let report = merge_logs(&mut target, &mut MemoryLogStore::from_logs(read)).unwrap();
+    assert_eq!(report.imported, 2);
+    assert!(report.conflicts.is_empty());
+    assert_eq!(target.len(), 2);
+}
+
+#[test]
+fn test_bad_adif_reports_the_line() {
+    let utc = LogTimeZone::utc();
+    let missing_position =
+        b"<EOH>\n<CALL:4>G3XY <MODE:2>CW\n<QSO_DATE:8>20251102 <TIME_ON:4>0900 <FREQ:5>7.030 <EOR>";
+    assert_eq!(
+        LogFormat::Adif
+            .read_logs(missing_position, utc)
+            .unwrap_err(),
+        ValidationError::InvalidRecord {
+            line: 2,
+            reason: "MY_LAT/MY_LON or MY_GRIDSQUARE is missing".to_string(),
+        }
+    );
+    assert!(matches!(
+        LogFormat::Adif.read_logs(b"<CALL:40>G3XY <EOR>", utc),
+        Err(ValidationError::InvalidRecord { line: 1, .. })
+    ));
+    assert_eq!(
+        LogFormat::Adif
+            .read_logs(b"<CALL:18446744073709551615>G3XY <EOR>", utc)
+            .unwrap_err(),
+        ValidationError::InvalidRecord {
+            line: 1,
+            reason: "CALL runs past the end".to_string(),
+        }
+    );
+    assert!(matches!(
+        LogFormat::Adx.read_logs(b"<ADX><RECORDS><RECORD></RECORDS></ADX>", utc),
+        Err(ValidationError::InvalidRecord { .. })
+    ));
+    assert!(matches!(
+        LogFormat::Adx.read_logs(b"<gpx></gpx>", utc),
+        Err(ValidationError::InvalidRecord { .. })
+    ));
+}
+
+#[test]
+fn test_adif_enumerations() {
+    assert_eq!(adif_band(Frequency::from_mhz(7.074)), Some("40m"));
+    assert_eq!(adif_band(Frequency::from_mhz(433.92)), Some("70cm"));
+    assert_eq!(adif_band(Frequency::from_mhz(98.1)), None);
+
+    assert_eq!(
+        adif_mode(&SignalMode::DMR),
+        (Some("DIGITALVOICE"), Some("DMR"))
+    );
+    let hf = Frequency::from_mhz(14.2);
+    assert_eq!(signal_mode("ssb", None, hf), Ok(SignalMode::USB));
+    assert_eq!(
+        signal_mode("DIGITALVOICE", Some("DSTAR"), hf),
+        Ok(SignalMode::DStar)
+    );
+    assert_eq!(signal_mode("FT8", None, hf), Ok(SignalMode::FT8));
+
+    assert_eq!(adif_location(-0.1278, 'E', 'W'), "W000 07.668");
+    assert_eq!(adif_location(51.999_999_9, 'N', 'S'), "N052 00.000");
+    assert_eq!(
+        parse_adif_location("S033 52.129"),
+        Some(-(33.0 + 52.129 / 60.0))
+    );
+    assert_eq!(parse_adif_location("-33.5"), Some(-33.5));
+    assert_eq!(parse_adif_location("N051 61.000"), None);
+}
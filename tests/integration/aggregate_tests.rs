@@ -14,7 +14,7 @@ fn test_aggregate_by_location_groups_correctly() {
         create_measurement_at_location(location, base_time + Duration::minutes(10), -70.0),
     ];
 
-    let aggregates = aggregate_by_location(&measurements, 0.0001); // ~10m epsilon
+    let aggregates = aggregate_by_location(&measurements, 10.0); // 10 m tolerance
 
     assert_eq!(aggregates.len(), 1); // All at same location
     assert_eq!(aggregates[0].measurement_count, 3);
@@ -31,3 +31,71 @@ fn create_measurement_at_location(
 ) -> Measurement {
     Measurement::new(coord, timestamp, 2.45e9, power, 20e6, 18.0).unwrap()
 }
+
+#[test]
+fn test_aggregate_by_location_separates_distant_sites() {
+    // Two sites roughly 1.1 km apart, each with two nearby readings
+    let base_time = Utc::now();
+    let site_a = Coordinate::new(37.7749, -122.4194).unwrap();
+    let site_a_nearby = Coordinate::new(37.77495, -122.4194).unwrap(); // ~5.5 m north
+    let site_b = Coordinate::new(37.7849, -122.4194).unwrap();
+
+    let measurements = vec![
+        create_measurement_at_location(site_a, base_time, -60.0),
+        create_measurement_at_location(site_b, base_time, -80.0),
+        create_measurement_at_location(site_a_nearby, base_time, -62.0),
+        create_measurement_at_location(site_b, base_time, -82.0),
+    ];
+
+    let aggregates = aggregate_by_location(&measurements, 50.0);
+
+    assert_eq!(aggregates.len(), 2);
+    assert_eq!(aggregates[0].measurement_count, 2);
+    assert_eq!(aggregates[1].measurement_count, 2);
+    assert_eq!(aggregates[0].power.max, -60.0);
+    assert_eq!(aggregates[1].power.max, -80.0);
+}
+
+#[test]
+fn test_aggregate_reports_spread_time_window_and_frequencies() {
+    let base_time = Utc::now();
+    let location = Coordinate::new(51.5074, -0.1278).unwrap();
+
+    let measurements = vec![
+        Measurement::new(location, base_time + Duration::minutes(10), 146.52e6, -60.0, 12.5e3, 20.0).unwrap(),
+        Measurement::new(location, base_time, 433.92e6, -70.0, 12.5e3, 10.0).unwrap(),
+        Measurement::new(location, base_time + Duration::minutes(5), 146.52e6, -65.0, 12.5e3, 15.0).unwrap(),
+    ];
+
+    let aggregates = aggregate_by_location(&measurements, 10.0);
+
+    assert_eq!(aggregates.len(), 1);
+    let site = &aggregates[0];
+    assert_eq!(site.first_seen, base_time);
+    assert_eq!(site.last_seen, base_time + Duration::minutes(10));
+    assert_eq!(site.frequencies, vec![146.52e6, 433.92e6]);
+    // Population stddev of [-60, -70, -65]
+    assert!((site.power.stddev - 4.082).abs() < 0.01);
+    assert!((site.snr.stddev - 4.082).abs() < 0.01);
+}
+
+#[test]
+fn test_aggregate_centroid_across_the_antimeridian() {
+    // Two readings about 200 m apart either side of ±180° on Taveuni, Fiji
+    let base_time = Utc::now();
+    let east = Coordinate::new(-16.8, 179.999).unwrap();
+    let west = Coordinate::new(-16.8, -179.999).unwrap();
+    let measurements = vec![
+        create_measurement_at_location(east, base_time, -60.0),
+        create_measurement_at_location(west, base_time, -62.0),
+        create_measurement_at_location(west, base_time, -64.0),
+    ];
+
+    let aggregates = aggregate_by_location(&measurements, 500.0);
+
+    assert_eq!(aggregates.len(), 1);
+    let centroid = aggregates[0].location;
+    assert!((centroid.lat() + 16.8).abs() < 1e-9);
+    // One third of the way from 179.999° to -179.999° going east
+    assert!((centroid.lon() + 179.999_666_667).abs() < 1e-6, "{:?}", centroid);
+}
@@ -0,0 +1,80 @@
+//! Fixtures shared by the integration tests
+use std::sync::Once;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use diesel::prelude::*;
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
+use sdr_db::db::{CaptureQueue, SledLogStore};
+use sdr_db::{Coordinate, Frequency, LogDetails, NewLog, SignalMode};
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
+
+/// Server the Postgres tests run against; they are skipped when it is unset
+const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
+
+/// `year-month-day hour:minute:second` UTC, for fixture times
+pub fn utc_at(
+    year: i32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, second)
+        .unwrap()
+}
+
+/// 2025-10-01 12:00 UTC, the time fixture logs are recorded relative to
+pub fn base_time() -> DateTime<Utc> {
+    utc_at(2025, 10, 1, 12, 0, 0)
+}
+
+/// A valid entry `minutes` after [`base_time`]: W1AW on 146.52 MHz FM in New York
+///
+/// Adjust it with struct update syntax.
+pub fn new_log(minutes: i64) -> NewLog<'static> {
+    NewLog::new(
+        Frequency::from_mhz(146.52),
+        Coordinate::new(40.7128, -74.0060).unwrap(),
+        "W1AW",
+        SignalMode::FM,
+        "",
+        2.0,
+        Some(base_time() + Duration::minutes(minutes)),
+        LogDetails::default(),
+    )
+    .unwrap()
+}
+
+pub fn temporary_sled_store() -> SledLogStore {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    SledLogStore::from_db(db).unwrap()
+}
+
+pub fn temporary_queue() -> CaptureQueue {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    CaptureQueue::from_db(db).unwrap()
+}
+
+/// URL of the test database with every migration applied, None to skip the test
+fn test_database_url() -> Option<String> {
+    static MIGRATED: Once = Once::new();
+
+    let Ok(url) = std::env::var(TEST_DATABASE_URL) else {
+        eprintln!("{} is not set, skipping", TEST_DATABASE_URL);
+        return None;
+    };
+    MIGRATED.call_once(|| {
+        let mut conn = PgConnection::establish(&url).unwrap();
+        conn.run_pending_migrations(MIGRATIONS).unwrap();
+    });
+    Some(url)
+}
+
+/// Connection to the test database inside a transaction that is never committed
+pub fn test_connection() -> Option<PgConnection> {
+    let mut conn = PgConnection::establish(&test_database_url()?).unwrap();
+    conn.begin_test_transaction().unwrap();
+    Some(conn)
+}
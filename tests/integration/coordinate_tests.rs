@@ -19,3 +19,62 @@ fn test_coordinate_invalid_longitude() {
     assert!(Coordinate::new(0.0, 181.0).is_err());
     assert!(Coordinate::new(0.0, -181.0).is_err());
 }
+
+#[test]
+fn test_db_point_ewkb_round_trip() {
+    use sdr_db::DbPoint;
+
+    // SRID=4326;POINT(-122.4194 37.7749)
+    let point = DbPoint::from(Coordinate::new(37.7749, -122.4194).unwrap());
+    let decoded = DbPoint::from_ewkb(&point.to_ewkb()).unwrap();
+    assert_eq!(decoded, point);
+    assert_eq!(decoded.0.x(), -122.4194);
+    assert_eq!(decoded.0.y(), 37.7749);
+}
+
+#[test]
+fn test_db_point_decodes_postgis_output() {
+    use sdr_db::DbPoint;
+
+    // ST_AsEWKB('SRID=4326;POINT(1 2)'::geography)
+    let le: [u8; 25] = [
+        0x01, 0x01, 0x00, 0x00, 0x20, 0xe6, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xf0, 0x3f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+    ];
+    let point = DbPoint::from_ewkb(&le).unwrap();
+    assert_eq!((point.0.x(), point.0.y()), (1.0, 2.0));
+
+    // Big-endian plain WKB without an SRID
+    let be: [u8; 21] = [
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x3f, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(DbPoint::from_ewkb(&be).unwrap(), point);
+
+    // Truncated input is an error, not a panic
+    assert!(DbPoint::from_ewkb(&le[..20]).is_err());
+}
+
+#[test]
+fn test_grid_square_locators() {
+    use sdr_db::spatial::{grid_square, grid_square_center};
+
+    let london = Coordinate::new(51.5074, -0.1278).unwrap();
+    assert_eq!(grid_square(&london, 3), "IO91wm");
+    assert_eq!(grid_square(&london, 4).len(), 8);
+    let newington = Coordinate::new(41.7147, -72.7272).unwrap();
+    assert_eq!(grid_square(&newington, 2), "FN31");
+    // The north-east corner stays inside the last square
+    let corner = Coordinate::new(90.0, 180.0).unwrap();
+    assert_eq!(grid_square(&corner, 3), "RR99xx");
+
+    let center = grid_square_center("io91WM").unwrap();
+    assert!(center.distance_m(&london) < 5_000.0);
+    assert_eq!(grid_square(&center, 3), "IO91wm");
+    let field = grid_square_center("JJ").unwrap();
+    assert_eq!((field.lat(), field.lon()), (5.0, 10.0));
+
+    for bad in ["", "I", "IO9", "SA00", "IO9a", "IO91yy", "IO91wm12ab"] {
+        assert!(grid_square_center(bad).is_err(), "{}", bad);
+    }
+}
@@ -0,0 +1,487 @@
+//! Postgres-backed tests, run against the server at `TEST_DATABASE_URL`
+//!
+//! Each test works inside a transaction that is rolled back, so they leave the
+//! database as they found it.
+use chrono::Duration;
+use diesel::prelude::*;
+use sdr_db::db::filter::query_logs;
+use sdr_db::db::logs::{
+    delete_log, get_log, get_logs, get_logs_by_uuid, insert_log, update_log, update_logs,
+};
+use sdr_db::db::revisions::{log_history, restore_revision};
+use sdr_db::db::search::search_logs;
+use sdr_db::db::{
+    LogStore, MemoryLogStore, active_session, get_sessions, start_session, stop_session,
+    summarize_session,
+};
+use sdr_db::{
+    Band, DatabaseError, Error, Frequency, Log, LogChanges, LogFilter, NewLog, NewSession,
+    RevisionAction, SignalMode,
+};
+
+use super::common::{base_time, new_log, test_connection};
+
+#[test]
+fn test_query_logs_pages_through_postgres() {
+    let Some(conn) = &mut test_connection() else {
+        return;
+    };
+    let filter = LogFilter::new().callsign_like("DB1PAGE");
+    let mut created: Vec<Log> = (0..7)
+        .map(|minutes| {
+            // Pairs of logs share a time, so pages also split on the id
+            let entry = NewLog {
+                callsign: "DB1PAGE",
+                timestamp: base_time() + Duration::minutes(minutes / 2),
+                ..new_log(0)
+            };
+            insert_log(conn, &entry).unwrap()
+        })
+        .collect();
+    created.sort_by_key(|log| std::cmp::Reverse((log.timestamp, log.id)));
+
+    let mut paged = Vec::new();
+    let mut after = None;
+    let mut pages = 0;
+    loop {
+        let page = query_logs(conn, &filter, after, 3).unwrap();
+        pages += 1;
+        paged.extend(page.logs.iter().map(|log| log.id));
+        match page.next {
+            Some(next) => after = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(pages, 3);
+    assert_eq!(
+        paged,
+        created.iter().map(|log| log.id).collect::<Vec<i32>>()
+    );
+
+    // A negative limit reads as zero, as in memory
+    let empty = query_logs(conn, &filter, None, -5).unwrap();
+    assert!(empty.logs.is_empty());
+    assert_eq!(empty.next, None);
+    let mut memory = MemoryLogStore::from_logs(created);
+    let in_memory = memory.query(&filter, None, -5).unwrap();
+    assert!(in_memory.logs.is_empty());
+    assert_eq!(in_memory.next, None);
+    assert!(get_logs(conn, -5).unwrap().is_empty());
+}
+
+#[test]
+fn test_rows_with_unreadable_modes_still_load() {
+    let Some(conn) = &mut test_connection() else {
+        return;
+    };
+    let blank = insert_log(conn, &new_log(0)).unwrap();
+    let pocsag = insert_log(conn, &new_log(1)).unwrap();
+    diesel::sql_query("UPDATE logs SET mode = '' WHERE id = $1")
+        .bind::<diesel::sql_types::Integer, _>(blank.id)
+        .execute(conn)
+        .unwrap();
+    diesel::sql_query("UPDATE logs SET mode = 'pocsag' WHERE id = $1")
+        .bind::<diesel::sql_types::Integer, _>(pocsag.id)
+        .execute(conn)
+        .unwrap();
+
+    assert_eq!(
+        get_log(conn, blank.id).unwrap().mode,
+        SignalMode::Other(String::new())
+    );
+    let page = query_logs(conn, &LogFilter::new().callsign_like("W1AW"), None, 10).unwrap();
+    let modes: Vec<SignalMode> = page
+        .logs
+        .iter()
+        .filter(|log| [blank.id, pocsag.id].contains(&log.id))
+        .map(|log| log.mode.clone())
+        .collect();
+    assert_eq!(
+        modes,
+        vec![
+            SignalMode::Other("POCSAG".to_string()),
+            SignalMode::Other(String::new())
+        ]
+    );
+}
+
+#[test]
+fn test_get_logs_by_uuid_skips_deleted_logs() {
+    let Some(conn) = &mut test_connection() else {
+        return;
+    };
+    let first = insert_log(conn, &new_log(0)).unwrap();
+    let second = insert_log(conn, &new_log(1)).unwrap();
+    let deleted = insert_log(conn, &new_log(2)).unwrap();
+    delete_log(conn, deleted.id, "tester").unwrap();
+
+    let mut found = get_logs_by_uuid(
+        conn,
+        &[first.uuid, deleted.uuid, new_log(3).uuid, second.uuid],
+    )
+    .unwrap();
+    found.sort_by_key(|log| log.id);
+    assert_eq!(
+        found.iter().map(|log| log.uuid).collect::<Vec<_>>(),
+        vec![first.uuid, second.uuid]
+    );
+    assert!(get_logs_by_uuid(conn, &[]).unwrap().is_empty());
+}
+
+#[test]
+fn test_update_log_records_a_revision() {
+    let Some(conn) = &mut test_connection() else {
+        return;
+    };
+    let log = insert_log(conn, &new_log(0)).unwrap();
+    let changes = LogChanges {
+        comment: Some("repeater output".to_string()),
+        mode: Some(SignalMode::NFM),
+        ..Default::default()
+    };
+    let updated = update_log(conn, log.id, &changes, "tester").unwrap();
+    assert_eq!(updated.comment.as_deref(), Some("repeater output"));
+    assert_eq!(get_log(conn, log.id).unwrap().mode, SignalMode::NFM);
+    // Empty changes write nothing and are not recorded
+    update_log(conn, log.id, &LogChanges::default(), "tester").unwrap();
+
+    let history = log_history(conn, log.id).unwrap();
+    assert_eq!(history.len(), 1);
+    let revision = &history[0];
+    assert_eq!(revision.log_id, log.id);
+    assert_eq!(revision.action, RevisionAction::Update);
+    assert_eq!(revision.operator, "tester");
+    let old = revision.old_log().unwrap();
+    assert_eq!(old.comment.as_deref(), Some(""));
+    assert_eq!(old.mode, SignalMode::FM);
+    let new = revision.new_log().unwrap().unwrap();
+    assert_eq!(new.comment.as_deref(), Some("repeater output"));
+    assert_eq!(new.mode, SignalMode::NFM);
+}
+
+#[test]
+fn test_deleted_logs_drop_out_of_queries() {
+    let Some(conn) = &mut test_connection() else {
+        return;
+    };
+    let kept = insert_log(conn, &new_log(0)).unwrap();
+    let deleted = insert_log(conn, &new_log(1)).unwrap();
+    delete_log(conn, deleted.id, "tester").unwrap();
+
+    assert!(matches!(
+        get_log(conn, deleted.id),
+        Err(Error::Database(DatabaseError::NotFound(_)))
+    ));
+    let page = query_logs(conn, &LogFilter::new().callsign_like("W1AW"), None, 10).unwrap();
+    assert_eq!(
+        page.logs.iter().map(|log| log.id).collect::<Vec<i32>>(),
+        vec![kept.id]
+    );
+    assert!(matches!(
+        delete_log(conn, deleted.id, "tester"),
+        Err(Error::Database(DatabaseError::NotFound(_)))
+    ));
+    assert!(matches!(
+        update_log(conn, deleted.id, &LogChanges::default(), "tester"),
+        Err(Error::Database(DatabaseError::NotFound(_)))
+    ));
+
+    // The row is kept for its history
+    let still_stored: i64 = sdr_db::schema::logs::table
+        .filter(sdr_db::schema::logs::id.eq(deleted.id))
+        .count()
+        .get_result(conn)
+        .unwrap();
+    assert_eq!(still_stored, 1);
+}
+
+#[test]
+fn test_update_logs_applies_every_change_or_none() {
+    let Some(conn) = &mut test_connection() else {
+        return;
+    };
+    let first = insert_log(conn, &new_log(0)).unwrap();
+    let second = insert_log(conn, &new_log(1)).unwrap();
+    let comment = |text: &str| LogChanges {
+        comment: Some(text.to_string()),
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        update_logs(
+            conn,
+            &[(first.id, comment("moved")), (i32::MAX, comment("lost"))],
+            "tester"
+        ),
+        Err(Error::Database(DatabaseError::NotFound(_)))
+    ));
+    assert_eq!(get_log(conn, first.id).unwrap().comment, first.comment);
+    assert!(log_history(conn, first.id).unwrap().is_empty());
+
+    let updated = update_logs(
+        conn,
+        &[(first.id, comment("moved")), (second.id, comment("parked"))],
+        "tester",
+    )
+    .unwrap();
+    assert_eq!(updated.len(), 2);
+    assert_eq!(
+        get_log(conn, second.id).unwrap().comment.as_deref(),
+        Some("parked")
+    );
+    assert_eq!(log_history(conn, first.id).unwrap().len(), 1);
+}
+
+#[test]
+fn test_restore_revision_brings_back_earlier_values() {
+    let Some(conn) = &mut test_connection() else {
+        return;
+    };
+    let original = insert_log(conn, &new_log(0)).unwrap();
+    let changes = LogChanges {
+        callsign: Some("W1XYZ".to_string()),
+        comment: Some("misheard".to_string()),
+        tags: Some(vec!["retake".to_string()]),
+        ..Default::default()
+    };
+    update_log(conn, original.id, &changes, "tester").unwrap();
+    let edit = log_history(conn, original.id).unwrap().remove(0);
+
+    let restored = restore_revision(conn, edit.id, "reviewer").unwrap();
+    assert_eq!(restored.callsign, original.callsign);
+    assert_eq!(restored.comment, original.comment);
+    let stored = get_log(conn, original.id).unwrap();
+    assert_eq!(stored.callsign.as_deref(), Some("W1AW"));
+    assert!(stored.tags.is_empty());
+
+    // A delete can be undone too
+    delete_log(conn, original.id, "tester").unwrap();
+    let delete = log_history(conn, original.id).unwrap().pop().unwrap();
+    assert_eq!(delete.action, RevisionAction::Delete);
+    restore_revision(conn, delete.id, "reviewer").unwrap();
+    assert_eq!(get_log(conn, original.id).unwrap().id, original.id);
+
+    assert!(matches!(
+        restore_revision(conn, i32::MAX, "reviewer"),
+        Err(DatabaseError::NotFound(_))
+    ));
+}
+
+#[test]
+fn test_log_history_lists_every_change_oldest_first() {
+    let Some(conn) = &mut test_connection() else {
+        return;
+    };
+    let log = insert_log(conn, &new_log(0)).unwrap();
+    let other = insert_log(conn, &new_log(1)).unwrap();
+    assert!(log_history(conn, log.id).unwrap().is_empty());
+
+    let comment = |text: &str| LogChanges {
+        comment: Some(text.to_string()),
+        ..Default::default()
+    };
+    update_log(conn, log.id, &comment("first"), "tester").unwrap();
+    update_log(conn, other.id, &comment("elsewhere"), "tester").unwrap();
+    update_log(conn, log.id, &comment("second"), "tester").unwrap();
+    delete_log(conn, log.id, "cleanup").unwrap();
+    let first_edit = log_history(conn, log.id).unwrap()[0].id;
+    restore_revision(conn, first_edit, "reviewer").unwrap();
+
+    let history = log_history(conn, log.id).unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|revision| (revision.action, revision.operator.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (RevisionAction::Update, "tester"),
+            (RevisionAction::Update, "tester"),
+            (RevisionAction::Delete, "cleanup"),
+            (RevisionAction::Restore, "reviewer"),
+        ]
+    );
+    assert!(history.windows(2).all(|pair| pair[0].id < pair[1].id));
+    assert_eq!(history[2].new_values, None);
+    assert_eq!(
+        history[3].new_log().unwrap().unwrap().comment.as_deref(),
+        Some("")
+    );
+    assert!(log_history(conn, i32::MAX).unwrap().is_empty());
+}
+
+#[test]
+fn test_logs_attach_to_sessions_and_filter_by_them() {
+    let Some(conn) = &mut test_connection() else {
+        return;
+    };
+    let started_at = base_time() - Duration::hours(1);
+    let ridge = start_session(
+        conn,
+        &NewSession::new("Ridge survey", "tester", started_at, None, None).unwrap(),
+    )
+    .unwrap();
+    let valley = start_session(
+        conn,
+        &NewSession::new("Valley survey", "tester", started_at, None, None).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        active_session(conn, "tester").unwrap().unwrap().id,
+        valley.id
+    );
+
+    let first = insert_log(conn, &new_log(0).in_session(Some(ridge.id))).unwrap();
+    let second = insert_log(
+        conn,
+        &NewLog {
+            frequency: Frequency::from_mhz(433.92),
+            ..new_log(1).in_session(Some(ridge.id))
+        },
+    )
+    .unwrap();
+    let elsewhere = insert_log(conn, &new_log(2).in_session(Some(valley.id))).unwrap();
+    let loose = insert_log(conn, &new_log(3)).unwrap();
+    assert_eq!(first.session_id, Some(ridge.id));
+    assert_eq!(loose.session_id, None);
+
+    // Attach a log recorded outside any session afterwards
+    let attach = LogChanges {
+        session_id: Some(Some(ridge.id)),
+        ..Default::default()
+    };
+    update_log(conn, loose.id, &attach, "tester").unwrap();
+
+    let in_session = |conn: &mut PgConnection, id| -> Vec<i32> {
+        let page = query_logs(conn, &LogFilter::new().session(id), None, 10).unwrap();
+        page.logs.iter().map(|log| log.id).collect()
+    };
+    assert_eq!(
+        in_session(conn, ridge.id),
+        vec![loose.id, second.id, first.id]
+    );
+    assert_eq!(in_session(conn, valley.id), vec![elsewhere.id]);
+
+    delete_log(conn, first.id, "tester").unwrap();
+    let summary = summarize_session(conn, ridge.id, base_time()).unwrap();
+    assert_eq!(summary.log_count, 2);
+    assert_eq!(summary.bands, vec![Band::VHF, Band::UHF]);
+
+    let stopped = stop_session(conn, valley.id).unwrap();
+    assert!(stopped.ended_at.is_some());
+    assert_eq!(
+        active_session(conn, "tester").unwrap().unwrap().id,
+        ridge.id
+    );
+    // Unknown ids are left out of a lookup, as when naming export folders
+    let mut named: Vec<(i32, String)> = get_sessions(conn, &[valley.id, ridge.id, i32::MAX])
+        .unwrap()
+        .into_iter()
+        .map(|session| (session.id, session.name))
+        .collect();
+    named.sort();
+    let mut expected = vec![(ridge.id, ridge.name), (valley.id, valley.name)];
+    expected.sort();
+    assert_eq!(named, expected);
+    // A session that does not exist is refused; this aborts the transaction, so last
+    assert!(matches!(
+        insert_log(conn, &new_log(4).in_session(Some(i32::MAX))),
+        Err(Error::Database(DatabaseError::QueryError(_)))
+    ));
+}
+
+#[test]
+fn test_query_logs_requires_every_tag() {
+    let Some(conn) = &mut test_connection() else {
+        return;
+    };
+    let mut insert = |minutes, tags: &[&str]| {
+        let entry = new_log(minutes).with_tags(tags).unwrap();
+        insert_log(conn, &entry).unwrap()
+    };
+    let both = insert(0, &["night", "repeater"]);
+    let night = insert(1, &["night"]);
+    let repeater = insert(2, &["repeater", "beacon"]);
+    let untagged = insert(3, &[]);
+    let created = vec![both, night, repeater, untagged];
+
+    let mut memory = MemoryLogStore::from_logs(created.clone());
+    let mut matching = |filter: LogFilter| -> Vec<i32> {
+        let page = query_logs(conn, &filter, None, 10).unwrap();
+        let ids: Vec<i32> = page.logs.iter().map(|log| log.id).collect();
+        // Memory numbers the same logs from 1, in the order created
+        let in_memory: Vec<i32> = memory
+            .query(&filter, None, 10)
+            .unwrap()
+            .logs
+            .iter()
+            .map(|log| created[log.id as usize - 1].id)
+            .collect();
+        assert_eq!(ids, in_memory, "{:?}", filter);
+        ids
+    };
+
+    assert_eq!(
+        matching(LogFilter::new().tag("night")),
+        vec![created[1].id, created[0].id]
+    );
+    // Tags are normalized as when they were stored
+    assert_eq!(
+        matching(LogFilter::new().tag(" Repeater ")),
+        vec![created[2].id, created[0].id]
+    );
+    assert_eq!(
+        matching(LogFilter::new().tags(["night", "repeater"])),
+        vec![created[0].id]
+    );
+    assert_eq!(
+        matching(LogFilter::new().tags(["night", "repeater", "beacon"])),
+        Vec::<i32>::new()
+    );
+    assert_eq!(matching(LogFilter::new().tag("absent")), Vec::<i32>::new());
+}
+
+#[test]
+fn test_search_logs_ignores_tsquery_punctuation() {
+    let Some(conn) = &mut test_connection() else {
+        return;
+    };
+    let mut insert = |minutes, callsign, comment| {
+        let entry = NewLog {
+            callsign,
+            comment,
+            ..new_log(minutes)
+        };
+        insert_log(conn, &entry).unwrap()
+    };
+    let pager = insert(0, "K1PAG", "pager bursts: POCSAG & FLEX");
+    let repeater = insert(1, "K1RPT", "repeater | net control");
+    let created = vec![pager.clone(), repeater.clone()];
+
+    let mut memory = MemoryLogStore::from_logs(created.clone());
+    let mut found = |query: &str| -> Vec<i32> {
+        let ids: Vec<i32> = search_logs(conn, query, 10)
+            .unwrap()
+            .iter()
+            .map(|hit| hit.log.id)
+            .collect();
+        let in_memory: Vec<i32> = memory
+            .search(query, 10)
+            .unwrap()
+            .iter()
+            .map(|hit| created[hit.log.id as usize - 1].id)
+            .collect();
+        assert_eq!(ids, in_memory, "{:?}", query);
+        ids
+    };
+
+    // Operators and quotes in the query are not passed on as tsquery syntax
+    assert_eq!(found("pocsag & flex"), vec![pager.id]);
+    assert_eq!(found("pocsag | net"), Vec::<i32>::new());
+    assert_eq!(found("bursts:"), vec![pager.id]);
+    assert_eq!(found("k1rpt:*"), vec![repeater.id]);
+    assert_eq!(found("!repeater"), vec![repeater.id]);
+    assert_eq!(found("'net' (control)"), vec![repeater.id]);
+    assert_eq!(found("&|:!()'\\"), Vec::<i32>::new());
+    assert_eq!(found("repeater"), vec![repeater.id]);
+    assert!(search_logs(conn, "repeater", -1).unwrap().is_empty());
+}
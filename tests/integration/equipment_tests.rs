@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use diesel::PgConnection;
+use diesel::r2d2::ConnectionManager;
+use sdr_db::db::{LogStore, MemoryLogStore, QueuedLog, QueuedLogStore, pool};
+use sdr_db::tabs::spectrum_view::{SpectrumSource, SpectrumViewerState};
+use sdr_db::{DatabaseError, LogChanges, NewEquipmentProfile, NewLog, ValidationError};
+
+use super::common::temporary_queue;
+
+fn new_log() -> NewLog<'static> {
+    NewLog {
+        power_dbm: Some(-71.5),
+        ..super::common::new_log(0)
+    }
+}
+
+fn discone() -> NewEquipmentProfile {
+    NewEquipmentProfile {
+        serial: Some("0000000000000000a06063c8234e925f".to_string()),
+        antenna: Some("Discone, 10 m RG-58".to_string()),
+        cable_loss_db: 1.8,
+        lna_gain_db: 16,
+        vga_gain_db: 20,
+        calibration_offset_ppm: -1.5,
+        ..NewEquipmentProfile::new("HackRF One")
+    }
+}
+
+#[test]
+fn test_equipment_profile_validation() {
+    assert!(discone().validate().is_ok());
+
+    let invalid = [
+        NewEquipmentProfile::new(" "),
+        NewEquipmentProfile::new("x".repeat(101)),
+        NewEquipmentProfile {
+            antenna: Some("y".repeat(201)),
+            ..discone()
+        },
+        NewEquipmentProfile {
+            cable_loss_db: -0.5,
+            ..discone()
+        },
+        NewEquipmentProfile {
+            vga_gain_db: -2,
+            ..discone()
+        },
+        NewEquipmentProfile {
+            calibration_offset_ppm: f32::NAN,
+            ..discone()
+        },
+    ];
+    for profile in invalid {
+        assert!(
+            matches!(
+                profile.validate(),
+                Err(ValidationError::InvalidEquipmentProfile(_))
+            ),
+            "{:?} should be rejected",
+            profile
+        );
+    }
+}
+
+#[test]
+fn test_spectrum_viewer_snapshots_gains_once_it_has_streamed() {
+    let mut viewer = SpectrumViewerState::default();
+    viewer.increase_lna_gain();
+    viewer.increase_vga_gain();
+    assert_eq!(viewer.equipment_snapshot(), None);
+
+    viewer.start_streaming();
+    let snapshot = viewer.equipment_snapshot().unwrap();
+    assert_eq!(snapshot.sdr_model, "HackRF One");
+    assert_eq!(snapshot.lna_gain_db, 8);
+    assert_eq!(snapshot.vga_gain_db, 2);
+    assert!(snapshot.validate().is_ok());
+
+    viewer.toggle_source();
+    assert_eq!(viewer.source, SpectrumSource::File);
+    let snapshot = viewer.equipment_snapshot().unwrap();
+    assert_eq!(snapshot.sdr_model, "File playback");
+    assert!(snapshot.validate().is_ok());
+}
+
+#[test]
+fn test_log_created_after_leaving_the_spectrum_viewer_keeps_its_equipment() {
+    let mut viewer = SpectrumViewerState::default();
+    viewer.start_streaming();
+    viewer.increase_lna_gain();
+    // Logs are only submitted from Create Log, so the viewer has stopped by then
+    viewer.stop_streaming();
+    viewer.increase_vga_gain();
+
+    let entry = QueuedLog::from(&new_log()).with_equipment(viewer.equipment_snapshot());
+    let equipment = entry.equipment.unwrap();
+    assert_eq!(equipment.sdr_model, "HackRF One");
+    assert_eq!((equipment.lna_gain_db, equipment.vga_gain_db), (8, 0));
+}
+
+#[test]
+fn test_queued_log_keeps_equipment_snapshot() {
+    let queue = temporary_queue();
+    let entry = QueuedLog::from(&new_log()).with_equipment(Some(discone()));
+    queue.enqueue_entry(entry.clone()).unwrap();
+
+    let pending = queue.pending().unwrap();
+    assert_eq!(pending, vec![entry]);
+    assert_eq!(pending[0].equipment, Some(discone()));
+
+    // Entries queued before profiles existed have neither key
+    let mut json = serde_json::to_value(&pending[0]).unwrap();
+    let object = json.as_object_mut().unwrap();
+    object.remove("equipment");
+    object.remove("equipment_profile_id");
+    let old: QueuedLog = serde_json::from_value(json).unwrap();
+    assert_eq!(old.equipment, None);
+    assert_eq!(old.equipment_profile_id, None);
+}
+
+#[test]
+fn test_enqueue_rejects_invalid_equipment() {
+    let queue = temporary_queue();
+    let equipment = NewEquipmentProfile {
+        lna_gain_db: -8,
+        ..discone()
+    };
+    let entry = QueuedLog::from(&new_log()).with_equipment(Some(equipment));
+    assert!(matches!(
+        queue.enqueue_entry(entry),
+        Err(DatabaseError::InvalidData(
+            ValidationError::InvalidEquipmentProfile(_)
+        ))
+    ));
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn test_queued_store_queues_equipment_while_offline() {
+    let queue = temporary_queue();
+    let unreachable = pool::pool_builder()
+        .connection_timeout(Duration::from_millis(200))
+        .build_unchecked(ConnectionManager::<PgConnection>::new(
+            "postgres://127.0.0.1:1/sdr_db",
+        ));
+    let mut store = QueuedLogStore::with_queue(unreachable, queue.clone(), Duration::from_secs(60));
+
+    let created = store.create_with_equipment(&new_log(), &discone()).unwrap();
+    assert_eq!(created.id, 0);
+    assert_eq!(queue.pending().unwrap()[0].equipment, Some(discone()));
+}
+
+#[test]
+fn test_stores_without_profiles_keep_the_log() {
+    let mut store = MemoryLogStore::new();
+    let created = store.create_with_equipment(&new_log(), &discone()).unwrap();
+    assert_eq!(created.equipment_profile_id, None);
+
+    let changes = LogChanges {
+        equipment_profile_id: Some(Some(3)),
+        ..Default::default()
+    };
+    let updated = store.update(created.id, &changes).unwrap();
+    assert_eq!(updated.equipment_profile_id, Some(3));
+}
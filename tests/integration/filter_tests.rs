@@ -0,0 +1,219 @@
+use chrono::Duration;
+use sdr_db::tabs::view_logs::ViewLogsState;
+use sdr_db::{
+    BoundingBox, Coordinate, Frequency, Log, LogCursor, LogFilter, LogPage, LogTimeZone, LogUuid,
+    SignalMode, ValidationError,
+};
+
+use super::common::base_time;
+
+fn log(id: i32, mhz: f64, lat: f64, lon: f64, callsign: &str, mode: SignalMode) -> Log {
+    let position = Coordinate::new(lat, lon).unwrap();
+    Log {
+        id,
+        frequency: Frequency::from_mhz(mhz),
+        xcoord: lat as f32,
+        ycoord: lon as f32,
+        callsign: Some(callsign.to_string()),
+        mode,
+        comment: Some(format!("Repeater net #{}", id)),
+        timestamp: base_time() + Duration::minutes(id as i64),
+        recording_duration: 10.0,
+        location: Some(position.into()),
+        power_dbm: None,
+        snr_db: None,
+        bandwidth_hz: None,
+        altitude_m: None,
+        position_accuracy_m: None,
+        heading_deg: None,
+        speed_mps: None,
+        uuid: LogUuid::new_v7(),
+        session_id: None,
+        equipment_profile_id: None,
+        tags: Vec::new(),
+    }
+}
+
+fn sample_logs() -> Vec<Log> {
+    vec![
+        log(1, 146.52, 40.7128, -74.0060, "W1AW", SignalMode::FM),
+        log(2, 7.074, 40.7580, -73.9855, "K2ABC", SignalMode::FT8),
+        log(3, 446.0, 51.5074, -0.1278, "G4XYZ", SignalMode::DMR),
+        log(4, 144.39, 40.6892, -74.0445, "W1XYZ", SignalMode::APRS),
+    ]
+}
+
+fn matching_ids(filter: &LogFilter) -> Vec<i32> {
+    sample_logs()
+        .iter()
+        .filter(|l| filter.matches(l))
+        .map(|l| l.id)
+        .collect()
+}
+
+#[test]
+fn test_empty_filter_matches_everything() {
+    let filter = LogFilter::new();
+    assert!(filter.is_empty());
+    assert_eq!(matching_ids(&filter), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn test_frequency_and_time_filters() {
+    let two_metre =
+        LogFilter::new().frequency_range(Frequency::from_mhz(144.0), Frequency::from_mhz(148.0));
+    assert_eq!(matching_ids(&two_metre), vec![1, 4]);
+
+    // The window is half-open, so log 4 at exactly `end` is excluded
+    let window = LogFilter::new().time_window(
+        base_time() + Duration::minutes(2),
+        base_time() + Duration::minutes(4),
+    );
+    assert_eq!(matching_ids(&window), vec![2, 3]);
+}
+
+#[test]
+fn test_mode_set_filter() {
+    let filter = LogFilter::new().modes([SignalMode::FT8, SignalMode::DMR, SignalMode::FT8]);
+    assert_eq!(matching_ids(&filter), vec![2, 3]);
+}
+
+#[test]
+fn test_callsign_glob_and_comment_text() {
+    assert_eq!(
+        matching_ids(&LogFilter::new().callsign_like("w1*")),
+        vec![1, 4]
+    );
+    assert_eq!(
+        matching_ids(&LogFilter::new().callsign_like("*XYZ")),
+        vec![3, 4]
+    );
+    assert_eq!(
+        matching_ids(&LogFilter::new().callsign_like("K?ABC")),
+        vec![2]
+    );
+    assert_eq!(
+        matching_ids(&LogFilter::new().callsign_like("W1")),
+        Vec::<i32>::new()
+    );
+
+    assert_eq!(
+        matching_ids(&LogFilter::new().comment_contains("NET #3")),
+        vec![3]
+    );
+}
+
+#[test]
+fn test_spatial_filters() {
+    let nyc = BoundingBox::new(
+        Coordinate::new(40.5, -74.3).unwrap(),
+        Coordinate::new(40.9, -73.7).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        matching_ids(&LogFilter::new().within_bbox(nyc)),
+        vec![1, 2, 4]
+    );
+
+    let center = Coordinate::new(40.7128, -74.0060).unwrap();
+    let nearby = LogFilter::new().within_radius(center, 6_000.0);
+    assert_eq!(matching_ids(&nearby), vec![1, 2, 4]);
+    let close = LogFilter::new().within_radius(center, 1_000.0);
+    assert_eq!(matching_ids(&close), vec![1]);
+}
+
+#[test]
+fn test_criteria_combine() {
+    let filter = LogFilter::new().callsign_like("W1*").mode(SignalMode::APRS);
+    assert_eq!(matching_ids(&filter), vec![4]);
+}
+
+#[test]
+fn test_without_tag_matches_the_normalized_tag() {
+    let filter = LogFilter::new().tag("Weak").tag("mobile");
+    assert_eq!(filter.required_tags(), ["weak", "mobile"]);
+    assert_eq!(filter.without_tag("Weak").required_tags(), ["mobile"]);
+}
+
+#[test]
+fn test_filter_terms_match_builder_criteria() {
+    let utc = LogTimeZone::utc();
+    let terms = |terms: &[&str]| LogFilter::new().terms(terms, utc).unwrap();
+
+    assert_eq!(
+        terms(&["mode=ft8,dmr"]),
+        LogFilter::new().modes([SignalMode::FT8, SignalMode::DMR])
+    );
+    assert_eq!(
+        terms(&["freq=144..148"]),
+        LogFilter::new().frequency_range(Frequency::from_mhz(144.0), Frequency::from_mhz(148.0))
+    );
+    assert_eq!(
+        terms(&["freq=400M.."]),
+        LogFilter::new().min_frequency(Frequency::from_mhz(400.0))
+    );
+    assert_eq!(
+        terms(&["since=2025-10-01 12:02", "until=2025-10-01T12:04:00Z"]),
+        LogFilter::new().time_window(
+            base_time() + Duration::minutes(2),
+            base_time() + Duration::minutes(4),
+        )
+    );
+    assert_eq!(
+        terms(&["callsign=W1*", "tag=Weak", "session=3"]),
+        LogFilter::new().callsign_like("W1*").tag("weak").session(3)
+    );
+    assert_eq!(matching_ids(&terms(&["near=40.7128,-74.0060,1"])), vec![1]);
+    assert_eq!(
+        matching_ids(&terms(&["bbox=40.5,-74.3,40.9,-73.7", "comment=net #4"])),
+        vec![4]
+    );
+}
+
+#[test]
+fn test_invalid_filter_terms_are_rejected() {
+    let utc = LogTimeZone::utc();
+    for term in ["mode", "colour=red", "session=", "near=1,2", "freq=144"] {
+        assert_eq!(
+            LogFilter::new().term(term, utc),
+            Err(ValidationError::InvalidFilter(term.to_string())),
+            "{}",
+            term
+        );
+    }
+    assert!(matches!(
+        LogFilter::new().term("since=yesterday", utc),
+        Err(ValidationError::InvalidTimestamp(_))
+    ));
+    assert!(matches!(
+        LogFilter::new().term("near=91,0,5", utc),
+        Err(ValidationError::InvalidLatitude(_))
+    ));
+}
+
+#[test]
+fn test_view_logs_state_pages() {
+    let mut logs = sample_logs();
+    let second_page = logs.split_off(2);
+    let cursor = LogCursor::from(&logs[1]);
+
+    let mut state = ViewLogsState::default();
+    state.load_first_page(LogPage {
+        logs,
+        next: Some(cursor),
+    });
+    assert!(state.loaded);
+    assert!(!state.needs_next_page());
+
+    state.select_last();
+    assert!(state.needs_next_page());
+
+    state.append_page(LogPage {
+        logs: second_page,
+        next: None,
+    });
+    assert_eq!(state.logs.len(), 4);
+    assert_eq!(state.selected_index, 1);
+    state.select_last();
+    assert!(!state.needs_next_page());
+}
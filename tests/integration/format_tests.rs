@@ -0,0 +1,239 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use sdr_db::db::{LogStore, MemoryLogStore, merge_logs};
+use sdr_db::{
+    Band, Coordinate, Frequency, Log, LogFormat, LogStats, LogTimeZone, LogUuid, SignalMode,
+    ValidationError,
+};
+
+use super::common::utc_at;
+
+fn base_time() -> DateTime<Utc> {
+    utc_at(2025, 11, 2, 18, 30, 0)
+}
+
+fn log(id: i32, mhz: f64, callsign: &str, mode: SignalMode, tags: &[&str]) -> Log {
+    let position = Coordinate::new(51.5074, -0.1278).unwrap();
+    Log {
+        id,
+        frequency: Frequency::from_mhz(mhz),
+        xcoord: position.lat() as f32,
+        ycoord: position.lon() as f32,
+        callsign: Some(callsign.to_string()),
+        mode,
+        comment: Some(format!("Heard, \"clearly\"\nat #{}", id)),
+        timestamp: base_time() + chrono::Duration::minutes(id as i64),
+        recording_duration: 12.5,
+        location: Some(position.into()),
+        power_dbm: Some(-71.5),
+        snr_db: None,
+        bandwidth_hz: Some(Frequency::from_khz(12.5)),
+        altitude_m: Some(35.0),
+        position_accuracy_m: None,
+        heading_deg: None,
+        speed_mps: None,
+        uuid: LogUuid::new_v7(),
+        session_id: Some(4),
+        equipment_profile_id: None,
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+    }
+}
+
+fn sample_logs() -> Vec<Log> {
+    vec![
+        log(1, 145.5, "G4XYZ", SignalMode::FM, &["net", "weak"]),
+        log(2, 7.074, "", SignalMode::FT8, &[]),
+        log(3, 446.00625, "m0abc", SignalMode::DMR, &["weak"]),
+    ]
+}
+
+fn write(format: LogFormat, logs: &[Log]) -> Vec<u8> {
+    let mut out = Vec::new();
+    format.write_logs(logs, &mut out).unwrap();
+    out
+}
+
+fn assert_same_logs(read: &[Log], written: &[Log]) {
+    assert_eq!(read.len(), written.len());
+    for (read, written) in read.iter().zip(written) {
+        assert_eq!(read.id, written.id);
+        assert_eq!(read.uuid, written.uuid);
+        assert_eq!(
+            sdr_db::db::merge::differing_fields(read, written),
+            Vec::<&str>::new()
+        );
+        assert_eq!(read.session_id, written.session_id);
+    }
+}
+
+#[test]
+fn test_csv_round_trip() {
+    let logs = sample_logs();
+    let csv = write(LogFormat::Csv, &logs);
+    let text = String::from_utf8(csv.clone()).unwrap();
+    assert!(text.starts_with("id,uuid,timestamp,frequency_hz,"));
+    assert!(text.contains("\"Heard, \"\"clearly\"\"\nat #1\""));
+
+    let read = LogFormat::Csv.read_logs(&csv, LogTimeZone::utc()).unwrap();
+    assert_same_logs(&read, &logs);
+}
+
+#[test]
+fn test_json_round_trip() {
+    let logs = sample_logs();
+    let json = write(LogFormat::Json, &logs);
+    let read = LogFormat::Json
+        .read_logs(&json, LogTimeZone::utc())
+        .unwrap();
+    assert_same_logs(&read, &logs);
+}
+
+#[test]
+fn test_csv_from_other_tools() {
+    // Columns in another order, a subset of them, and times without an offset
+    let csv = "Mode,Latitude,Longitude,Timestamp,Frequency_Hz,Callsign\r\n\
+               usb,40.7128,-74.006,2025-11-02 09:15,14.074M,K2ABC\r\n\
+               \r\n\
+               CW,40.7,-74.0,2025-11-02T10:00:00Z,7030000,\r\n";
+    let zone: LogTimeZone = "-05:00".parse().unwrap();
+    let logs = LogFormat::Csv.read_logs(csv.as_bytes(), zone).unwrap();
+
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].mode, SignalMode::USB);
+    assert_eq!(logs[0].frequency, Frequency::from_khz(14_074.0));
+    assert_eq!(logs[0].callsign.as_deref(), Some("K2ABC"));
+    assert_eq!(logs[0].timestamp, utc_at(2025, 11, 2, 14, 15, 0));
+    assert_eq!(logs[1].callsign, None);
+    assert_eq!(logs[1].recording_duration, 0.0);
+    // Generated uuids carry the time of the log
+    assert_eq!(
+        logs[1].uuid.timestamp_ms(),
+        logs[1].timestamp.timestamp_millis() as u64
+    );
+}
+
+#[test]
+fn test_bad_csv_reports_the_line() {
+    let utc = LogTimeZone::utc();
+    assert_eq!(
+        LogFormat::Csv
+            .read_logs(b"timestamp,latitude,longitude,mode\n", utc)
+            .unwrap_err(),
+        ValidationError::InvalidRecord {
+            line: 1,
+            reason: "missing column \"frequency_hz\"".to_string(),
+        }
+    );
+
+    let csv = "timestamp,frequency_hz,latitude,longitude,mode,comment\n\
+               2025-11-02T10:00:00Z,7030000,40.7,-74.0,CW,\"multi\nline\"\n\
+               2025-11-02T10:00:00Z,7030000,north,-74.0,CW,\n";
+    assert_eq!(
+        LogFormat::Csv.read_logs(csv.as_bytes(), utc).unwrap_err(),
+        ValidationError::InvalidRecord {
+            line: 4,
+            reason: "latitude \"north\" is not a number".to_string(),
+        }
+    );
+
+    assert!(matches!(
+        LogFormat::Csv.read_logs(b"mode\n\"FM\n", utc),
+        Err(ValidationError::InvalidRecord { line: 2, .. })
+    ));
+    assert!(matches!(
+        LogFormat::Table.read_logs(b"", utc),
+        Err(ValidationError::UnsupportedFormat(_))
+    ));
+}
+
+#[test]
+fn test_table_output() {
+    let table = String::from_utf8(write(LogFormat::Table, &sample_logs())).unwrap();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("ID  TIME (UTC)"));
+    // Comments stay on their row
+    assert!(lines[1].ends_with("Heard, \"clearly\" at #1"));
+    assert!(lines[3].contains("M0ABC"));
+
+    let mut detail = Vec::new();
+    LogFormat::Table
+        .write_log(&sample_logs()[0], &mut detail)
+        .unwrap();
+    let detail = String::from_utf8(detail).unwrap();
+    assert!(detail.contains("power:     -71.5 dBm"));
+    assert!(detail.contains("tags:      net, weak"));
+}
+
+#[test]
+fn test_format_names_and_extensions() {
+    assert_eq!("JSON".parse::<LogFormat>(), Ok(LogFormat::Json));
+    assert!("xls".parse::<LogFormat>().is_err());
+    assert_eq!(
+        LogFormat::from_path(Path::new("export/logs.CSV")),
+        Some(LogFormat::Csv)
+    );
+    assert_eq!(LogFormat::from_path(Path::new("logs")), None);
+}
+
+#[test]
+fn test_import_skips_logs_already_stored() {
+    let logs = sample_logs();
+    let csv = write(LogFormat::Csv, &logs);
+    let mut target = MemoryLogStore::new();
+
+    for expected_imported in [3, 0] {
+        let read = LogFormat::Csv.read_logs(&csv, LogTimeZone::utc()).unwrap();
+        let report = merge_logs(&mut target, &mut MemoryLogStore::from_logs(read)).unwrap();
+        assert_eq!(report.imported, expected_imported);
+        assert_eq!(report.duplicates, 3 - expected_imported);
+    }
+    assert_eq!(target.len(), 3);
+    // Session ids belong to the exporting database
+    assert!(
+        target
+            .stream_all()
+            .unwrap()
+            .all(|log| log.unwrap().session_id.is_none())
+    );
+}
+
+#[test]
+fn test_import_without_uuids_skips_logs_already_stored() {
+    let csv = "timestamp,frequency_hz,latitude,longitude,mode,callsign\n\
+               2025-11-02T10:00:00Z,7030000,40.7,-74.0,CW,K2ABC\n\
+               2025-11-02T10:05:00Z,7030000,40.7,-74.0,CW,K2ABC\n\
+               2025-11-02T10:05:00Z,7030000,40.7,-74.0,USB,K2ABC\n";
+    let mut target = MemoryLogStore::new();
+
+    for expected_imported in [3, 0] {
+        let read = LogFormat::Csv
+            .read_logs(csv.as_bytes(), LogTimeZone::utc())
+            .unwrap();
+        let report = merge_logs(&mut target, &mut MemoryLogStore::from_logs(read)).unwrap();
+        assert_eq!(report.imported, expected_imported);
+    }
+    assert_eq!(target.len(), 3);
+}
+
+#[test]
+fn test_log_stats() {
+    let stats = LogStats::from_logs(&sample_logs());
+    assert_eq!(stats.log_count, 3);
+    assert_eq!(stats.callsign_count, 2);
+    assert_eq!(
+        stats.first,
+        Some(base_time() + chrono::Duration::minutes(1))
+    );
+    assert_eq!(stats.last, Some(base_time() + chrono::Duration::minutes(3)));
+    assert_eq!(stats.by_mode.get("FT8"), Some(&1));
+    assert_eq!(
+        stats.by_band.iter().collect::<Vec<_>>(),
+        vec![(&Band::HF, &1), (&Band::VHF, &1), (&Band::UHF, &1)]
+    );
+    assert_eq!(stats.by_tag.get("weak"), Some(&2));
+    assert!(stats.to_string().starts_with("3 logs from 2 callsigns"));
+
+    assert_eq!(LogStats::from_logs(&[]), LogStats::default());
+}
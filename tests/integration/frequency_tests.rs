@@ -0,0 +1,67 @@
+use sdr_db::error::ValidationError;
+use sdr_db::{Frequency, FrequencyUnit};
+
+#[test]
+fn test_parse_with_unit_suffixes() {
+    assert_eq!("146.52M".parse(), Ok(Frequency::from_hz(146_520_000)));
+    assert_eq!("7.074 MHz".parse(), Ok(Frequency::from_hz(7_074_000)));
+    assert_eq!("433920k".parse(), Ok(Frequency::from_hz(433_920_000)));
+    assert_eq!("2.45e9".parse(), Ok(Frequency::from_hz(2_450_000_000)));
+    assert_eq!("1.2e3 kHz".parse(), Ok(Frequency::from_hz(1_200_000)));
+    assert_eq!("5.8 ghz".parse(), Ok(Frequency::from_hz(5_800_000_000)));
+    assert_eq!("  1000 Hz ".parse(), Ok(Frequency::from_hz(1_000)));
+}
+
+#[test]
+fn test_parse_keeps_1hz_resolution_at_ghz() {
+    assert_eq!(
+        "2.400000001G".parse(),
+        Ok(Frequency::from_hz(2_400_000_001))
+    );
+}
+
+#[test]
+fn test_parse_default_unit_applies_to_bare_numbers() {
+    assert_eq!(
+        Frequency::parse_with_default_unit("146.52", FrequencyUnit::MHz),
+        Ok(Frequency::from_hz(146_520_000))
+    );
+    assert_eq!(
+        Frequency::parse_with_default_unit("433920k", FrequencyUnit::MHz),
+        Ok(Frequency::from_hz(433_920_000))
+    );
+}
+
+#[test]
+fn test_parse_rejects_garbage_and_negative_values() {
+    assert!(matches!(
+        "146.52 furlongs".parse::<Frequency>(),
+        Err(ValidationError::InvalidFrequencyInput(_))
+    ));
+    assert!(matches!(
+        "".parse::<Frequency>(),
+        Err(ValidationError::InvalidFrequencyInput(_))
+    ));
+    assert!(matches!(
+        "-5M".parse::<Frequency>(),
+        Err(ValidationError::InvalidFrequency(_))
+    ));
+}
+
+#[test]
+fn test_display_picks_unit_automatically() {
+    assert_eq!(Frequency::from_hz(146_520_000).to_string(), "146.52 MHz");
+    assert_eq!(Frequency::from_hz(7_074_000).to_string(), "7.074 MHz");
+    assert_eq!(Frequency::from_hz(2_400_000_001).to_string(), "2.400000001 GHz");
+    assert_eq!(Frequency::from_hz(433_920).to_string(), "433.92 kHz");
+    assert_eq!(Frequency::from_hz(50).to_string(), "50 Hz");
+    assert_eq!(format!("{:.1}", Frequency::from_hz(162_550_000)), "162.6 MHz");
+}
+
+#[test]
+fn test_display_round_trips_through_parse() {
+    for hz in [1, 999, 1_000, 14_074_000, 433_920_000, 2_400_000_001] {
+        let freq = Frequency::from_hz(hz);
+        assert_eq!(freq.to_string().parse(), Ok(freq));
+    }
+}
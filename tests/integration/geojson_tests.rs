@@ -0,0 +1,302 @@
+use chrono::{DateTime, TimeZone, Utc};
+use sdr_db::formats::geojson::{
+    FeatureWriter, read_layer, write_aggregates, write_layer, write_logs,
+};
+use sdr_db::{
+    Coordinate, Frequency, Layer, Log, LogFilter, LogFormat, LogTimeZone, LogUuid, Measurement,
+    SignalMode, ValidationError,
+};
+use serde_json::Value;
+
+use super::common::utc_at;
+
+fn base_time() -> DateTime<Utc> {
+    utc_at(2025, 11, 8, 21, 4, 30)
+}
+
+fn log(id: i32, lat: f64, lon: f64, mode: SignalMode) -> Log {
+    let position = Coordinate::new(lat, lon).unwrap();
+    Log {
+        id,
+        frequency: Frequency::from_mhz(146.52 + id as f64 * 0.025),
+        xcoord: position.lat() as f32,
+        ycoord: position.lon() as f32,
+        callsign: Some(format!("VK2AB{}", id)),
+        mode,
+        comment: Some(format!("\"quoted\" log {}", id)),
+        timestamp: base_time() + chrono::Duration::minutes(id as i64),
+        recording_duration: 1.5,
+        location: Some(position.into()),
+        power_dbm: None,
+        snr_db: None,
+        bandwidth_hz: None,
+        altitude_m: None,
+        position_accuracy_m: None,
+        heading_deg: None,
+        speed_mps: None,
+        uuid: LogUuid::new_v7(),
+        session_id: None,
+        equipment_profile_id: None,
+        tags: Vec::new(),
+    }
+}
+
+fn sample_logs() -> Vec<Log> {
+    let mut logs = vec![
+        log(1, -33.868_82, 151.209_29, SignalMode::FM),
+        log(2, 51.5074, -0.1278, SignalMode::DStar),
+        log(3, 0.0, 0.0, SignalMode::Other("JS8".to_string())),
+    ];
+    let full = &mut logs[0];
+    full.timestamp += chrono::Duration::microseconds(250_001);
+    full.power_dbm = Some(-87.25);
+    full.snr_db = Some(11.5);
+    full.bandwidth_hz = Some(Frequency::from_khz(12.5));
+    full.altitude_m = Some(58.0);
+    full.position_accuracy_m = Some(3.2);
+    full.heading_deg = Some(271.0);
+    full.speed_mps = Some(13.9);
+    full.session_id = Some(4);
+    full.tags = vec!["mobile".to_string(), "weak".to_string()];
+    logs[1].callsign = None;
+    logs[1].comment = None;
+    logs
+}
+
+fn json(out: Vec<u8>) -> Value {
+    serde_json::from_slice(&out).unwrap()
+}
+
+#[test]
+fn test_geojson_round_trip() {
+    let logs = sample_logs();
+    let mut out = Vec::new();
+    LogFormat::GeoJson.write_logs(&logs, &mut out).unwrap();
+    let read = LogFormat::GeoJson
+        .read_logs(&out, LogTimeZone::utc())
+        .unwrap();
+
+    assert_eq!(read.len(), logs.len());
+    for (read, written) in read.iter().zip(&logs) {
+        assert_eq!(read.uuid, written.uuid);
+        assert_eq!(
+            sdr_db::db::merge::differing_fields(read, written),
+            Vec::<&str>::new()
+        );
+        assert_eq!(read.session_id, written.session_id);
+    }
+}
+
+#[test]
+fn test_geojson_features_are_points_with_properties() {
+    let logs = sample_logs();
+    let mut out = Vec::new();
+    LogFormat::GeoJson.write_logs(&logs, &mut out).unwrap();
+    let collection = json(out);
+
+    assert_eq!(collection["type"], "FeatureCollection");
+    let feature = &collection["features"][0];
+    assert_eq!(feature["type"], "Feature");
+    assert_eq!(feature["geometry"]["type"], "Point");
+    // Longitude first, as GeoJSON requires
+    let coordinates = feature["geometry"]["coordinates"].as_array().unwrap();
+    assert_eq!(coordinates[0].as_f64().unwrap() as f32, logs[0].ycoord);
+    assert_eq!(coordinates[1].as_f64().unwrap() as f32, logs[0].xcoord);
+
+    let properties = &feature["properties"];
+    assert_eq!(properties["timestamp"], "2025-11-08T21:05:30.250001Z");
+    assert_eq!(properties["frequency"], 146_545_000);
+    assert_eq!(properties["mode"], "FM");
+    assert_eq!(properties["callsign"], "VK2AB1");
+    assert_eq!(properties["uuid"], logs[0].uuid.to_string());
+    assert_eq!(properties["tags"], serde_json::json!(["mobile", "weak"]));
+    assert!(properties.get("xcoord").is_none());
+}
+
+#[test]
+fn test_geojson_writer_applies_the_filter() {
+    let logs = sample_logs();
+    let filter = LogFilter::new().mode(SignalMode::DStar);
+    let mut out = Vec::new();
+    let written = write_logs(&logs, Some(&filter), &mut out).unwrap();
+
+    assert_eq!(written, 1);
+    let collection = json(out);
+    let features = collection["features"].as_array().unwrap();
+    assert_eq!(features.len(), 1);
+    assert_eq!(features[0]["properties"]["mode"], "D-STAR");
+}
+
+#[test]
+fn test_feature_writer_streams_a_valid_collection() {
+    let features = FeatureWriter::new(Vec::new()).unwrap();
+    let empty = json(features.finish().unwrap());
+    assert_eq!(empty["features"], serde_json::json!([]));
+
+    let mut features = FeatureWriter::new(Vec::new()).unwrap();
+    for log in &sample_logs() {
+        features.write_log(log).unwrap();
+    }
+    assert_eq!(features.count(), 3);
+    let out = features.finish().unwrap();
+    // One feature per line, so the output can be read a feature at a time
+    assert_eq!(String::from_utf8_lossy(&out).lines().count(), 5);
+    assert_eq!(json(out)["features"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn test_read_features_edited_in_other_tools() {
+    let geojson = br#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [-0.1, 51.5, 35.0]},
+                "properties": {
+                    "timestamp": "2025-11-08 21:04:30",
+                    "frequency": "146.52 MHz",
+                    "mode": "nfm",
+                    "callsign": "",
+                    "power_dbm": "-80.5",
+                    "tags": "mobile, weak",
+                    "name": "ignored"
+                }
+            },
+            {
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [2.35, 48.85]},
+                "properties": {
+                    "timestamp": "2025-11-08T22:00:00+01:00",
+                    "frequency": 433920000,
+                    "mode": "FM",
+                    "comment": null
+                }
+            }
+        ]
+    }"#;
+    let zone: LogTimeZone = "+02:00".parse().unwrap();
+    let logs = LogFormat::GeoJson.read_logs(geojson, zone).unwrap();
+
+    assert_eq!(logs[0].xcoord, 51.5);
+    assert_eq!(logs[0].ycoord, -0.1);
+    assert_eq!(logs[0].altitude_m, Some(35.0));
+    assert_eq!(logs[0].frequency, Frequency::from_khz(146_520.0));
+    assert_eq!(logs[0].mode, SignalMode::NFM);
+    assert_eq!(logs[0].callsign, None);
+    assert_eq!(logs[0].power_dbm, Some(-80.5));
+    assert_eq!(logs[0].tags, vec!["mobile", "weak"]);
+    assert_eq!(logs[0].timestamp, base_time() - chrono::Duration::hours(2));
+    assert_eq!(
+        logs[1].timestamp,
+        base_time() - chrono::Duration::minutes(4) - chrono::Duration::seconds(30)
+    );
+    assert_eq!(logs[1].comment, None);
+}
+
+#[test]
+fn test_bad_geojson_reports_the_feature() {
+    let utc = LogTimeZone::utc();
+    let read = |text: &str| LogFormat::GeoJson.read_logs(text.as_bytes(), utc);
+
+    let missing_mode = r#"{"type": "FeatureCollection", "features": [
+        {"type": "Feature", "geometry": {"type": "Point", "coordinates": [0, 0]},
+         "properties": {"timestamp": "2025-11-08T21:04:30Z", "frequency": 1e8, "mode": "FM"}},
+        {"type": "Feature", "geometry": {"type": "Point", "coordinates": [0, 0]},
+         "properties": {"timestamp": "2025-11-08T21:04:30Z", "frequency": 1e8}}
+    ]}"#;
+    assert_eq!(
+        read(missing_mode).unwrap_err(),
+        ValidationError::InvalidFeature {
+            index: 1,
+            reason: "mode is missing".to_string(),
+        }
+    );
+
+    let line = r#"{"type": "Feature", "geometry": {"type": "LineString",
+        "coordinates": [[0, 0], [1, 1]]}, "properties": {}}"#;
+    assert_eq!(
+        read(line).unwrap_err(),
+        ValidationError::InvalidFeature {
+            index: 0,
+            reason: "its geometry is a LineString, not a Point".to_string(),
+        }
+    );
+
+    let off_the_map = r#"{"type": "Feature", "geometry": {"type": "Point",
+        "coordinates": [0, 91]}, "properties": {}}"#;
+    assert!(matches!(
+        read(off_the_map),
+        Err(ValidationError::InvalidFeature { index: 0, .. })
+    ));
+    assert!(matches!(
+        read("{\"type\": \"Topology\"}"),
+        Err(ValidationError::InvalidRecord { line: 1, .. })
+    ));
+    assert!(matches!(
+        read("{\n\"type\": "),
+        Err(ValidationError::InvalidRecord { line: 2, .. })
+    ));
+}
+
+fn measurement(lat: f64, lon: f64, minutes: i64, power: f64) -> Measurement {
+    let timestamp =
+        Utc.with_ymd_and_hms(2025, 11, 8, 21, 0, 0).unwrap() + chrono::Duration::minutes(minutes);
+    let location = Coordinate::new(lat, lon).unwrap();
+    Measurement::new(location, timestamp, 146.52e6, power, 12.5e3, 18.0).unwrap()
+}
+
+#[test]
+fn test_layer_geojson_round_trip_with_filter() {
+    let layer = Layer::new(vec![
+        measurement(51.5, -0.12, 0, -60.0),
+        measurement(51.6, -0.13, 1, -95.0),
+        measurement(51.7, -0.14, 2, -70.5),
+    ]);
+    let mut out = Vec::new();
+    assert_eq!(write_layer(&layer, None, &mut out).unwrap(), 3);
+    assert_eq!(
+        read_layer(std::str::from_utf8(&out).unwrap())
+            .unwrap()
+            .measurements(),
+        layer.measurements()
+    );
+
+    let strong = |m: &Measurement| m.power >= -75.0;
+    let mut out = Vec::new();
+    assert_eq!(write_layer(&layer, Some(&strong), &mut out).unwrap(), 2);
+    let read = read_layer(std::str::from_utf8(&out).unwrap()).unwrap();
+    assert_eq!(read.measurement_count(), 2);
+    assert!(read.measurements().iter().all(strong));
+}
+
+#[test]
+fn test_aggregate_geojson_flattens_statistics() {
+    let layer = Layer::new(vec![
+        measurement(51.5, -0.12, 0, -60.0),
+        measurement(51.5, -0.12, 5, -70.0),
+        measurement(48.85, 2.35, 1, -80.0),
+    ]);
+    let aggregates = layer.aggregate_by_location(100.0);
+    let mut out = Vec::new();
+    let single = |aggregate: &sdr_db::spatial::LocationAggregate| aggregate.measurement_count == 1;
+    assert_eq!(write_aggregates(&aggregates, None, &mut out).unwrap(), 2);
+    let collection = json(out);
+
+    let properties = &collection["features"][0]["properties"];
+    assert_eq!(properties["measurement_count"], 2);
+    assert_eq!(properties["power_min"], -70.0);
+    assert_eq!(properties["power_max"], -60.0);
+    assert_eq!(properties["power_avg"], -65.0);
+    assert_eq!(properties["frequencies"], serde_json::json!([146.52e6]));
+    assert!(properties.get("power").is_none());
+    assert_eq!(
+        collection["features"][1]["geometry"]["coordinates"],
+        serde_json::json!([2.35, 48.85])
+    );
+
+    let mut out = Vec::new();
+    assert_eq!(
+        write_aggregates(&aggregates, Some(&single), &mut out).unwrap(),
+        1
+    );
+}
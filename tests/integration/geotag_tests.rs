@@ -0,0 +1,299 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use sdr_db::db::geotag::{GeotagOptions, geotag_log, geotag_logs};
+use sdr_db::db::{LogStore, LogStream, MemoryLogStore, SearchHit};
+use sdr_db::formats::gpx::read_track;
+use sdr_db::spatial::{Track, TrackMiss, TrackPoint};
+use sdr_db::{
+    Coordinate, DatabaseError, Frequency, Log, LogChanges, LogCursor, LogFilter, LogPage,
+    LogUuid, NewLog, SignalMode, ValidationError,
+};
+
+use super::common::utc_at;
+
+const GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="phone" xmlns="http://www.topografix.com/GPX/1/1">
+  <wpt lat="10.0" lon="10.0"><name>ignored</name></wpt>
+  <trk>
+    <name>Drive</name>
+    <trkseg>
+      <trkpt lat="51.5000" lon="-0.1200"><ele>12.5</ele><time>2025-11-08T21:00:00Z</time></trkpt>
+      <trkpt lat="51.5100" lon="-0.1000"><time>2025-11-08T21:00:20Z</time></trkpt>
+      <trkpt lat="51.9000" lon="-0.1000"></trkpt>
+      <trkpt lat="51.5200" lon="-0.1000"><time>2025-11-08T21:05:00Z</time></trkpt>
+    </trkseg>
+    <trkseg>
+      <trkpt lat="51.6000" lon="-0.2000"><time>2025-11-08T22:07:00+01:00</time></trkpt>
+      <trkpt lat="51.6000" lon="-0.3000"><time>2025-11-08T21:08:00Z</time></trkpt>
+    </trkseg>
+  </trk>
+</gpx>"#;
+
+fn utc(minutes: i64, seconds: i64) -> DateTime<Utc> {
+    utc_at(2025, 11, 8, 21, 0, 0) + TimeDelta::minutes(minutes) + TimeDelta::seconds(seconds)
+}
+
+fn log(id: i32, timestamp: DateTime<Utc>) -> Log {
+    let position = Coordinate::new(0.0, 0.0).unwrap();
+    Log {
+        id,
+        frequency: Frequency::from_mhz(146.52),
+        xcoord: 0.0,
+        ycoord: 0.0,
+        callsign: None,
+        mode: SignalMode::FM,
+        comment: None,
+        timestamp,
+        recording_duration: 0.0,
+        location: Some(position.into()),
+        power_dbm: None,
+        snr_db: None,
+        bandwidth_hz: None,
+        altitude_m: None,
+        position_accuracy_m: None,
+        heading_deg: None,
+        speed_mps: None,
+        uuid: LogUuid::new_v7(),
+        session_id: None,
+        equipment_profile_id: None,
+        tags: Vec::new(),
+    }
+}
+
+/// A store that loses the oldest log of each page it returns, as if another user
+/// deleted it while the page was being read
+struct VanishingStore(MemoryLogStore);
+
+impl LogStore for VanishingStore {
+    fn create(&mut self, new_log: &NewLog) -> Result<Log, DatabaseError> {
+        self.0.create(new_log)
+    }
+
+    fn get(&mut self, id: i32) -> Result<Log, DatabaseError> {
+        self.0.get(id)
+    }
+
+    fn get_by_uuids(&mut self, uuids: &[LogUuid]) -> Result<Vec<Log>, DatabaseError> {
+        self.0.get_by_uuids(uuids)
+    }
+
+    fn query(
+        &mut self,
+        filter: &LogFilter,
+        after: Option<LogCursor>,
+        limit: i64,
+    ) -> Result<LogPage, DatabaseError> {
+        let page = self.0.query(filter, after, limit)?;
+        if let Some(oldest) = page.logs.last() {
+            self.0.delete(oldest.id)?;
+        }
+        Ok(page)
+    }
+
+    fn update(&mut self, id: i32, changes: &LogChanges) -> Result<Log, DatabaseError> {
+        self.0.update(id, changes)
+    }
+
+    fn update_many(&mut self, updates: &[(i32, LogChanges)]) -> Result<Vec<Log>, DatabaseError> {
+        self.0.update_many(updates)
+    }
+
+    fn delete(&mut self, id: i32) -> Result<(), DatabaseError> {
+        self.0.delete(id)
+    }
+
+    fn search(&mut self, query: &str, limit: i64) -> Result<Vec<SearchHit>, DatabaseError> {
+        self.0.search(query, limit)
+    }
+
+    fn stream_all(&mut self) -> Result<LogStream<'_>, DatabaseError> {
+        self.0.stream_all()
+    }
+}
+
+fn assert_near(position: Coordinate, lat: f64, lon: f64) {
+    assert!(
+        (position.lat() - lat).abs() < 1e-9 && (position.lon() - lon).abs() < 1e-9,
+        "{:?} is not ({}, {})",
+        position,
+        lat,
+        lon
+    );
+}
+
+#[test]
+fn test_read_gpx_track_segments() {
+    let track = read_track(GPX).unwrap();
+    // The point without a time is skipped
+    assert_eq!(track.point_count(), 5);
+    assert_eq!(track.segments().len(), 2);
+    let first = track.segments()[0][0];
+    assert_eq!(first.time, utc(0, 0));
+    assert_eq!(first.elevation_m, Some(12.5));
+    assert_eq!(track.segments()[0][1].elevation_m, None);
+    assert_eq!(track.time_range(), Some((utc(0, 0), utc(8, 0))));
+}
+
+#[test]
+fn test_bad_gpx_reports_the_line() {
+    assert_eq!(
+        read_track("<gpx>\n<trk><trkseg>\n<trkpt lat=\"95\" lon=\"0\"/></trkseg></trk></gpx>")
+            .unwrap_err(),
+        ValidationError::InvalidRecord {
+            line: 3,
+            reason: ValidationError::InvalidLatitude(95.0).to_string(),
+        }
+    );
+    assert!(matches!(
+        read_track(
+            "<gpx><trk><trkseg><trkpt lat=\"1\" lon=\"2\"><time>noon</time></trkpt></trkseg></trk></gpx>"
+        ),
+        Err(ValidationError::InvalidRecord { line: 1, .. })
+    ));
+    assert_eq!(
+        read_track("<gpx><wpt lat=\"1\" lon=\"2\"/></gpx>").unwrap_err(),
+        ValidationError::InvalidRecord {
+            line: 1,
+            reason: "no track points with a time".to_string(),
+        }
+    );
+    assert!(matches!(
+        read_track("<kml></kml>"),
+        Err(ValidationError::InvalidRecord { .. })
+    ));
+}
+
+#[test]
+fn test_track_interpolates_between_fixes() {
+    let track = read_track(GPX).unwrap();
+    let max_gap = TimeDelta::seconds(60);
+
+    assert_near(track.position_at(utc(0, 0), max_gap).unwrap(), 51.5, -0.12);
+    assert_near(
+        track.position_at(utc(0, 5), max_gap).unwrap(),
+        51.5025,
+        -0.115,
+    );
+    assert_near(track.position_at(utc(0, 20), max_gap).unwrap(), 51.51, -0.1);
+    assert_near(track.position_at(utc(7, 30), max_gap).unwrap(), 51.6, -0.25);
+
+    assert_eq!(
+        track.position_at(utc(0, 30), max_gap),
+        Err(TrackMiss::InGap(TimeDelta::seconds(280)))
+    );
+    assert!(track.position_at(utc(0, 30), TimeDelta::minutes(5)).is_ok());
+    assert_eq!(
+        track.position_at(utc(-1, 0), max_gap),
+        Err(TrackMiss::BeforeStart(TimeDelta::minutes(1)))
+    );
+    assert_eq!(
+        track.position_at(utc(12, 0), max_gap),
+        Err(TrackMiss::AfterEnd(TimeDelta::minutes(4)))
+    );
+    // Never across the break between segments, however long the allowed gap
+    assert_eq!(
+        track.position_at(utc(5, 30), TimeDelta::hours(1)),
+        Err(TrackMiss::InGap(TimeDelta::minutes(2)))
+    );
+}
+
+#[test]
+fn test_track_interpolates_across_the_antimeridian() {
+    let point = |lon: f64, seconds: i64| TrackPoint {
+        position: Coordinate::new(-17.0, lon).unwrap(),
+        time: utc(0, seconds),
+        elevation_m: None,
+    };
+    let track = Track::new(vec![vec![point(179.5, 0), point(-179.5, 10)]]);
+    let max_gap = TimeDelta::seconds(60);
+    assert_near(track.position_at(utc(0, 2), max_gap).unwrap(), -17.0, 179.7);
+    assert_near(
+        track.position_at(utc(0, 8), max_gap).unwrap(),
+        -17.0,
+        -179.7,
+    );
+}
+
+#[test]
+fn test_geotag_applies_the_clock_offset() {
+    let track = read_track(GPX).unwrap();
+    // The logging clock runs 30 s ahead of the GPS
+    let options = GeotagOptions {
+        clock_offset: TimeDelta::seconds(30),
+        ..GeotagOptions::default()
+    };
+    let early = log(1, utc(0, 35));
+    assert_near(
+        geotag_log(&early, &track, &options).unwrap(),
+        51.5025,
+        -0.115,
+    );
+    assert_eq!(
+        geotag_log(&log(2, utc(0, 10)), &track, &options),
+        Err(TrackMiss::BeforeStart(TimeDelta::seconds(20)))
+    );
+}
+
+#[test]
+fn test_geotag_logs_reports_logs_outside_the_track() {
+    let track = read_track(GPX).unwrap();
+    let logs = vec![
+        log(0, utc(0, 10)),
+        log(0, utc(-5, 0)),
+        log(0, utc(2, 0)),
+        log(0, utc(7, 30)),
+    ];
+    let mut store = MemoryLogStore::from_logs(logs);
+    let options = GeotagOptions::default();
+
+    let dry_run = geotag_logs(&mut store, &LogFilter::new(), &track, &options, false).unwrap();
+    assert_eq!(dry_run.scanned, 4);
+    assert_eq!(dry_run.tagged.len(), 2);
+    let mut untagged: Vec<(i32, TrackMiss)> = dry_run
+        .untagged
+        .iter()
+        .map(|miss| (miss.id, miss.miss))
+        .collect();
+    untagged.sort_by_key(|(id, _)| *id);
+    assert_eq!(
+        untagged,
+        vec![
+            (2, TrackMiss::BeforeStart(TimeDelta::minutes(5))),
+            (3, TrackMiss::InGap(TimeDelta::seconds(280))),
+        ]
+    );
+    assert_eq!(store.get(1).unwrap().xcoord, 0.0);
+    let report = dry_run.to_string();
+    assert!(report.starts_with("Scanned 4 logs, 2 placed on the track, 2 outside it"));
+    assert!(report.contains("id 2 at 2025-11-08 20:55:00.000 UTC: 5m 00s before the track starts"));
+    assert!(report.contains("id 3 at 2025-11-08 21:02:00.000 UTC: in a 4m 40s gap between fixes"));
+    assert!(report.ends_with("Dry run, nothing written (use --apply to move the logs)"));
+
+    let applied = geotag_logs(&mut store, &LogFilter::new(), &track, &options, true).unwrap();
+    assert!(applied.applied);
+    let moved = store.get(1).unwrap();
+    assert_eq!(moved.xcoord, 51.505_f32);
+    assert_eq!(moved.ycoord, -0.11_f32);
+    assert_eq!(store.get(4).unwrap().xcoord, 51.6_f32);
+    // Logs outside the track keep their position
+    assert_eq!(store.get(2).unwrap().xcoord, 0.0);
+}
+
+#[test]
+fn test_geotag_logs_applies_nothing_when_a_move_fails() {
+    let track = read_track(GPX).unwrap();
+    // All on the track; the oldest is gone by the time the moves are written
+    let logs = vec![log(0, utc(0, 10)), log(0, utc(7, 30)), log(0, utc(7, 45))];
+    let mut store = VanishingStore(MemoryLogStore::from_logs(logs));
+
+    let result = geotag_logs(
+        &mut store,
+        &LogFilter::new(),
+        &track,
+        &GeotagOptions::default(),
+        true,
+    );
+    assert!(matches!(result, Err(DatabaseError::NotFound(_))));
+    // The logs still stored were not moved either
+    assert_eq!(store.get(2).unwrap().xcoord, 0.0);
+    assert_eq!(store.get(3).unwrap().xcoord, 0.0);
+}
@@ -0,0 +1,226 @@
+use chrono::{DateTime, Utc};
+use sdr_db::formats::kml::{KmlColours, KmlFolders, KmlOptions, write_kml};
+use sdr_db::formats::xml::{self, Element};
+use sdr_db::formats::zip::crc32;
+use sdr_db::{
+    Coordinate, Frequency, Log, LogFormat, LogTimeZone, LogUuid, SignalMode, ValidationError,
+};
+
+use super::common::utc_at;
+
+fn base_time() -> DateTime<Utc> {
+    utc_at(2025, 11, 8, 21, 4, 30)
+}
+
+fn log(id: i32, mhz: f64, mode: SignalMode, session_id: Option<i32>, minutes: i64) -> Log {
+    let position = Coordinate::new(51.5 + id as f64 * 0.001, -0.12).unwrap();
+    Log {
+        id,
+        frequency: Frequency::from_mhz(mhz),
+        xcoord: position.lat() as f32,
+        ycoord: position.lon() as f32,
+        callsign: None,
+        mode,
+        comment: None,
+        timestamp: base_time() + chrono::Duration::minutes(minutes),
+        recording_duration: 0.0,
+        location: Some(position.into()),
+        power_dbm: None,
+        snr_db: None,
+        bandwidth_hz: None,
+        altitude_m: None,
+        position_accuracy_m: None,
+        heading_deg: None,
+        speed_mps: None,
+        uuid: LogUuid::new_v7(),
+        session_id,
+        equipment_profile_id: None,
+        tags: Vec::new(),
+    }
+}
+
+/// A drive over two sessions plus a log outside any session
+fn drive() -> Vec<Log> {
+    let mut logs = vec![
+        log(1, 146.52, SignalMode::FM, Some(2), 10),
+        log(2, 7.074, SignalMode::FT8, None, 0),
+        log(3, 433.92, SignalMode::DStar, Some(1), 5),
+        log(4, 145.8, SignalMode::FM, Some(2), 3),
+    ];
+    logs[0].power_dbm = Some(-50.0);
+    logs[2].power_dbm = Some(-101.5);
+    logs[3].power_dbm = Some(-72.0);
+    logs
+}
+
+fn kml(logs: &[Log], options: KmlOptions) -> Element {
+    let mut out = Vec::new();
+    write_kml(logs, &options, &mut out).unwrap();
+    xml::parse(std::str::from_utf8(&out).unwrap()).unwrap()
+}
+
+fn folder_names(document: &Element) -> Vec<&str> {
+    document
+        .children_named("Folder")
+        .map(|folder| folder.child_text("name").unwrap())
+        .collect()
+}
+
+#[test]
+fn test_kml_folders_per_session_replay_in_time_order() {
+    // Session 1 is not known to the store, so it is named by its id
+    let options = KmlOptions {
+        session_names: [(2, "Ridge <drive>".to_string())].into(),
+        ..KmlOptions::default()
+    };
+    let root = kml(&drive(), options);
+    assert_eq!(root.name, "kml");
+    let document = root.child("Document").unwrap();
+    assert_eq!(
+        folder_names(document),
+        vec!["Session 1", "Ridge <drive>", "No session"]
+    );
+
+    let session_2 = document.children_named("Folder").nth(1).unwrap();
+    let placemarks: Vec<&Element> = session_2.children_named("Placemark").collect();
+    assert_eq!(placemarks.len(), 2);
+    let begins: Vec<&str> = placemarks
+        .iter()
+        .map(|placemark| {
+            placemark
+                .child("TimeSpan")
+                .and_then(|span| span.child_text("begin"))
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(begins, vec!["2025-11-08T21:07:30Z", "2025-11-08T21:14:30Z"]);
+    assert_eq!(placemarks[0].child_text("styleUrl"), Some("#mode-FM"));
+    assert_eq!(
+        placemarks[0]
+            .child("Point")
+            .and_then(|point| point.child_text("coordinates")),
+        Some("-0.12,51.504")
+    );
+}
+
+#[test]
+fn test_kml_band_folders_and_power_colours() {
+    let options = KmlOptions {
+        folders: KmlFolders::Band,
+        colours: KmlColours::Power,
+        ..KmlOptions::default()
+    };
+    let root = kml(&drive(), options);
+    let document = root.child("Document").unwrap();
+    assert_eq!(
+        folder_names(document),
+        vec!["HF band", "VHF band", "UHF band"]
+    );
+
+    let styles: Vec<(&str, &str)> = document
+        .children_named("Style")
+        .map(|style| {
+            let colour = style
+                .child("IconStyle")
+                .and_then(|icon| icon.child_text("color"))
+                .unwrap();
+            (style.attribute("id").unwrap(), colour)
+        })
+        .collect();
+    assert_eq!(
+        styles,
+        vec![
+            ("power-0", "ffba832b"),
+            ("power-2", "ffbfffff"),
+            ("power-4", "ff1c19d7"),
+            ("power-none", "ff9e9e9e"),
+        ]
+    );
+    let hf = document.child("Folder").unwrap();
+    assert_eq!(
+        hf.child("Placemark").and_then(|p| p.child_text("styleUrl")),
+        Some("#power-none")
+    );
+}
+
+#[test]
+fn test_kml_balloons_list_the_log_fields() {
+    let mut full = log(7, 146.52, SignalMode::NFM, None, 0);
+    full.callsign = Some("vk2abc".to_string());
+    full.comment = Some("<b>loud</b> & clear ]]>".to_string());
+    full.recording_duration = 12.345;
+    full.snr_db = Some(11.5);
+    full.tags = vec!["mobile".to_string(), "weak".to_string()];
+    let root = kml(&[full], KmlOptions::default());
+    let placemark = root
+        .descendants()
+        .into_iter()
+        .find(|element| element.is("Placemark"))
+        .unwrap();
+
+    assert_eq!(placemark.child_text("name"), Some("146.52 MHz VK2ABC"));
+    let description = placemark.child_text("description").unwrap();
+    let lines: Vec<&str> = description.split("<br/>").collect();
+    assert_eq!(
+        lines,
+        vec![
+            "<b>146.52 MHz</b>",
+            "Callsign: VK2ABC",
+            "Coordinate: (51.507, -0.12)",
+            "Comment: &lt;b&gt;loud&lt;/b&gt; &amp; clear ]]&gt;",
+            "Mode: NFM",
+            "Recorded at: 2025-11-08 21:04:30 UTC",
+            "Duration: 12.35 sec",
+            "SNR: 11.5 dB",
+            "Tags: mobile, weak",
+        ]
+    );
+}
+
+#[test]
+fn test_kmz_is_a_zip_holding_the_kml() {
+    assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    assert_eq!(crc32(b""), 0);
+
+    let logs = drive();
+    let mut kml = Vec::new();
+    LogFormat::Kml.write_logs(&logs, &mut kml).unwrap();
+    let mut kmz = Vec::new();
+    LogFormat::Kmz.write_logs(&logs, &mut kmz).unwrap();
+
+    let u16_at = |at: usize| u16::from_le_bytes([kmz[at], kmz[at + 1]]) as usize;
+    let u32_at = |at: usize| u32::from_le_bytes(kmz[at..at + 4].try_into().unwrap());
+    // Local header: stored, with the checksum and sizes of the document
+    assert_eq!(u32_at(0), 0x0403_4b50);
+    assert_eq!(u16_at(8), 0);
+    assert_eq!(u32_at(14), crc32(&kml));
+    assert_eq!(u32_at(18) as usize, kml.len());
+    assert_eq!(u32_at(22) as usize, kml.len());
+    let name_len = u16_at(26);
+    assert_eq!(&kmz[30..30 + name_len], b"doc.kml");
+    let data = 30 + name_len;
+    assert_eq!(&kmz[data..data + kml.len()], kml.as_slice());
+
+    // The end record points back at one central directory entry
+    let end = kmz.len() - 22;
+    assert_eq!(u32_at(end), 0x0605_4b50);
+    assert_eq!(u16_at(end + 10), 1);
+    let central = u32_at(end + 16) as usize;
+    assert_eq!(central, data + kml.len());
+    assert_eq!(u32_at(central), 0x0201_4b50);
+    assert_eq!(u32_at(central + 42), 0);
+}
+
+#[test]
+fn test_kml_is_export_only() {
+    assert!(matches!(
+        LogFormat::Kmz.read_logs(b"PK", LogTimeZone::utc()),
+        Err(ValidationError::UnsupportedFormat(_))
+    ));
+    assert_eq!(
+        LogFormat::from_path(std::path::Path::new("drive.KMZ")),
+        Some(LogFormat::Kmz)
+    );
+    assert_eq!("power".parse::<KmlColours>(), Ok(KmlColours::Power));
+    assert!("size".parse::<KmlFolders>().is_err());
+}
@@ -29,3 +29,26 @@ fn test_query_by_bbox_filters_correctly() {
     let results = layer.query_by_bbox(&bbox);
     assert_eq!(results.len(), 2); // First two measurements
 }
+
+#[test]
+fn test_bbox_rejects_inverted_corners() {
+    let result = BoundingBox::new(
+        Coordinate::new(39.0, -121.0).unwrap(),
+        Coordinate::new(36.0, -124.0).unwrap(),
+    );
+    assert!(matches!(result, Err(sdr_db::Error::Validation(sdr_db::ValidationError::InvalidBoundingBox(_)))));
+}
+
+#[test]
+fn test_spatial_extent_covers_all_measurements() {
+    let layer = Layer::new(vec![
+        measurement_at(37.0, -122.0),
+        measurement_at(40.0, -120.0),
+        measurement_at(38.0, -123.0),
+    ]);
+
+    let extent = layer.spatial_extent().unwrap();
+    assert_eq!(extent.min(), Coordinate::new(37.0, -123.0).unwrap());
+    assert_eq!(extent.max(), Coordinate::new(40.0, -120.0).unwrap());
+    assert!(Layer::default().spatial_extent().is_none());
+}
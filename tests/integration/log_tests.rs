@@ -0,0 +1,285 @@
+use chrono::{Duration, NaiveDate, Utc};
+use sdr_db::error::ValidationError;
+use sdr_db::tabs::create_log::{DetailField, NewLogInputForm};
+use sdr_db::{
+    Coordinate, DatabaseError, Frequency, LogChanges, LogDetails, LogTimeZone, NewLog, SignalMode,
+    parse_timestamp,
+};
+
+use super::common::utc_at;
+
+fn new_log(details: LogDetails) -> Result<NewLog<'static>, ValidationError> {
+    NewLog::new(
+        Frequency::from_mhz(146.52),
+        Coordinate::new(40.7128, -74.0060).unwrap(),
+        "W1AW",
+        SignalMode::FM,
+        "",
+        1.5,
+        None,
+        details,
+    )
+}
+
+#[test]
+fn test_new_log_carries_details() {
+    let details = LogDetails {
+        power_dbm: Some(-72.5),
+        snr_db: Some(18.0),
+        bandwidth_hz: Some(Frequency::from_khz(12.5)),
+        altitude_m: Some(35.0),
+        position_accuracy_m: Some(4.2),
+        heading_deg: Some(270.0),
+        speed_mps: Some(13.9),
+    };
+    let log = new_log(details).unwrap();
+    assert_eq!(log.details(), details);
+
+    let empty = new_log(LogDetails::default()).unwrap();
+    assert_eq!(empty.details(), LogDetails::default());
+}
+
+#[test]
+fn test_new_log_rejects_invalid_details() {
+    let cases = [
+        LogDetails {
+            power_dbm: Some(f32::NAN),
+            ..Default::default()
+        },
+        LogDetails {
+            bandwidth_hz: Some(Frequency::from_hz(0)),
+            ..Default::default()
+        },
+        LogDetails {
+            position_accuracy_m: Some(-1.0),
+            ..Default::default()
+        },
+        LogDetails {
+            heading_deg: Some(360.0),
+            ..Default::default()
+        },
+        LogDetails {
+            speed_mps: Some(-0.5),
+            ..Default::default()
+        },
+    ];
+    for details in cases {
+        assert!(new_log(details).is_err(), "{:?} should be rejected", details);
+    }
+}
+
+#[test]
+fn test_form_parses_optional_details() {
+    let mut form = NewLogInputForm::new();
+    assert_eq!(form.details(), Ok(LogDetails::default()));
+
+    form.detail_inputs[DetailField::Power as usize] = "-80".to_string();
+    form.detail_inputs[DetailField::Bandwidth as usize] = "12.5".to_string();
+    let details = form.details().unwrap();
+    assert_eq!(details.power_dbm, Some(-80.0));
+    assert_eq!(details.bandwidth_hz, Some(Frequency::from_hz(12_500)));
+
+    form.detail_inputs[DetailField::Snr as usize] = "loud".to_string();
+    assert!(matches!(
+        form.details(),
+        Err(ValidationError::InvalidNumber { .. })
+    ));
+}
+
+#[test]
+fn test_new_log_timestamp_defaults_to_now() {
+    let before = Utc::now();
+    let entry = new_log(LogDetails::default()).unwrap();
+    assert!(entry.timestamp >= before && entry.timestamp <= Utc::now());
+
+    let notebook = utc_at(1987, 6, 1, 21, 15, 0);
+    let entry = NewLog::new(
+        Frequency::from_mhz(14.074),
+        Coordinate::new(40.7128, -74.0060).unwrap(),
+        "W1AW",
+        SignalMode::USB,
+        "from the paper log",
+        0.0,
+        Some(notebook),
+        LogDetails::default(),
+    )
+    .unwrap();
+    assert_eq!(entry.timestamp, notebook);
+}
+
+#[test]
+fn test_timestamps_must_be_after_1900_and_not_in_the_future() {
+    let mut entry = new_log(LogDetails::default()).unwrap();
+    entry.timestamp = utc_at(1899, 12, 31, 23, 59, 59);
+    assert!(matches!(
+        entry.validate(),
+        Err(ValidationError::InvalidTimestamp(_))
+    ));
+
+    let tomorrow = Utc::now() + Duration::days(1);
+    let changes = LogChanges {
+        timestamp: Some(tomorrow),
+        ..Default::default()
+    };
+    assert!(matches!(
+        changes.validate(),
+        Err(ValidationError::InvalidTimestamp(_))
+    ));
+
+    // A unit whose clock runs slightly fast can still log
+    entry.timestamp = Utc::now() + Duration::minutes(1);
+    assert_eq!(entry.validate(), Ok(()));
+}
+
+#[test]
+fn test_parse_timestamp_with_offset_or_zone() {
+    let expected = NaiveDate::from_ymd_opt(2025, 11, 14)
+        .unwrap()
+        .and_hms_opt(7, 30, 0)
+        .unwrap()
+        .and_utc();
+    let berlin: LogTimeZone = "+02:00".parse().unwrap();
+    let utc: LogTimeZone = "utc".parse().unwrap();
+
+    assert_eq!(
+        parse_timestamp("2025-11-14T07:30:00Z", berlin),
+        Ok(expected)
+    );
+    assert_eq!(
+        parse_timestamp("2025-11-14T09:30:00+02:00", utc),
+        Ok(expected)
+    );
+    assert_eq!(parse_timestamp("2025-11-14 09:30", berlin), Ok(expected));
+    assert_eq!(parse_timestamp(" 2025-11-14T07:30:00 ", utc), Ok(expected));
+
+    for invalid in [
+        "yesterday",
+        "14/11/2025 09:30",
+        "1850-01-01 00:00",
+        "2999-01-01T00:00:00Z",
+    ] {
+        assert!(
+            matches!(
+                parse_timestamp(invalid, utc),
+                Err(ValidationError::InvalidTimestamp(_))
+            ),
+            "{} should be rejected",
+            invalid
+        );
+    }
+
+    assert_eq!("Local".parse(), Ok(LogTimeZone::Local));
+    assert!("Mars/Olympus".parse::<LogTimeZone>().is_err());
+}
+
+#[test]
+fn test_form_timestamp_is_optional() {
+    let mut form = NewLogInputForm::new();
+    form.time_zone = "-05:00".parse().unwrap();
+    assert_eq!(form.timestamp(), Ok(None));
+
+    form.timestamp_input = "2025-11-14 02:30".to_string();
+    assert_eq!(
+        form.timestamp().unwrap().unwrap().naive_utc(),
+        NaiveDate::from_ymd_opt(2025, 11, 14)
+            .unwrap()
+            .and_hms_opt(7, 30, 0)
+            .unwrap()
+    );
+
+    form.reset();
+    assert_eq!(form.timestamp_input, "");
+    assert_eq!(form.time_zone, "-05:00".parse().unwrap());
+}
+
+#[test]
+fn test_new_log_validate_catches_literal_fields() {
+    let mut log = new_log(LogDetails::default()).unwrap();
+    assert_eq!(log.validate(), Ok(()));
+
+    log.xcoord = 123.0;
+    assert_eq!(log.validate(), Err(ValidationError::InvalidLatitude(123.0)));
+
+    log.xcoord = 40.0;
+    log.recording_duration = -1.0;
+    assert_eq!(
+        log.validate(),
+        Err(ValidationError::InvalidRecordingDuration(-1.0))
+    );
+}
+
+#[test]
+fn test_new_log_validate_checks_location_matches_coordinates() {
+    let log = new_log(LogDetails::default()).unwrap();
+    let moved = NewLog {
+        location: Coordinate::new(51.5074, -0.1278).unwrap().into(),
+        ..log.clone()
+    };
+    assert!(matches!(
+        moved.validate(),
+        Err(ValidationError::InvalidGeometry(_))
+    ));
+
+    // The coordinate columns only hold f32 precision, and ±180° is one meridian
+    let antimeridian = NewLog {
+        xcoord: -16.8,
+        ycoord: 180.0,
+        location: Coordinate::new(-16.8, -180.0).unwrap().into(),
+        ..log
+    };
+    assert_eq!(antimeridian.validate(), Ok(()));
+}
+
+#[test]
+fn test_log_changes_validation() {
+    assert!(LogChanges::default().is_empty());
+    assert_eq!(LogChanges::default().validate(), Ok(()));
+
+    let changes = LogChanges {
+        frequency: Some(Frequency::from_hz(0)),
+        ..Default::default()
+    };
+    assert!(!changes.is_empty());
+    assert_eq!(
+        changes.validate(),
+        Err(ValidationError::InvalidFrequency(0.0))
+    );
+
+    let changes = LogChanges {
+        details: Some(LogDetails {
+            heading_deg: Some(400.0),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(
+        changes.validate(),
+        Err(ValidationError::InvalidHeading(400.0))
+    );
+}
+
+#[test]
+fn test_database_error_from_diesel() {
+    use diesel::result::{DatabaseErrorKind, Error};
+
+    assert!(matches!(
+        DatabaseError::from(Error::NotFound),
+        DatabaseError::NotFound(_)
+    ));
+    let duplicate = Error::DatabaseError(
+        DatabaseErrorKind::UniqueViolation,
+        Box::new("duplicate key value".to_string()),
+    );
+    assert_eq!(
+        DatabaseError::from(duplicate),
+        DatabaseError::DuplicateEntry("duplicate key value".to_string())
+    );
+    assert!(matches!(
+        DatabaseError::from(Error::RollbackTransaction),
+        DatabaseError::QueryError(_)
+    ));
+    assert_eq!(
+        DatabaseError::from(ValidationError::EmptyDataset),
+        DatabaseError::InvalidData(ValidationError::EmptyDataset)
+    );
+}
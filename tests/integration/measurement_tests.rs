@@ -1,6 +1,6 @@
 use sdr_db::spatial::Coordinate;
 use sdr_db::Measurement;
-use sdr_db::error::ValidationError;
+use sdr_db::error::{Error, ValidationError};
 use chrono::Utc;
 
 #[test]
@@ -28,5 +28,8 @@ fn test_measurement_new_invalid_frequency() {
         20e6,
         18.3,
     );
-    assert!(matches!(m, Err(ValidationError::InvalidFrequency(_))));
+    assert!(matches!(
+        m,
+        Err(Error::Validation(ValidationError::InvalidFrequency(_)))
+    ));
 }
@@ -0,0 +1,165 @@
+use chrono::Duration;
+use sdr_db::db::merge::{differing_fields, source_config};
+use sdr_db::db::{LogStore, MemoryLogStore, SledLogStore, StoreConfig, merge_logs};
+use sdr_db::{LogChanges, NewLog, SignalMode};
+
+use super::common::temporary_sled_store;
+
+fn new_log(minutes: i64, callsign: &str) -> NewLog<'_> {
+    NewLog {
+        callsign,
+        comment: "APRS",
+        power_dbm: Some(-72.0),
+        ..super::common::new_log(minutes)
+    }
+}
+
+#[test]
+fn test_merge_imports_new_logs_once() {
+    let mut source = temporary_sled_store();
+    let mut target = MemoryLogStore::new();
+    let shared = new_log(0, "DL1ABC");
+    source.create(&shared).unwrap();
+    target.create(&shared).unwrap();
+    let fresh = source.create(&new_log(5, "DL2XYZ")).unwrap();
+
+    let report = merge_logs(&mut target, &mut source).unwrap();
+    assert_eq!(report.scanned, 2);
+    assert_eq!(report.imported, 1);
+    assert_eq!(report.duplicates, 1);
+    assert!(report.conflicts.is_empty());
+
+    let imported = target
+        .stream_all()
+        .unwrap()
+        .map(Result::unwrap)
+        .find(|log| log.uuid == fresh.uuid)
+        .unwrap();
+    assert_eq!(imported.callsign.as_deref(), Some("DL2XYZ"));
+    assert_eq!(imported.power_dbm, Some(-72.0));
+    assert!(differing_fields(&imported, &fresh).is_empty());
+
+    let again = merge_logs(&mut target, &mut source).unwrap();
+    assert_eq!(again.imported, 0);
+    assert_eq!(again.duplicates, 2);
+}
+
+#[test]
+fn test_merge_does_not_restore_deleted_logs() {
+    let mut source = MemoryLogStore::new();
+    let mut sled = temporary_sled_store();
+    let mut memory = MemoryLogStore::new();
+    source.create(&new_log(0, "DL1ABC")).unwrap();
+    for target in [&mut sled as &mut dyn LogStore, &mut memory] {
+        merge_logs(target, &mut source).unwrap();
+        let id = target.stream_all().unwrap().next().unwrap().unwrap().id;
+        target.delete(id).unwrap();
+
+        let again = merge_logs(target, &mut source).unwrap();
+        assert_eq!(again.imported, 0);
+        assert_eq!(again.deleted, 1);
+        assert_eq!(target.stream_all().unwrap().count(), 0);
+    }
+}
+
+#[test]
+fn test_get_by_uuids_finds_only_the_given_logs() {
+    let mut sled = temporary_sled_store();
+    let mut memory = MemoryLogStore::new();
+    for store in [&mut sled as &mut dyn LogStore, &mut memory] {
+        let first = store.create(&new_log(0, "DL1ABC")).unwrap();
+        let second = store.create(&new_log(5, "DL2XYZ")).unwrap();
+        let deleted = store.create(&new_log(10, "DL3QRS")).unwrap();
+        store.delete(deleted.id).unwrap();
+        let unknown = new_log(15, "DL4TUV").uuid;
+
+        let mut found = store
+            .get_by_uuids(&[second.uuid, unknown, deleted.uuid, first.uuid])
+            .unwrap();
+        found.sort_by_key(|log| log.id);
+        assert_eq!(
+            found.iter().map(|log| log.uuid).collect::<Vec<_>>(),
+            vec![first.uuid, second.uuid]
+        );
+        assert!(store.get_by_uuids(&[]).unwrap().is_empty());
+    }
+}
+
+#[test]
+fn test_merge_reports_conflicts() {
+    let mut source = MemoryLogStore::new();
+    let mut target = MemoryLogStore::new();
+    let entry = new_log(0, "DL1ABC");
+    let incoming = source.create(&entry).unwrap();
+    let local = target.create(&entry).unwrap();
+    let changes = LogChanges {
+        comment: Some("APRS, corrected".to_string()),
+        mode: Some(SignalMode::NFM),
+        ..Default::default()
+    };
+    target.update(local.id, &changes).unwrap();
+
+    let report = merge_logs(&mut target, &mut source).unwrap();
+    assert_eq!(report.imported, 0);
+    assert_eq!(report.conflicts.len(), 1);
+    let conflict = &report.conflicts[0];
+    assert_eq!(conflict.uuid, incoming.uuid);
+    assert_eq!(conflict.fields, vec!["mode", "comment"]);
+    assert!(report.to_string().contains("differ in mode, comment"));
+    assert_eq!(target.len(), 1);
+}
+
+#[test]
+fn test_differing_fields_ignores_sub_microsecond_time() {
+    let mut store = MemoryLogStore::new();
+    let log = store.create(&new_log(0, "DL1ABC")).unwrap();
+    let mut stored = log.clone();
+    stored.id = 42;
+    stored.timestamp += Duration::nanoseconds(300);
+    assert!(differing_fields(&log, &stored).is_empty());
+
+    stored.timestamp += Duration::seconds(1);
+    assert_eq!(differing_fields(&log, &stored), vec!["timestamp"]);
+}
+
+#[test]
+fn test_merge_source_config() {
+    assert_eq!(
+        source_config("postgres://unit2@10.0.0.2/sdr_db"),
+        StoreConfig::Postgres {
+            database_url: "postgres://unit2@10.0.0.2/sdr_db".to_string()
+        }
+    );
+    assert_eq!(
+        source_config("/media/usb/unit2.sled"),
+        StoreConfig::Sled {
+            path: "/media/usb/unit2.sled".into()
+        }
+    );
+}
+
+#[test]
+fn test_sled_store_backfills_missing_uuid() {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    let legacy = r#"{"id":1,"frequency":146520000,"xcoord":40.7128,"ycoord":-74.006,
+        "callsign":"W1AW","mode":"FM","comment":null,"timestamp":"2025-10-01T12:00:00",
+        "recording_duration":2.0,"power_dbm":null,"snr_db":null,"bandwidth_hz":null,
+        "altitude_m":null,"position_accuracy_m":null,"heading_deg":null,"speed_mps":null,
+        "uuid":null,"position":[-74.006,40.7128]}"#;
+    db.open_tree("logs")
+        .unwrap()
+        .insert(1i32.to_be_bytes(), legacy.as_bytes())
+        .unwrap();
+
+    let mut store = SledLogStore::from_db(db.clone()).unwrap();
+    let log = store.get(1).unwrap();
+    assert_eq!(log.uuid.version(), 7);
+    assert_eq!(
+        log.uuid.timestamp_ms() as i64,
+        log.timestamp.timestamp_millis()
+    );
+
+    // The assigned uuid is kept
+    let reopened = SledLogStore::from_db(db).unwrap().get(1).unwrap();
+    assert_eq!(reopened.uuid, log.uuid);
+}
@@ -0,0 +1,50 @@
+use sdr_db::SignalMode;
+use sdr_db::error::ValidationError;
+
+#[test]
+fn test_mode_parses_common_spellings() {
+    assert_eq!("fm".parse(), Ok(SignalMode::FM));
+    assert_eq!("D-STAR".parse(), Ok(SignalMode::DStar));
+    assert_eq!("dstar".parse(), Ok(SignalMode::DStar));
+    assert_eq!("ads-b".parse(), Ok(SignalMode::ADSB));
+    assert_eq!("ADSB".parse(), Ok(SignalMode::ADSB));
+    assert_eq!(" ft8 ".parse(), Ok(SignalMode::FT8));
+    assert_eq!("Digital".parse(), Ok(SignalMode::Digital));
+}
+
+#[test]
+fn test_unknown_mode_becomes_other() {
+    assert_eq!("pocsag".parse(), Ok(SignalMode::Other("POCSAG".to_string())));
+    assert!(matches!(
+        "".parse::<SignalMode>(),
+        Err(ValidationError::InvalidMode(_))
+    ));
+    assert!(matches!(
+        "a mode name that is far too long".parse::<SignalMode>(),
+        Err(ValidationError::InvalidMode(_))
+    ));
+}
+
+#[test]
+fn test_mode_display_round_trips() {
+    for mode in SignalMode::ALL {
+        assert_eq!(mode.to_string().parse::<SignalMode>().as_ref(), Ok(mode));
+    }
+    let other = SignalMode::Other("POCSAG".to_string());
+    assert_eq!(other.to_string().parse(), Ok(other));
+}
+
+#[test]
+fn test_mode_cycling_visits_every_mode() {
+    let mut mode = SignalMode::AM;
+    let mut seen = Vec::new();
+    for _ in 0..SignalMode::ALL.len() {
+        seen.push(mode.clone());
+        mode = mode.next();
+    }
+    assert_eq!(mode, SignalMode::AM);
+    assert_eq!(seen, SignalMode::ALL);
+
+    assert_eq!(SignalMode::AM.previous(), SignalMode::Digital);
+    assert_eq!(SignalMode::Other("X".to_string()).next(), SignalMode::AM);
+}
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use diesel::PgConnection;
+use diesel::r2d2::ConnectionManager;
+use sdr_db::db::{LogStore, PgPool, QueuedLogStore, pool};
+use sdr_db::model::LogUuid;
+use sdr_db::{DatabaseError, NewLog, ValidationError};
+
+use super::common::temporary_queue;
+
+// Nothing listens on port 1, so connecting fails straight away
+const UNREACHABLE_URL: &str = "postgres://127.0.0.1:1/sdr_db";
+
+fn new_log(callsign: &str) -> NewLog<'_> {
+    NewLog {
+        callsign,
+        snr_db: Some(12.5),
+        ..super::common::new_log(0)
+    }
+}
+
+fn unreachable_pool() -> PgPool {
+    pool::pool_builder()
+        .connection_timeout(Duration::from_millis(200))
+        .build_unchecked(ConnectionManager::<PgConnection>::new(UNREACHABLE_URL))
+}
+
+#[test]
+fn test_log_uuid_v7_layout() {
+    let uuid = LogUuid::from_parts(0x0192_f0a1_b2c3, [0xff; 10]);
+    assert_eq!(uuid.version(), 7);
+    assert_eq!(uuid.timestamp_ms(), 0x0192_f0a1_b2c3);
+    assert_eq!(uuid.to_string(), "0192f0a1-b2c3-7fff-bfff-ffffffffffff");
+
+    let generated = LogUuid::new_v7();
+    assert_eq!(generated.version(), 7);
+    assert_ne!(generated, LogUuid::new_v7());
+}
+
+#[test]
+fn test_log_uuid_parse() {
+    let uuid = LogUuid::new_v7();
+    assert_eq!(uuid.to_string().parse::<LogUuid>().unwrap(), uuid);
+    assert_eq!(
+        "0192F0A1B2C37FFFBFFFFFFFFFFFFFFF"
+            .parse::<LogUuid>()
+            .unwrap(),
+        LogUuid::from_parts(0x0192_f0a1_b2c3, [0xff; 10])
+    );
+    assert_eq!(
+        "not-a-uuid".parse::<LogUuid>().unwrap_err(),
+        ValidationError::InvalidUuid("not-a-uuid".to_string())
+    );
+}
+
+#[test]
+fn test_log_uuid_parse_rejects_non_hex_digits() {
+    // A sign inside a byte pair is not a hex digit, though from_str_radix accepts it
+    for text in [
+        "0192f0a1-b2c3-7fff-bfff-ffffffffff+f",
+        "+192f0a1b2c37fffbfffffffffffffff",
+        "0192f0a1-b2c3-7fff-bfff-ffffffffffgf",
+        "0192f0a1 b2c3 7fff bfff ffffffffffff",
+    ] {
+        assert_eq!(
+            text.parse::<LogUuid>().unwrap_err(),
+            ValidationError::InvalidUuid(text.to_string())
+        );
+    }
+}
+
+#[test]
+fn test_new_log_gets_uuid() {
+    let first = new_log("W1AW");
+    let second = new_log("W1AW");
+    assert_eq!(first.uuid.version(), 7);
+    assert_ne!(first.uuid, second.uuid);
+}
+
+#[test]
+fn test_enqueue_keeps_entry_and_uuid() {
+    let queue = temporary_queue();
+    let entry = new_log("G4ABC");
+    let uuid = queue.enqueue(&entry).unwrap();
+
+    assert_eq!(uuid, entry.uuid);
+    assert_eq!(queue.len(), 1);
+    let pending = queue.pending().unwrap();
+    let queued = pending[0].as_new_log();
+    assert_eq!(queued.callsign, "G4ABC");
+    assert_eq!(queued.snr_db, Some(12.5));
+    assert_eq!(queued.location, entry.location);
+    assert_eq!(queued.uuid, uuid);
+}
+
+#[test]
+fn test_enqueue_same_entry_twice_is_one_entry() {
+    let queue = temporary_queue();
+    let entry = new_log("G4ABC");
+    queue.enqueue(&entry).unwrap();
+    queue.enqueue(&entry).unwrap();
+    assert_eq!(queue.len(), 1);
+}
+
+#[test]
+fn test_enqueue_rejects_invalid_entry() {
+    let queue = temporary_queue();
+    let mut entry = new_log("G4ABC");
+    entry.recording_duration = -1.0;
+    assert_eq!(
+        queue.enqueue(&entry).unwrap_err(),
+        DatabaseError::InvalidData(ValidationError::InvalidRecordingDuration(-1.0))
+    );
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn test_queued_store_captures_while_offline() {
+    let queue = temporary_queue();
+    let mut store =
+        QueuedLogStore::with_queue(unreachable_pool(), queue.clone(), Duration::from_secs(60));
+
+    let created = store.create(&new_log("M0XYZ")).unwrap();
+    assert_eq!(created.id, 0);
+    assert_eq!(created.uuid.version(), 7);
+    assert_eq!(queue.len(), 1);
+
+    assert!(matches!(
+        store.get(1).unwrap_err(),
+        DatabaseError::ConnectionError(_)
+    ));
+    // Queued entries survive a failed read
+    assert_eq!(store.sync_status().unwrap().pending, 1);
+}
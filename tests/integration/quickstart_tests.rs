@@ -86,7 +86,7 @@ fn test_scenario_4_temporal_aggregation() {
         create_measurement_at_time_power(location, base_time + Duration::minutes(10), -70.0, 15.0),
     ];
 
-    let aggregates = aggregate_by_location(&measurements, 0.0001);
+    let aggregates = aggregate_by_location(&measurements, 10.0);
 
     assert_eq!(aggregates.len(), 1);
     assert_eq!(aggregates[0].measurement_count, 3);
@@ -0,0 +1,47 @@
+use sdr_db::Coordinate;
+use sdr_db::db::repair::{CoordIssue, ReferencePosition, classify_coordinates};
+
+#[test]
+fn test_valid_row_with_location_is_not_flagged() {
+    assert_eq!(classify_coordinates(37.7749, -122.4194, true, None), None);
+}
+
+#[test]
+fn test_row_only_valid_when_swapped() {
+    let (issue, fix) = classify_coordinates(-122.4194, 37.7749, false, None).unwrap();
+    assert_eq!(issue, CoordIssue::Swapped);
+    let fix = fix.unwrap();
+    assert!((fix.lat() - 37.7749).abs() < 1e-4);
+    assert!((fix.lon() - -122.4194).abs() < 1e-4);
+}
+
+#[test]
+fn test_row_invalid_in_both_orders_has_no_fix() {
+    assert_eq!(
+        classify_coordinates(120.0, 200.0, false, None),
+        Some((CoordIssue::OutOfRange, None))
+    );
+}
+
+#[test]
+fn test_reference_position_detects_ambiguous_swap() {
+    // (10, 50) is valid either way round; only the reference tells us it is swapped
+    let reference = ReferencePosition {
+        position: Coordinate::new(50.0, 10.0).unwrap(),
+        max_distance_m: 10_000.0,
+    };
+    let (issue, fix) = classify_coordinates(10.0, 50.0, true, Some(&reference)).unwrap();
+    assert_eq!(issue, CoordIssue::Swapped);
+    assert_eq!(fix, Some(Coordinate::new(50.0, 10.0).unwrap()));
+
+    let (issue, fix) = classify_coordinates(-33.0, 151.0, true, Some(&reference)).unwrap();
+    assert!(matches!(issue, CoordIssue::FarFromReference { .. }));
+    assert_eq!(fix, None);
+}
+
+#[test]
+fn test_missing_location_is_backfilled() {
+    let (issue, fix) = classify_coordinates(51.5, -0.125, false, None).unwrap();
+    assert_eq!(issue, CoordIssue::MissingLocation);
+    assert_eq!(fix, Some(Coordinate::new(51.5, -0.125).unwrap()));
+}
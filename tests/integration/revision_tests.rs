@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use sdr_db::db::{LogStore, MemoryLogStore};
+use sdr_db::model::revision::{
+    MAX_OPERATOR_LEN, default_operator, log_snapshot, validate_operator,
+};
+use sdr_db::{LogChanges, LogRevision, NewLog, RevisionAction, SignalMode, ValidationError};
+
+use super::common::utc_at;
+
+fn changed_at(hour: u32) -> DateTime<Utc> {
+    utc_at(2025, 11, 5, hour, 15, 0)
+}
+
+fn create(store: &mut MemoryLogStore, callsign: &str, minutes: i64) -> sdr_db::Log {
+    let new_log = NewLog {
+        callsign,
+        mode: SignalMode::USB,
+        comment: "beacon",
+        snr_db: Some(8.0),
+        heading_deg: Some(270.0),
+        ..super::common::new_log(minutes)
+    };
+    store.create(&new_log).unwrap()
+}
+
+#[test]
+fn test_changes_from_log_restore_every_field() {
+    let mut store = MemoryLogStore::new();
+    let original = create(&mut store, "IK2AAA", 0);
+    let edited = create(&mut store, "IK2BBB", 480);
+
+    let changes = LogChanges::try_from(&original).unwrap();
+    let restored = store.update(edited.id, &changes).unwrap();
+    assert_eq!(restored.id, edited.id);
+    assert_eq!(restored.callsign, original.callsign);
+    assert_eq!(restored.timestamp, original.timestamp);
+    assert_eq!(restored.details(), original.details());
+    assert_eq!(
+        restored.coordinate().unwrap(),
+        original.coordinate().unwrap()
+    );
+}
+
+#[test]
+fn test_revision_snapshots_round_trip() {
+    let mut store = MemoryLogStore::new();
+    let before = create(&mut store, "IK2AAA", 0);
+    let changes = LogChanges {
+        comment: Some("beacon, antenna swapped".to_string()),
+        ..Default::default()
+    };
+    let after = store.update(before.id, &changes).unwrap();
+
+    let revision = LogRevision {
+        id: 1,
+        log_id: before.id,
+        action: RevisionAction::Update,
+        old_values: log_snapshot(&before).unwrap(),
+        new_values: Some(log_snapshot(&after).unwrap()),
+        operator: "field-team-2".to_string(),
+        changed_at: changed_at(18),
+    };
+    let old = revision.old_log().unwrap();
+    assert_eq!(old.comment.as_deref(), Some("beacon"));
+    assert_eq!(old.uuid, before.uuid);
+    assert_eq!(old.snr_db, Some(8.0));
+    let new = revision.new_log().unwrap().unwrap();
+    assert_eq!(new.comment.as_deref(), Some("beacon, antenna swapped"));
+
+    let delete = LogRevision {
+        action: RevisionAction::Delete,
+        new_values: None,
+        ..revision
+    };
+    assert!(delete.new_log().unwrap().is_none());
+}
+
+#[test]
+fn test_operator_validation() {
+    assert!(validate_operator("field-team-2").is_ok());
+    assert_eq!(
+        validate_operator("  "),
+        Err(ValidationError::InvalidOperator("  ".to_string()))
+    );
+    let long = "x".repeat(MAX_OPERATOR_LEN + 1);
+    assert!(validate_operator(&long).is_err());
+    assert!(validate_operator(&default_operator()).is_ok());
+}
@@ -0,0 +1,140 @@
+use sdr_db::db::search::search_terms;
+use sdr_db::db::{LogStore, MemoryLogStore, SledLogStore};
+use sdr_db::tabs::view_logs::ViewLogsState;
+use sdr_db::{LogChanges, LogFilter, NewLog, SearchHit};
+
+use super::common::temporary_sled_store;
+
+fn new_log(minute: i64, callsign: &'static str, comment: &'static str) -> NewLog<'static> {
+    NewLog {
+        callsign,
+        comment,
+        ..super::common::new_log(minute)
+    }
+}
+
+fn hit_ids(hits: &[SearchHit]) -> Vec<i32> {
+    hits.iter().map(|hit| hit.log.id).collect()
+}
+
+fn exercise_search(store: &mut dyn LogStore) {
+    let pager = store
+        .create(&new_log(0, "KPAGER", "pager bursts every minute"))
+        .unwrap();
+    let comment_only = store
+        .create(&new_log(1, "W0UTE", "heard kpager faintly under the noise"))
+        .unwrap();
+    let unrelated = store
+        .create(&new_log(2, "N0AX", "weather net check-in"))
+        .unwrap();
+
+    // A callsign match outranks a comment match
+    let hits = store.search("kpager", 10).unwrap();
+    assert_eq!(hit_ids(&hits), vec![pager.id, comment_only.id]);
+    assert!(hits[0].rank > hits[1].rank);
+
+    // Partly typed words match as prefixes and every word must match
+    assert_eq!(
+        hit_ids(&store.search("pag bur", 10).unwrap()),
+        vec![pager.id]
+    );
+    assert_eq!(
+        hit_ids(&store.search("WEATHER, net!", 10).unwrap()),
+        vec![unrelated.id]
+    );
+    assert!(store.search("pager weather", 10).unwrap().is_empty());
+    assert!(store.search("  -- ", 10).unwrap().is_empty());
+    assert_eq!(store.search("kpager", 1).unwrap().len(), 1);
+
+    // Edits and deletes are searchable straight away
+    let changes = LogChanges {
+        comment: Some("repeater ident".to_string()),
+        ..Default::default()
+    };
+    store.update(comment_only.id, &changes).unwrap();
+    assert_eq!(
+        hit_ids(&store.search("kpager", 10).unwrap()),
+        vec![pager.id]
+    );
+    assert_eq!(
+        hit_ids(&store.search("ident", 10).unwrap()),
+        vec![comment_only.id]
+    );
+
+    store.delete(pager.id).unwrap();
+    assert!(store.search("kpager", 10).unwrap().is_empty());
+}
+
+#[test]
+fn test_search_terms_split_on_punctuation() {
+    assert_eq!(
+        search_terms("  Pager-bursts, KPAGER/2 "),
+        vec!["pager", "bursts", "kpager", "2"]
+    );
+    assert!(search_terms("--").is_empty());
+}
+
+#[test]
+fn test_memory_store_search() {
+    exercise_search(&mut MemoryLogStore::new());
+}
+
+#[test]
+fn test_sled_store_search() {
+    exercise_search(&mut temporary_sled_store());
+}
+
+#[test]
+fn test_sled_search_index_is_rebuilt_on_open() {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    let created = SledLogStore::from_db(db.clone())
+        .unwrap()
+        .create(&new_log(0, "KPAGER", "pager bursts"))
+        .unwrap();
+    db.drop_tree("search_index").unwrap();
+
+    let mut reopened = SledLogStore::from_db(db).unwrap();
+    assert_eq!(
+        hit_ids(&reopened.search("burst", 10).unwrap()),
+        vec![created.id]
+    );
+}
+
+#[test]
+fn test_view_logs_search_prompt() {
+    let mut store = MemoryLogStore::new();
+    let pager = store.create(&new_log(0, "KPAGER", "pager bursts")).unwrap();
+    let mut state = ViewLogsState::new(Vec::new());
+
+    state.start_search();
+    assert!(state.search_editing);
+    assert_eq!(state.search_query(), None);
+    for c in "kpag".chars() {
+        state.push_search_char(c);
+    }
+    assert_eq!(state.search_query(), Some("kpag"));
+
+    // Results for a query that has since been edited are dropped
+    let hits = store.search("kpag", 10).unwrap();
+    state.pop_search_char();
+    assert!(!state.load_search_results("kpag", hits.clone()));
+    state.push_search_char('g');
+    assert!(state.load_search_results("kpag", hits));
+    assert_eq!(state.logs.len(), 1);
+    assert_eq!(state.logs[0].id, pager.id);
+    assert_eq!(state.next_cursor, None);
+
+    state.finish_search();
+    assert!(!state.search_editing);
+    assert_eq!(state.search.as_deref(), Some("kpag"));
+
+    state.clear_search();
+    assert_eq!(state.search, None);
+    assert_eq!(state.filter, LogFilter::default());
+
+    // A prompt closed without words is dropped
+    state.start_search();
+    state.push_search_char(' ');
+    state.finish_search();
+    assert_eq!(state.search, None);
+}
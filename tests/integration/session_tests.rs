@@ -0,0 +1,141 @@
+use chrono::{DateTime, Duration, Utc};
+use sdr_db::db::{LogStore, MemoryLogStore, QueuedLog};
+use sdr_db::{
+    Band, Frequency, LogChanges, LogFilter, NewLog, NewSession, Session, SessionSummary,
+    ValidationError,
+};
+
+use super::common::utc_at;
+
+fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+    utc_at(2025, 11, 7, hour, minute, 0)
+}
+
+fn session(ended_at: Option<DateTime<Utc>>) -> Session {
+    Session {
+        id: 7,
+        name: "Ridge survey".to_string(),
+        operator: "alice".to_string(),
+        started_at: at(9, 0),
+        ended_at,
+        notes: None,
+        equipment: Some("RTL-SDR v4, discone".to_string()),
+    }
+}
+
+fn new_log(mhz: f64, session_id: Option<i32>) -> NewLog<'static> {
+    NewLog {
+        frequency: Frequency::from_mhz(mhz),
+        ..super::common::new_log(0)
+    }
+    .in_session(session_id)
+}
+
+#[test]
+fn test_band_classification() {
+    assert_eq!(Frequency::from_khz(198.0).band(), Some(Band::LF));
+    assert_eq!(Frequency::from_mhz(7.074).band(), Some(Band::HF));
+    assert_eq!(Frequency::from_mhz(145.5).band(), Some(Band::VHF));
+    assert_eq!(Frequency::from_mhz(433.92).band(), Some(Band::UHF));
+    assert_eq!(Frequency::from_mhz(300.0).band(), Some(Band::UHF));
+    assert_eq!(Frequency::from_mhz(10_368.0).band(), Some(Band::SHF));
+    assert_eq!(Frequency::from_hz(1_000).band(), None);
+}
+
+#[test]
+fn test_summary_counts_logs_and_collects_sorted_bands() {
+    let logs = [
+        (Frequency::from_mhz(433.92), 46.0, 8.9),
+        (Frequency::from_mhz(7.1), 46.2, 8.7),
+        (Frequency::from_mhz(145.5), 45.9, 9.1),
+        (Frequency::from_mhz(144.8), 46.1, 9.0),
+    ];
+    let summary = SessionSummary::from_logs(session(Some(at(11, 30))), logs, at(12, 0));
+
+    assert_eq!(summary.log_count, 4);
+    assert_eq!(summary.bands, vec![Band::HF, Band::VHF, Band::UHF]);
+    assert_eq!(summary.duration, Duration::minutes(150));
+
+    let bbox = summary.bounding_box.unwrap();
+    assert!((bbox.min().lat() - 45.9).abs() < 1e-5);
+    assert!((bbox.min().lon() - 8.7).abs() < 1e-5);
+    assert!((bbox.max().lat() - 46.2).abs() < 1e-5);
+    assert!((bbox.max().lon() - 9.1).abs() < 1e-5);
+}
+
+#[test]
+fn test_summary_of_running_session_without_logs() {
+    let summary = SessionSummary::from_logs(session(None), [], at(9, 45));
+    assert!(summary.session.is_active());
+    assert_eq!(summary.log_count, 0);
+    assert_eq!(summary.duration, Duration::minutes(45));
+    assert_eq!(summary.bounding_box, None);
+    assert!(summary.bands.is_empty());
+    assert!(summary.to_string().contains("still running"));
+}
+
+#[test]
+fn test_new_session_validation() {
+    assert!(NewSession::new("Ridge survey", "alice", at(9, 0), None, None).is_ok());
+    assert!(matches!(
+        NewSession::new("  ", "alice", at(9, 0), None, None),
+        Err(ValidationError::InvalidSessionName(_))
+    ));
+    assert!(matches!(
+        NewSession::new(&"x".repeat(101), "alice", at(9, 0), None, None),
+        Err(ValidationError::InvalidSessionName(_))
+    ));
+    assert!(matches!(
+        NewSession::new("Ridge survey", "", at(9, 0), None, None),
+        Err(ValidationError::InvalidOperator(_))
+    ));
+    assert!(matches!(
+        NewSession::new(
+            "Ridge survey",
+            "alice",
+            at(9, 0),
+            None,
+            Some(&"y".repeat(201))
+        ),
+        Err(ValidationError::InvalidEquipment(_))
+    ));
+}
+
+#[test]
+fn test_logs_keep_their_session_and_filter_by_it() {
+    let mut store = MemoryLogStore::new();
+    let in_session = store.create(&new_log(145.5, Some(7))).unwrap();
+    let outside = store.create(&new_log(433.92, None)).unwrap();
+    assert_eq!(in_session.session_id, Some(7));
+    assert_eq!(outside.session_id, None);
+
+    let page = store.query(&LogFilter::new().session(7), None, 10).unwrap();
+    assert_eq!(page.logs.len(), 1);
+    assert_eq!(page.logs[0].id, in_session.id);
+
+    let detach = LogChanges {
+        session_id: Some(None),
+        ..Default::default()
+    };
+    let detached = store.update(in_session.id, &detach).unwrap();
+    assert_eq!(detached.session_id, None);
+    assert!(!LogFilter::new().session(7).matches(&detached));
+}
+
+#[test]
+fn test_queued_log_carries_session() {
+    let queued = QueuedLog::from(&new_log(145.5, Some(7)));
+    assert_eq!(queued.as_new_log().session_id, Some(7));
+
+    // Entries queued before sessions existed have no session_id key
+    let mut json = serde_json::to_value(&queued).unwrap();
+    json.as_object_mut().unwrap().remove("session_id");
+    let old: QueuedLog = serde_json::from_value(json).unwrap();
+    assert_eq!(old.session_id, None);
+}
+
+#[test]
+fn test_no_active_session_outside_postgres() {
+    let mut store = MemoryLogStore::new();
+    assert_eq!(store.active_session().unwrap(), None);
+}
@@ -0,0 +1,190 @@
+use chrono::Duration;
+use sdr_db::db::{LogStore, MemoryLogStore, SledLogStore};
+use sdr_db::{
+    Coordinate, DatabaseError, LogChanges, LogDetails, LogFilter, NewLog, SignalMode,
+    ValidationError,
+};
+
+use super::common::{base_time, temporary_sled_store};
+
+fn new_log(minutes: i64, mode: SignalMode) -> NewLog<'static> {
+    NewLog {
+        mode,
+        ..super::common::new_log(minutes)
+    }
+}
+
+fn exercise_crud(store: &mut dyn LogStore) {
+    let created = store.create(&new_log(0, SignalMode::FM)).unwrap();
+    assert_eq!(
+        store.get(created.id).unwrap().callsign.as_deref(),
+        Some("W1AW")
+    );
+    assert!(created.location.is_some());
+
+    let changes = LogChanges {
+        mode: Some(SignalMode::NFM),
+        details: Some(LogDetails {
+            power_dbm: Some(-90.0),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let updated = store.update(created.id, &changes).unwrap();
+    assert_eq!(updated.mode, SignalMode::NFM);
+    assert_eq!(store.get(created.id).unwrap().power_dbm, Some(-90.0));
+
+    let invalid = LogChanges {
+        recording_duration: Some(-1.0),
+        ..Default::default()
+    };
+    assert_eq!(
+        store.update(created.id, &invalid).unwrap_err(),
+        DatabaseError::InvalidData(ValidationError::InvalidRecordingDuration(-1.0))
+    );
+
+    store.delete(created.id).unwrap();
+    assert!(matches!(
+        store.get(created.id),
+        Err(DatabaseError::NotFound(_))
+    ));
+    assert!(matches!(
+        store.delete(created.id),
+        Err(DatabaseError::NotFound(_))
+    ));
+}
+
+fn exercise_paging(store: &mut dyn LogStore) {
+    for minutes in 0..5 {
+        let mode = if minutes % 2 == 0 {
+            SignalMode::FM
+        } else {
+            SignalMode::DMR
+        };
+        store.create(&new_log(minutes, mode)).unwrap();
+    }
+
+    let filter = LogFilter::new();
+    let first = store.query(&filter, None, 2).unwrap();
+    assert_eq!(first.logs.len(), 2);
+    assert_eq!(first.logs[0].timestamp, base_time() + Duration::minutes(4));
+
+    let second = store.query(&filter, first.next, 2).unwrap();
+    let third = store.query(&filter, second.next, 2).unwrap();
+    assert_eq!(third.logs.len(), 1);
+    assert_eq!(third.next, None);
+    assert_eq!(third.logs[0].timestamp, base_time());
+
+    let dmr = store
+        .query(&LogFilter::new().mode(SignalMode::DMR), None, 10)
+        .unwrap();
+    assert_eq!(dmr.logs.len(), 2);
+
+    let ids: Vec<i32> = store.stream_all().unwrap().map(|l| l.unwrap().id).collect();
+    assert_eq!(ids.len(), 5);
+    assert!(ids.is_sorted());
+}
+
+fn exercise_update_many(store: &mut dyn LogStore) {
+    let first = store.create(&new_log(0, SignalMode::FM)).unwrap();
+    let second = store.create(&new_log(1, SignalMode::FM)).unwrap();
+    let comment = |text: &str| LogChanges {
+        comment: Some(text.to_string()),
+        ..Default::default()
+    };
+
+    // A missing log fails the batch before anything is written
+    assert!(matches!(
+        store.update_many(&[(first.id, comment("moved")), (i32::MAX, comment("lost"))]),
+        Err(DatabaseError::NotFound(_))
+    ));
+    assert_eq!(store.get(first.id).unwrap().comment, first.comment);
+    assert!(store.search("moved", 10).unwrap().is_empty());
+
+    let updated = store
+        .update_many(&[
+            (first.id, comment("moved once")),
+            (second.id, comment("parked")),
+            (first.id, comment("moved twice")),
+        ])
+        .unwrap();
+    let comments: Vec<Option<&str>> = updated.iter().map(|log| log.comment.as_deref()).collect();
+    assert_eq!(
+        comments,
+        vec![Some("moved once"), Some("parked"), Some("moved twice")]
+    );
+    assert_eq!(
+        store.get(first.id).unwrap().comment.as_deref(),
+        Some("moved twice")
+    );
+    // Only the final version of a log changed twice is searchable
+    assert!(store.search("once", 10).unwrap().is_empty());
+    assert_eq!(store.search("twice", 10).unwrap()[0].log.id, first.id);
+}
+
+fn exercise_uuid_uniqueness(store: &mut dyn LogStore) {
+    let original = store.create(&new_log(0, SignalMode::FM)).unwrap();
+    let copy = NewLog {
+        uuid: original.uuid,
+        ..new_log(1, SignalMode::CW)
+    };
+    assert!(matches!(
+        store.create(&copy),
+        Err(DatabaseError::DuplicateEntry(_))
+    ));
+    let found = store.get_by_uuids(&[original.uuid]).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, original.id);
+    assert_eq!(found[0].mode, SignalMode::FM);
+
+    // A deleted log keeps its uuid, so it can't be created again
+    store.delete(original.id).unwrap();
+    assert!(store.get_by_uuids(&[original.uuid]).unwrap().is_empty());
+    assert!(matches!(
+        store.create(&copy),
+        Err(DatabaseError::DuplicateEntry(_))
+    ));
+}
+
+#[test]
+fn test_memory_store() {
+    exercise_crud(&mut MemoryLogStore::new());
+    exercise_paging(&mut MemoryLogStore::new());
+    exercise_update_many(&mut MemoryLogStore::new());
+    exercise_uuid_uniqueness(&mut MemoryLogStore::new());
+}
+
+#[test]
+fn test_sled_store() {
+    exercise_crud(&mut temporary_sled_store());
+    exercise_paging(&mut temporary_sled_store());
+    exercise_update_many(&mut temporary_sled_store());
+    exercise_uuid_uniqueness(&mut temporary_sled_store());
+}
+
+#[test]
+fn test_sled_store_persists_across_reopen() {
+    let dir = std::env::temp_dir().join(format!("sdr_db_store_test_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let id = {
+        let mut store = SledLogStore::open(&dir).unwrap();
+        store.create(&new_log(0, SignalMode::CW)).unwrap().id
+    };
+    let mut store = SledLogStore::open(&dir).unwrap();
+    let log = store.get(id).unwrap();
+    assert_eq!(log.mode, SignalMode::CW);
+    let position = Coordinate::try_from(log.location.unwrap()).unwrap();
+    assert_eq!((position.lat(), position.lon()), (40.7128, -74.0060));
+    drop(store);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_stores_reject_invalid_entries() {
+    let mut entry = new_log(0, SignalMode::FM);
+    entry.xcoord = 95.0;
+    assert_eq!(
+        MemoryLogStore::new().create(&entry).unwrap_err(),
+        DatabaseError::InvalidData(ValidationError::InvalidLatitude(95.0))
+    );
+}
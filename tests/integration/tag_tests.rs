@@ -0,0 +1,128 @@
+use sdr_db::db::merge::differing_fields;
+use sdr_db::db::{LogStore, MemoryLogStore, QueuedLog};
+use sdr_db::model::tag::{normalize_tag, parse_tags};
+use sdr_db::tabs::view_logs::ViewLogsState;
+use sdr_db::{DatabaseError, LogChanges, LogFilter, LogPage, NewLog, ValidationError};
+
+fn new_log(minute: i64, tags: &[&str]) -> NewLog<'static> {
+    super::common::new_log(minute).with_tags(tags).unwrap()
+}
+
+#[test]
+fn test_tags_are_normalized() {
+    assert_eq!(normalize_tag("  Needs-ID ").unwrap(), "needs-id");
+    assert_eq!(
+        parse_tags("weak, Repeater  weak,,interference").unwrap(),
+        vec!["interference", "repeater", "weak"]
+    );
+    assert_eq!(parse_tags("  ").unwrap(), Vec::<String>::new());
+
+    for invalid in ["", "two words", "a,b", &"x".repeat(51)] {
+        assert!(matches!(
+            normalize_tag(invalid),
+            Err(ValidationError::InvalidTag(_))
+        ));
+    }
+}
+
+#[test]
+fn test_new_log_keeps_sorted_unique_tags() {
+    let entry = new_log(0, &["Weak", "repeater", "weak"]);
+    assert_eq!(entry.tags, vec!["repeater", "weak"]);
+    assert_eq!(entry.to_log(1).tags, vec!["repeater", "weak"]);
+
+    let mut literal = new_log(0, &[]);
+    literal.tags = vec!["not valid".to_string()];
+    assert!(matches!(
+        literal.validate(),
+        Err(ValidationError::InvalidTag(_))
+    ));
+}
+
+#[test]
+fn test_filter_requires_every_tag() {
+    let mut store = MemoryLogStore::new();
+    let weak_repeater = store.create(&new_log(0, &["repeater", "weak"])).unwrap();
+    let repeater = store.create(&new_log(1, &["repeater"])).unwrap();
+    store.create(&new_log(2, &[])).unwrap();
+
+    let ids = |filter: LogFilter, store: &mut MemoryLogStore| -> Vec<i32> {
+        let page = store.query(&filter, None, 10).unwrap();
+        page.logs.iter().map(|log| log.id).collect()
+    };
+    assert_eq!(
+        ids(LogFilter::new().tag("Repeater"), &mut store),
+        vec![repeater.id, weak_repeater.id]
+    );
+    assert_eq!(
+        ids(LogFilter::new().tags(["repeater", "weak"]), &mut store),
+        vec![weak_repeater.id]
+    );
+    assert!(ids(LogFilter::new().tag("needs-id"), &mut store).is_empty());
+
+    let filter = LogFilter::new()
+        .tags(["repeater", "weak"])
+        .without_tag("weak");
+    assert_eq!(filter.required_tags(), ["repeater"]);
+}
+
+#[test]
+fn test_changes_replace_tags() {
+    let mut store = MemoryLogStore::new();
+    let log = store.create(&new_log(0, &["weak"])).unwrap();
+
+    let retag = LogChanges {
+        tags: Some(vec!["Interference".to_string(), "needs-id".to_string()]),
+        ..Default::default()
+    };
+    let updated = store.update(log.id, &retag).unwrap();
+    assert_eq!(updated.tags, vec!["interference", "needs-id"]);
+    assert_eq!(differing_fields(&log, &updated), vec!["tags"]);
+
+    let invalid = LogChanges {
+        tags: Some(vec!["bad tag".to_string()]),
+        ..Default::default()
+    };
+    assert!(matches!(
+        store.update(log.id, &invalid),
+        Err(DatabaseError::InvalidData(ValidationError::InvalidTag(_)))
+    ));
+
+    let restore = LogChanges::try_from(&log).unwrap();
+    assert_eq!(store.update(log.id, &restore).unwrap().tags, vec!["weak"]);
+}
+
+#[test]
+fn test_queued_log_carries_tags() {
+    let queued = QueuedLog::from(&new_log(0, &["repeater"]));
+    assert_eq!(queued.as_new_log().tags, vec!["repeater"]);
+
+    let mut json = serde_json::to_value(&queued).unwrap();
+    json.as_object_mut().unwrap().remove("tags");
+    let old: QueuedLog = serde_json::from_value(json).unwrap();
+    assert!(old.tags.is_empty());
+}
+
+#[test]
+fn test_view_logs_tag_chips_follow_selected_log() {
+    let mut state = ViewLogsState::default();
+    state.load_first_page(LogPage {
+        logs: vec![
+            new_log(0, &["repeater", "weak"]).to_log(2),
+            new_log(1, &[]).to_log(1),
+        ],
+        next: None,
+    });
+
+    assert!(state.add_tag_filter_from_selected());
+    assert!(state.add_tag_filter_from_selected());
+    assert_eq!(state.filter.required_tags(), ["repeater", "weak"]);
+    // Every tag of the selected log is already required
+    assert!(!state.add_tag_filter_from_selected());
+
+    assert!(state.remove_last_tag_filter());
+    assert_eq!(state.filter.required_tags(), ["repeater"]);
+    assert!(state.remove_last_tag_filter());
+    assert!(!state.remove_last_tag_filter());
+    assert!(state.filter.is_empty());
+}
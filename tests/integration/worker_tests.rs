@@ -0,0 +1,92 @@
+use std::time::{Duration, Instant};
+
+use chrono::TimeDelta;
+use sdr_db::db::{DbCommand, DbReply, DbWorker, MemoryLogStore, QueuedLog};
+use sdr_db::{DatabaseError, LogFilter, ValidationError};
+
+use super::common::base_time;
+
+fn entry(minute: i64) -> QueuedLog {
+    QueuedLog::from(&super::common::new_log(minute))
+}
+
+/// Wait for the next reply to a command, skipping status reports
+fn next_reply(worker: &mut DbWorker) -> DbReply {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        match worker.try_recv() {
+            Some(DbReply::SyncStatus(status)) => assert_eq!(status, None),
+            Some(reply) => return reply,
+            None => std::thread::sleep(Duration::from_millis(5)),
+        }
+    }
+    panic!("no reply from the database worker");
+}
+
+#[test]
+fn test_worker_creates_and_pages_logs() {
+    let mut worker = DbWorker::spawn(Box::new(MemoryLogStore::new())).unwrap();
+    for minute in 0..3 {
+        worker.send(DbCommand::CreateLog(entry(minute))).unwrap();
+        match next_reply(&mut worker) {
+            DbReply::LogCreated(Ok(log)) => {
+                assert_eq!(log.timestamp, base_time() + TimeDelta::minutes(minute))
+            }
+            other => panic!("unexpected reply {:?}", other),
+        }
+    }
+
+    worker
+        .send(DbCommand::QueryLogs {
+            filter: LogFilter::new(),
+            after: None,
+            limit: 2,
+        })
+        .unwrap();
+    let next = match next_reply(&mut worker) {
+        DbReply::LogsLoaded {
+            after: None,
+            page: Ok(page),
+        } => {
+            assert_eq!(page.logs.len(), 2);
+            assert_eq!(page.logs[0].timestamp, base_time() + TimeDelta::minutes(2));
+            page.next
+        }
+        other => panic!("unexpected reply {:?}", other),
+    };
+
+    worker
+        .send(DbCommand::QueryLogs {
+            filter: LogFilter::new(),
+            after: next,
+            limit: 2,
+        })
+        .unwrap();
+    match next_reply(&mut worker) {
+        DbReply::LogsLoaded {
+            after,
+            page: Ok(page),
+        } => {
+            assert_eq!(after, next);
+            assert_eq!(page.logs.len(), 1);
+            assert!(page.next.is_none());
+        }
+        other => panic!("unexpected reply {:?}", other),
+    }
+}
+
+#[test]
+fn test_worker_reports_store_errors() {
+    let mut worker = DbWorker::spawn(Box::new(MemoryLogStore::new())).unwrap();
+    let mut invalid = entry(0);
+    invalid.recording_duration = -2.0;
+    worker.send(DbCommand::CreateLog(invalid)).unwrap();
+
+    match next_reply(&mut worker) {
+        DbReply::LogCreated(Err(e)) => assert_eq!(
+            e,
+            DatabaseError::InvalidData(ValidationError::InvalidRecordingDuration(-2.0))
+        ),
+        other => panic!("unexpected reply {:?}", other),
+    }
+}